@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use libxivdat::dat_file::read_content;
+#[cfg(feature = "mmap")]
+use libxivdat::dat_file::read_content_mapped;
+#[cfg(feature = "mmap")]
+use libxivdat::xiv_macro::Macro;
+use libxivdat::xiv_macro::{as_macro_vec, read_macro_content};
+
+const MACRO_PATH: &str = "./resources/default_dats/MACRO.DAT";
+
+fn bench_macro_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("macro_read");
+    group.bench_function("read_macro_content", |b| {
+        b.iter(|| read_macro_content(black_box(MACRO_PATH)).unwrap())
+    });
+    group.bench_function("read_content+as_macro_vec", |b| {
+        b.iter(|| {
+            let content = read_content(black_box(MACRO_PATH)).unwrap();
+            as_macro_vec(&content).unwrap().len()
+        })
+    });
+    #[cfg(feature = "mmap")]
+    group.bench_function("read_content_mapped+as_macro_vec", |b| {
+        b.iter(|| {
+            let content = read_content_mapped(black_box(MACRO_PATH)).unwrap();
+            as_macro_vec(&content).unwrap().len()
+        })
+    });
+    #[cfg(feature = "mmap")]
+    group.bench_function("read_content_mapped+as_macro_vec+owned", |b| {
+        b.iter(|| {
+            let content = read_content_mapped(black_box(MACRO_PATH)).unwrap();
+            as_macro_vec(&content)
+                .unwrap()
+                .iter()
+                .map(Macro::from)
+                .collect::<Vec<Macro>>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_macro_read);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use libxivdat::dat_file::DATFile;
+use libxivdat::dat_type::apply_mask;
+use libxivdat::section::read_section;
+use std::fs::copy;
+use std::io::{Seek, SeekFrom, Write};
+use tempfile::tempdir;
+
+const MACRO_PATH: &str = "./resources/default_dats/MACRO.DAT";
+
+/// The byte-at-a-time masking loop used before [`apply_mask`].
+fn apply_mask_bytewise(bytes: &mut [u8], mask: u8) {
+    for byte in bytes.iter_mut() {
+        *byte ^= mask;
+    }
+}
+
+/// The per-call masking done by `DATFile::read()` before [`apply_mask`]: allocate, mask, and copy back.
+fn unmask_read_alloc(buf: &mut [u8], mask: u8) {
+    let mut internal_buf = vec![0u8; buf.len()];
+    internal_buf.copy_from_slice(buf);
+    apply_mask_bytewise(&mut internal_buf, mask);
+    buf.clone_from_slice(&internal_buf);
+}
+
+fn bench_apply_mask(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_mask");
+    for size in [16usize, 256, 4096, 65536].iter() {
+        let mut bytes = vec![0x42u8; *size];
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::new("bytewise", size), size, |b, _| {
+            b.iter(|| apply_mask_bytewise(black_box(&mut bytes), 0x73))
+        });
+        group.bench_with_input(BenchmarkId::new("word", size), size, |b, _| {
+            b.iter(|| apply_mask(black_box(&mut bytes), 0x73))
+        });
+    }
+    group.finish();
+
+    // Section-sized reads, where the per-call allocation dominates.
+    let mut group = c.benchmark_group("unmask_read");
+    for size in [3usize, 16, 64, 180].iter() {
+        let mut bytes = vec![0x42u8; *size];
+        group.bench_with_input(BenchmarkId::new("alloc_bytewise", size), size, |b, _| {
+            b.iter(|| unmask_read_alloc(black_box(&mut bytes), 0x73))
+        });
+        group.bench_with_input(BenchmarkId::new("in_place_word", size), size, |b, _| {
+            b.iter(|| apply_mask(black_box(&mut bytes), 0x73))
+        });
+    }
+    group.finish();
+}
+
+fn bench_read_sections(c: &mut Criterion) {
+    c.bench_function("read_section_all", |b| {
+        let mut dat_file = DATFile::open(MACRO_PATH).unwrap();
+        b.iter(|| {
+            dat_file.seek(SeekFrom::Start(0)).unwrap();
+            let mut count = 0;
+            while let Ok(section) = read_section(&mut dat_file) {
+                count += section.content.len();
+            }
+            count
+        })
+    });
+}
+
+fn bench_write(c: &mut Criterion) {
+    let tmp_dir = tempdir().unwrap();
+    let tmp_path = tmp_dir.path().join("MACRO.DAT");
+    copy(MACRO_PATH, &tmp_path).unwrap();
+    let mut dat_file = DATFile::open_options(&tmp_path, std::fs::OpenOptions::new().read(true).write(true)).unwrap();
+    let chunk = [b'a'; 64];
+    c.bench_function("write_small_chunks", |b| {
+        b.iter(|| {
+            dat_file.seek(SeekFrom::Start(0)).unwrap();
+            for _ in 0..64 {
+                dat_file.write_all(black_box(&chunk)).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_apply_mask, bench_read_sections, bench_write);
+criterion_main!(benches);
@@ -0,0 +1,260 @@
+//! Derive macros for [libxivdat](https://crates.io/crates/libxivdat).
+//!
+//! This crate should not be used directly. Enable the `derive` feature of `libxivdat` and use the
+//! re-exported `libxivdat::high_level::DatRecord` derive instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Lit, LitInt, Meta, NestedMeta, Type};
+
+/// Derives `DatRecord`, `AsBytes`, `Validate`, and `TryFrom<&[u8]>` for a struct representing a fixed-size
+/// binary record, such as a single resource in a block-based DAT file.
+///
+/// Fields are laid out in declaration order with no implicit alignment padding. Each field must either
+/// implement `DatRecord` (integers, floats, arrays of these, and other derived records) or be a
+/// `String` marked with `#[dat(string = N)]`.
+///
+/// # Attributes
+///
+/// | Attribute                | Target | Description                                                             |
+/// |--------------------------|--------|-------------------------------------------------------------------------|
+/// | `#[dat(string = N)]`     | Field  | A `String` stored as `N` bytes of null-padded UTF-8.                    |
+/// | `#[dat(pad = N)]`        | Field  | Skips `N` bytes before the field. Written as `0x00`.                    |
+/// | `#[dat(size = N)]`       | Struct | Pads the record to a total of `N` bytes. Written as `0x00`.             |
+#[proc_macro_derive(DatRecord, attributes(dat))]
+pub fn derive_dat_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Parsed `#[dat(...)]` options for a single field or struct.
+#[derive(Default)]
+struct DatOptions {
+    pad: Option<LitInt>,
+    size: Option<LitInt>,
+    string: Option<LitInt>,
+}
+
+/// Collects all `#[dat(...)]` attributes into a single set of options.
+fn parse_options(attrs: &[Attribute]) -> syn::Result<DatOptions> {
+    let mut options = DatOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("dat")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new(meta.span(), "expected `#[dat(...)]`")),
+        };
+        for nested in list.nested.iter() {
+            let name_value = match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+                _ => return Err(syn::Error::new(nested.span(), "expected `key = value`")),
+            };
+            let value = match &name_value.lit {
+                Lit::Int(value) => value.clone(),
+                lit => return Err(syn::Error::new(lit.span(), "expected an integer literal")),
+            };
+            value.base10_parse::<usize>()?;
+            let slot = if name_value.path.is_ident("pad") {
+                &mut options.pad
+            } else if name_value.path.is_ident("size") {
+                &mut options.size
+            } else if name_value.path.is_ident("string") {
+                &mut options.string
+            } else {
+                return Err(syn::Error::new(name_value.path.span(), "unknown `dat` attribute"));
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new(name_value.path.span(), "duplicate `dat` attribute"));
+            }
+            *slot = Some(value);
+        }
+    }
+    Ok(options)
+}
+
+/// Returns true if the type is a path ending in `String`.
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map_or(false, |seg| seg.ident == "String"),
+        _ => false,
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "`DatRecord` cannot be derived for generic types",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`DatRecord` can only be derived for structs",
+            ))
+        }
+    };
+    let struct_options = parse_options(&input.attrs)?;
+    if let Some(lit) = struct_options.pad.as_ref().or(struct_options.string.as_ref()) {
+        return Err(syn::Error::new(lit.span(), "only `size` is allowed on the struct"));
+    }
+
+    let lib = quote!(::libxivdat);
+    let record = quote!(#lib::high_level::DatRecord);
+    let error = quote!(#lib::dat_error::DATError);
+
+    // Running offset of the current field as a const expression.
+    let mut offset = quote!(0usize);
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    let mut validations = Vec::new();
+    let mut bindings = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let options = parse_options(&field.attrs)?;
+        if let Some(lit) = &options.size {
+            return Err(syn::Error::new(lit.span(), "`size` is only allowed on the struct"));
+        }
+        let binding = syn::Ident::new(&format!("__field{}", i), field.span());
+        let accessor = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        };
+        let field_name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        };
+
+        if let Some(pad) = &options.pad {
+            offset = quote!(#offset + #pad);
+            writes.push(quote!(buf.resize(buf.len() + #pad, 0);));
+        }
+
+        let ty = &field.ty;
+        if let Some(len) = &options.string {
+            if !is_string(ty) {
+                return Err(syn::Error::new(ty.span(), "`string` fields must be of type `String`"));
+            }
+            if len.base10_parse::<usize>()? == 0 {
+                return Err(syn::Error::new(len.span(), "`string` length must be greater than 0"));
+            }
+            let message = format!("String field `{}` must be shorter than {} bytes.", field_name, len);
+            reads.push(quote!(
+                let #binding = #lib::high_level::__derive::read_padded_string(&bytes[#offset..#offset + #len])?;
+            ));
+            writes.push(quote!(
+                #lib::high_level::__derive::write_padded_string(&self.#accessor, #len, buf)?;
+            ));
+            validations.push(quote!(
+                if self.#accessor.len() >= #len {
+                    return Some(#error::Overflow(#message.into()));
+                }
+            ));
+            offset = quote!(#offset + #len);
+        } else {
+            if is_string(ty) {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "`String` fields require a fixed length, ie `#[dat(string = N)]`",
+                ));
+            }
+            reads.push(quote!(
+                let #binding = <#ty as #record>::read_record(&bytes[#offset..])?;
+            ));
+            writes.push(quote!(
+                #record::write_record(&self.#accessor, buf)?;
+            ));
+            offset = quote!(#offset + <#ty as #record>::SIZE);
+        }
+        bindings.push((field.ident.clone(), binding));
+    }
+
+    let construct = match fields {
+        Fields::Named(_) => {
+            let pairs = bindings.iter().map(|(ident, binding)| quote!(#ident: #binding));
+            quote!(#name { #(#pairs),* })
+        }
+        Fields::Unnamed(_) => {
+            let values = bindings.iter().map(|(_, binding)| binding);
+            quote!(#name(#(#values),*))
+        }
+        Fields::Unit => quote!(#name),
+    };
+
+    let (size, size_check, tail) = match &struct_options.size {
+        Some(size) => (
+            quote!(#size),
+            // Fails to compile if the fields are larger than the declared size.
+            quote!(const _: usize = #size - (#offset);),
+            quote!(buf.resize(start + #size, 0);),
+        ),
+        None => (offset, quote!(), quote!()),
+    };
+    let start = if struct_options.size.is_some() {
+        quote!(let start = buf.len();)
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        #size_check
+
+        impl #record for #name {
+            const SIZE: usize = #size;
+
+            fn read_record(bytes: &[u8]) -> ::std::result::Result<Self, #error> {
+                if bytes.len() < <Self as #record>::SIZE {
+                    return Err(#error::Overflow("Data buffer is too small for record size.".into()));
+                }
+                #(#reads)*
+                Ok(#construct)
+            }
+
+            fn write_record(&self, buf: &mut ::std::vec::Vec<u8>) -> ::std::result::Result<(), #error> {
+                #start
+                #(#writes)*
+                #tail
+                Ok(())
+            }
+        }
+
+        impl #lib::high_level::AsBytes for #name {
+            fn as_bytes(&self) -> ::std::result::Result<::std::vec::Vec<u8>, #error> {
+                if let Some(err) = #lib::high_level::Validate::validate(self) {
+                    return Err(err);
+                }
+                let mut buf = ::std::vec::Vec::with_capacity(<Self as #record>::SIZE);
+                #record::write_record(self, &mut buf)?;
+                Ok(buf)
+            }
+        }
+
+        impl #lib::high_level::Validate for #name {
+            fn validate(&self) -> ::std::option::Option<#error> {
+                #(#validations)*
+                None
+            }
+        }
+
+        impl ::std::convert::TryFrom<&[u8]> for #name {
+            type Error = #error;
+            fn try_from(x: &[u8]) -> ::std::result::Result<Self, Self::Error> {
+                match <Self as #record>::SIZE.cmp(&x.len()) {
+                    ::std::cmp::Ordering::Greater => Err(#error::Overflow("Data buffer is too small for record size.".into())),
+                    ::std::cmp::Ordering::Less => Err(#error::Underflow("Data buffer is too large for record size.".into())),
+                    ::std::cmp::Ordering::Equal => <Self as #record>::read_record(x),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,949 @@
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::dat_error::DATError;
+use crate::dat_file::{self, DATFile};
+use crate::dat_type::DATType;
+use crate::section::{self, Section};
+#[cfg(feature = "macro")]
+use crate::xiv_macro::{self, Macro};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+use tokio::task::{spawn_blocking, JoinHandle};
+
+/// Maximum number of bytes moved by a single blocking read or write.
+const MAX_BUF_SIZE: usize = 2 * 1024 * 1024;
+
+/// Returns early with `Poll::Pending` if the expression is pending, otherwise unwraps the ready value.
+macro_rules! ready {
+    ($e:expr) => {
+        match $e {
+            Poll::Ready(val) => val,
+            Poll::Pending => return Poll::Pending,
+        }
+    };
+}
+
+/// An asynchronous reference to an open DAT file on the system. This is the async counterpart of
+/// [`DATFile`](crate::dat_file::DATFile), implementing [`AsyncRead`], [`AsyncSeek`], and [`AsyncWrite`]
+/// with the same semantics: reads and writes are performed only on the data contents of the file,
+/// XOR masks are applied automatically, and the content size header is kept up to date.
+///
+/// Like [`tokio::fs::File`], file operations are run on tokio's blocking thread pool, so this must
+/// be used from within a tokio runtime. Only one operation may be in flight at a time, and an operation
+/// still completes if its future is dropped.
+///
+/// Also like [`tokio::fs::File`], reads are buffered and writes are queued. Content read by a blocking read
+/// that does not fit the caller's buffer (including a read whose future was dropped) is kept and returned by
+/// following reads. A write reports its full length as soon as it is queued, and any error writing it is
+/// returned by the next operation. Call [`flush()`](tokio::io::AsyncWriteExt::flush) to wait for queued
+/// writes and observe their errors.
+///
+/// Enabled by feature `tokio`.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::async_dat_file::AsyncDATFile;
+/// use libxivdat::dat_type::DATType;
+/// use tokio::io::AsyncReadExt;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut dat_file = AsyncDATFile::open("./resources/TEST_XOR.DAT").await.unwrap();
+/// assert_eq!(dat_file.file_type(), DATType::Macro);
+///
+/// let mut content = Vec::new();
+/// dat_file.read_to_end(&mut content).await.unwrap();
+/// assert_eq!(&content, b"Macro!");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncDATFile {
+    /// Cached size in bytes of the readable content of the DAT file, including the trailing null byte.
+    content_size: u32,
+    /// Cached type of the file.
+    file_type: DATType,
+    /// Cached header ending byte of the file.
+    header_end_byte: u8,
+    /// Cached maximum allowed size of the content in bytes.
+    max_size: u32,
+    /// Content cursor position after the last completed operation, if known.
+    pos: Option<u64>,
+    /// The wrapped [`DATFile`], or the blocking task currently holding it.
+    state: State,
+}
+
+/// A [`DATFile`] and the buffer used to move data to and from the blocking thread pool.
+#[derive(Debug)]
+struct Inner {
+    /// Content read from or to be written to `file`. After a read, this holds the read content.
+    buf: Vec<u8>,
+    /// Index of the next unread byte in `buf` after a read. The cursor of `file` is at the end of `buf`.
+    buf_pos: usize,
+    file: DATFile,
+}
+
+/// The result of a completed blocking operation.
+#[derive(Debug)]
+enum Operation {
+    Flush(std::io::Result<()>),
+    Read(std::io::Result<usize>),
+    Seek(std::io::Result<u64>),
+    Write(std::io::Result<usize>),
+}
+
+/// A future that waits for the in-flight operation of an [`AsyncDATFile`], if any.
+struct PollIdle<'a>(&'a mut AsyncDATFile);
+
+impl Future for PollIdle<'_> {
+    type Output = std::io::Result<Option<Operation>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_idle(cx)
+    }
+}
+
+/// Whether the wrapped [`DATFile`] is available, or held by a blocking task.
+#[derive(Debug)]
+enum State {
+    Busy(JoinHandle<(Inner, Operation)>),
+    Idle(Option<Inner>),
+}
+
+impl From<DATFile> for AsyncDATFile {
+    fn from(file: DATFile) -> Self {
+        AsyncDATFile {
+            content_size: file.content_size(),
+            file_type: file.file_type(),
+            header_end_byte: file.header_end_byte(),
+            max_size: file.max_size(),
+            pos: None,
+            state: State::Idle(Some(Inner {
+                buf: Vec::new(),
+                buf_pos: 0,
+                file,
+            })),
+        }
+    }
+}
+
+impl AsyncRead for AsyncDATFile {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, dst: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let me = self.get_mut();
+        loop {
+            match ready!(me.poll_idle(cx))? {
+                // The content of a completed read, including one whose future was dropped, is kept in the
+                // buffer. Copy out only what fits, even if nothing was read at the end of the file.
+                Some(Operation::Read(res)) => {
+                    res?;
+                    me.inner_mut().copy_to(dst);
+                    return Poll::Ready(Ok(()));
+                }
+                Some(Operation::Write(Err(err))) => return Poll::Ready(Err(err)),
+                // Discard the result of any other operation whose future was dropped.
+                Some(_) => continue,
+                None if dst.remaining() == 0 => return Poll::Ready(Ok(())),
+                None if me.inner_mut().unread() > 0 => {
+                    me.inner_mut().copy_to(dst);
+                    return Poll::Ready(Ok(()));
+                }
+                None => {
+                    let len = dst.remaining().min(MAX_BUF_SIZE);
+                    me.spawn(move |inner| {
+                        inner.buf.resize(len, 0);
+                        Operation::Read(inner.file.read(&mut inner.buf))
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for AsyncDATFile {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let me = self.get_mut();
+        match me.state {
+            State::Busy(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Another file operation is pending. Call poll_complete() before start_seek().",
+            )),
+            State::Idle(_) => {
+                // The cursor of the file is past any unread content, so relative seeks start that far back.
+                let position = match (position, me.discard_unread()) {
+                    (SeekFrom::Current(offset), unread) => SeekFrom::Current(offset - unread as i64),
+                    (position, _) => position,
+                };
+                me.spawn(move |inner| Operation::Seek(inner.file.seek(position)));
+                Ok(())
+            }
+        }
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let me = self.get_mut();
+        loop {
+            match ready!(me.poll_idle(cx))? {
+                Some(Operation::Seek(res)) => return Poll::Ready(res),
+                Some(Operation::Write(Err(err))) => return Poll::Ready(Err(err)),
+                Some(_) => continue,
+                None => match me.pos {
+                    Some(pos) => return Poll::Ready(Ok(pos - me.inner_mut().unread() as u64)),
+                    // The position is unknown until an operation completes, so ask the file.
+                    None => {
+                        let unread = me.discard_unread();
+                        me.spawn(move |inner| Operation::Seek(inner.file.seek(SeekFrom::Current(-(unread as i64)))));
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl AsyncWrite for AsyncDATFile {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, src: &[u8]) -> Poll<std::io::Result<usize>> {
+        let me = self.get_mut();
+        loop {
+            match ready!(me.poll_idle(cx))? {
+                Some(Operation::Write(Err(err))) => return Poll::Ready(Err(err)),
+                // Discard the result of an operation whose future was dropped.
+                Some(_) => continue,
+                None if src.is_empty() => return Poll::Ready(Ok(0)),
+                None => {
+                    // The count is reported when the write is queued, so a write whose future was dropped
+                    // can never be reported as the count of a later call.
+                    let unread = me.discard_unread();
+                    let len = src.len().min(MAX_BUF_SIZE);
+                    me.inner_mut().buf.extend_from_slice(&src[..len]);
+                    me.spawn(move |inner| {
+                        let res = rewind(&mut inner.file, unread).and_then(|_| inner.file.write_all(&inner.buf));
+                        inner.buf.clear();
+                        Operation::Write(res.map(|_| len))
+                    });
+                    return Poll::Ready(Ok(len));
+                }
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let me = self.get_mut();
+        loop {
+            match ready!(me.poll_idle(cx))? {
+                Some(Operation::Flush(res)) => return Poll::Ready(res),
+                Some(Operation::Write(Err(err))) => return Poll::Ready(Err(err)),
+                Some(_) => continue,
+                None => me.spawn(|inner| Operation::Flush(inner.file.flush())),
+            }
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncDATFile {
+    /// Returns the size of the current content contained in the DAT file, as of the last completed operation.
+    /// DAT files store content as a null-terminated CString, so this size
+    /// is one byte larger than the actual content.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::async_dat_file::AsyncDATFile;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dat_file = AsyncDATFile::open("./resources/TEST.DAT").await.unwrap();
+    /// let content_size = dat_file.content_size();
+    /// # });
+    /// ```
+    pub fn content_size(&self) -> u32 {
+        self.content_size
+    }
+
+    /// Returns the file type of the DAT file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::async_dat_file::AsyncDATFile;
+    /// use libxivdat::dat_type::DATType;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dat_file = AsyncDATFile::open("./resources/TEST_XOR.DAT").await.unwrap();
+    /// assert_eq!(dat_file.file_type(), DATType::Macro);
+    /// # });
+    /// ```
+    pub fn file_type(&self) -> DATType {
+        self.file_type
+    }
+
+    /// Returns the terminating byte of the DAT file's header. The purpose of this byte is unknown,
+    /// but it is almost always 0xFF.
+    pub fn header_end_byte(&self) -> u8 {
+        self.header_end_byte
+    }
+
+    /// Waits for any in-flight operation to complete, then returns the wrapped [`DATFile`](crate::dat_file::DATFile).
+    /// This can be used to access the less common synchronous functionality of [`DATFile`] from a blocking task.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::FileIO`](crate::dat_error::DATError::FileIO) if the in-flight operation panicked
+    /// or was cancelled, or if a queued write failed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::async_dat_file::AsyncDATFile;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dat_file = AsyncDATFile::open("./resources/TEST.DAT").await.unwrap();
+    /// let dat_file = dat_file.into_dat_file().await.unwrap();
+    /// assert_eq!(dat_file.content_size(), 6);
+    /// # });
+    /// ```
+    pub async fn into_dat_file(mut self) -> Result<DATFile, DATError> {
+        if let Some(Operation::Write(Err(err))) = PollIdle(&mut self).await? {
+            return Err(err.into());
+        }
+        // Move the cursor of the file back to the first unread byte.
+        let unread = self.discard_unread();
+        if unread > 0 {
+            self.spawn(move |inner| Operation::Seek(inner.file.seek(SeekFrom::Current(-(unread as i64)))));
+            if let Some(Operation::Seek(Err(err))) = PollIdle(&mut self).await? {
+                return Err(err.into());
+            }
+        }
+        match self.state {
+            State::Idle(Some(inner)) => Ok(inner.file),
+            _ => unreachable!("An idle AsyncDATFile always holds its DATFile."),
+        }
+    }
+
+    /// Returns the maximum size allowed for the content block of the DAT file.
+    /// Content is stored as a null-terminated CString, so the actual maximum allowed content
+    /// is 1 byte less than `max_size`.
+    pub fn max_size(&self) -> u32 {
+        self.max_size
+    }
+
+    /// Opens a file in read-only mode. This is the async equivalent of
+    /// [`DATFile::open()`](crate::dat_file::DATFile::open).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`DATFile::open()`](crate::dat_file::DATFile::open). Additionally, a
+    /// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::async_dat_file::AsyncDATFile;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dat_file = AsyncDATFile::open("./resources/TEST.DAT").await.unwrap();
+    /// # });
+    /// ```
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self, DATError> {
+        let path = path.as_ref().to_owned();
+        let mut dat_file = AsyncDATFile::from(run_blocking(move || DATFile::open(path)).await?);
+        dat_file.pos = Some(0);
+        Ok(dat_file)
+    }
+
+    /// Opens a file using an [`OpenOptions`](std::fs::OpenOptions) builder. This is the async equivalent of
+    /// [`DATFile::open_options()`](crate::dat_file::DATFile::open_options).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`DATFile::open_options()`](crate::dat_file::DATFile::open_options). Additionally, a
+    /// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::async_dat_file::AsyncDATFile;
+    /// use std::fs::OpenOptions;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut open_opts = OpenOptions::new();
+    /// open_opts.read(true).write(true);
+    /// # let mut open_opts = OpenOptions::new();
+    /// # open_opts.read(true);
+    /// let dat_file = AsyncDATFile::open_options("./resources/TEST.DAT", &open_opts).await.unwrap();
+    /// # });
+    /// ```
+    pub async fn open_options<P: AsRef<Path>>(path: P, options: &OpenOptions) -> Result<Self, DATError> {
+        let path = path.as_ref().to_owned();
+        let mut options = options.clone();
+        let mut dat_file = AsyncDATFile::from(run_blocking(move || DATFile::open_options(path, &mut options)).await?);
+        dat_file.pos = Some(0);
+        Ok(dat_file)
+    }
+
+    /// Discards any unread content in the buffer, returning its length. The cursor of the file is still past
+    /// the discarded content, so the next operation must account for it.
+    fn discard_unread(&mut self) -> usize {
+        let inner = self.inner_mut();
+        let unread = inner.unread();
+        inner.buf.clear();
+        inner.buf_pos = 0;
+        self.pos = self.pos.map(|pos| pos - unread as u64);
+        unread
+    }
+
+    /// Returns the idle [`Inner`]. Panics if an operation is in flight.
+    fn inner_mut(&mut self) -> &mut Inner {
+        match &mut self.state {
+            State::Idle(Some(inner)) => inner,
+            _ => unreachable!("AsyncDATFile accessed while an operation was in flight."),
+        }
+    }
+
+    /// Waits for the in-flight operation, if any, returning its result and caching the updated file state.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<Option<Operation>>> {
+        let handle = match &mut self.state {
+            State::Busy(handle) => handle,
+            State::Idle(_) => return Poll::Ready(Ok(None)),
+        };
+        let (mut inner, op) = match ready!(Pin::new(handle).poll(cx)) {
+            Ok(completed) => completed,
+            Err(err) => return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::Other, err))),
+        };
+        // A completed read replaces the buffer. Other operations leave any unread content in place.
+        if let Operation::Read(res) = &op {
+            inner.buf.truncate(*res.as_ref().unwrap_or(&0));
+            inner.buf_pos = 0;
+        }
+        self.content_size = inner.file.content_size();
+        self.max_size = inner.file.max_size();
+        self.pos = match (&op, self.pos) {
+            (Operation::Read(Ok(count)), Some(pos)) | (Operation::Write(Ok(count)), Some(pos)) => {
+                Some(pos + *count as u64)
+            }
+            (Operation::Seek(Ok(pos)), _) => Some(*pos),
+            (Operation::Flush(_), pos) => pos,
+            _ => None,
+        };
+        self.state = State::Idle(Some(inner));
+        Poll::Ready(Ok(Some(op)))
+    }
+
+    /// Moves the idle [`Inner`] onto the blocking thread pool to run `op`.
+    fn spawn<F>(&mut self, op: F)
+    where
+        F: FnOnce(&mut Inner) -> Operation + Send + 'static,
+    {
+        let mut inner = match &mut self.state {
+            State::Idle(inner) => inner.take().expect("An idle AsyncDATFile always holds its DATFile."),
+            State::Busy(_) => unreachable!("AsyncDATFile operation started while another was in flight."),
+        };
+        self.state = State::Busy(spawn_blocking(move || {
+            let res = op(&mut inner);
+            (inner, res)
+        }));
+    }
+}
+
+impl Inner {
+    /// Copies as much unread content from the buffer as fits into `dst`.
+    fn copy_to(&mut self, dst: &mut ReadBuf<'_>) {
+        let len = self.unread().min(dst.remaining());
+        dst.put_slice(&self.buf[self.buf_pos..self.buf_pos + len]);
+        self.buf_pos += len;
+    }
+
+    /// Returns the number of unread bytes in the buffer.
+    fn unread(&self) -> usize {
+        self.buf.len() - self.buf_pos
+    }
+}
+
+/// Tries to read the content block of a DAT file without blocking the async runtime.
+/// This is the async equivalent of [`read_content()`](crate::dat_file::read_content).
+///
+/// Enabled by feature `tokio`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`read_content()`](crate::dat_file::read_content). Additionally, a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::async_dat_file::read_content;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let content = read_content("./resources/TEST.DAT").await.unwrap();
+/// assert_eq!(&content, b"Boop!");
+/// # });
+/// ```
+pub async fn read_content<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, DATError> {
+    let path = path.as_ref().to_owned();
+    run_blocking(move || dat_file::read_content(path)).await
+}
+
+/// Reads all [`Macros`](crate::xiv_macro::Macro) from a macro file without blocking the async runtime.
+/// This is the async equivalent of [`read_macro_content()`](crate::xiv_macro::read_macro_content).
+///
+/// Enabled by features `tokio` and `macro`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`read_macro_content()`](crate::xiv_macro::read_macro_content). Additionally, a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::async_dat_file::read_macro_content;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let macros = read_macro_content("./resources/TEST_MACRO.DAT").await.unwrap();
+/// assert_eq!(macros[1].title, "1");
+/// # });
+/// ```
+#[cfg(feature = "macro")]
+pub async fn read_macro_content<P: AsRef<Path>>(path: P) -> Result<Vec<Macro>, DATError> {
+    let path = path.as_ref().to_owned();
+    run_blocking(move || xiv_macro::read_macro_content(path)).await
+}
+
+/// Reads all [`Sections`](crate::section::Section) from a DAT file without blocking the async runtime.
+/// This is the async equivalent of [`read_section_content()`](crate::section::read_section_content).
+///
+/// Enabled by feature `tokio`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`read_section_content()`](crate::section::read_section_content). Additionally, a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::async_dat_file::read_section_content;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let sections = read_section_content("./resources/TEST_SECTION.DAT").await.unwrap();
+/// assert_eq!(sections[0].tag, "T");
+/// # });
+/// ```
+pub async fn read_section_content<P: AsRef<Path>>(path: P) -> Result<Vec<Section>, DATError> {
+    let path = path.as_ref().to_owned();
+    run_blocking(move || section::read_section_content(path)).await
+}
+
+/// Writes a content block to a DAT file without blocking the async runtime.
+/// This is the async equivalent of [`write_content()`](crate::dat_file::write_content).
+///
+/// Enabled by feature `tokio`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`write_content()`](crate::dat_file::write_content). Additionally, a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned if the blocking task panicked.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::async_dat_file::{read_content, write_content};
+/// # use libxivdat::dat_file::DATFile;
+/// # use libxivdat::dat_type::DATType;
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let temp_dir = tempdir().unwrap();
+/// # let path = temp_dir.path().join("TEST.DAT");
+/// # DATFile::create(&path, DATType::Macro).unwrap();
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// write_content(&path, b"Who's awesome? You're awesome!").await.unwrap();
+/// assert_eq!(read_content(&path).await.unwrap(), b"Who's awesome? You're awesome!");
+/// # });
+/// ```
+pub async fn write_content<P: AsRef<Path>>(path: P, buf: &[u8]) -> Result<usize, DATError> {
+    let path = path.as_ref().to_owned();
+    let buf = buf.to_vec();
+    run_blocking(move || dat_file::write_content(path, &buf)).await
+}
+
+/// Moves the cursor of a [`DATFile`] back by `count` bytes of discarded read content.
+fn rewind(file: &mut DATFile, count: usize) -> std::io::Result<()> {
+    if count > 0 {
+        file.seek(SeekFrom::Current(-(count as i64)))?;
+    }
+    Ok(())
+}
+
+/// Runs a blocking function on tokio's blocking thread pool, converting a failed task into a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO).
+async fn run_blocking<F, T>(f: F) -> Result<T, DATError>
+where
+    F: FnOnce() -> Result<T, DATError> + Send + 'static,
+    T: Send + 'static,
+{
+    match spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(err) => Err(DATError::from(std::io::Error::new(std::io::ErrorKind::Other, err))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempfile;
+    use tempfile::tempdir;
+
+    use super::*;
+    use std::fs::copy;
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    const TEST_PATH: &str = "./resources/TEST.DAT";
+    const TEST_XOR_PATH: &str = "./resources/TEST_XOR.DAT";
+    const TEST_EMPTY_PATH: &str = "./resources/TEST_EMPTY.DAT";
+    const TEST_SECTION_PATH: &str = "./resources/TEST_SECTION.DAT";
+    const TEST_LARGE_PATH: &str = "./resources/default_dats/MACRO.DAT";
+    #[cfg(feature = "macro")]
+    const TEST_MACRO_PATH: &str = "./resources/TEST_MACRO.DAT";
+    const TEST_CONTENTS: &[u8; 5] = b"Boop!";
+    const TEST_XOR_CONTENTS: &[u8; 6] = b"Macro!";
+
+    /// Polls a future once with a waker that does nothing, then drops it.
+    fn poll_once<F: Future>(fut: F) -> Poll<F::Output> {
+        fn noop_raw_waker() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                noop_raw_waker()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        // Safety: The vtable functions do nothing, so they uphold the RawWaker contract trivially.
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+        let mut fut = Box::pin(fut);
+        fut.as_mut().poll(&mut Context::from_waker(&waker))
+    }
+
+    // --- Module Functions
+
+    #[tokio::test]
+    async fn test_read_content() -> Result<(), String> {
+        match read_content(TEST_PATH).await {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, TEST_CONTENTS)),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_content_with_mask() -> Result<(), String> {
+        match read_content(TEST_XOR_PATH).await {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, TEST_XOR_CONTENTS)),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_content_error_path() -> Result<(), String> {
+        match read_content("./resources/DOES_NOT_EXIST.DAT").await {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::FileIO(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[cfg(feature = "macro")]
+    #[tokio::test]
+    async fn test_read_macro_content() -> Result<(), String> {
+        let expected = match xiv_macro::read_macro_content(TEST_MACRO_PATH) {
+            Ok(macros) => macros,
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match read_macro_content(TEST_MACRO_PATH).await {
+            Ok(macros) => Ok(assert_eq!(macros, expected)),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_section_content() -> Result<(), String> {
+        let expected = match section::read_section_content(TEST_SECTION_PATH) {
+            Ok(sections) => sections,
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match read_section_content(TEST_SECTION_PATH).await {
+            Ok(sections) => Ok(assert_eq!(sections, expected)),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_content() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST_XOR.DAT");
+        match copy(TEST_XOR_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        match write_content(&tmp_path, b"Boop!").await {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing content: {}", err)),
+        };
+        match dat_file::read_content(&tmp_path) {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, b"Boop!")),
+            Err(err) => Err(format!("Error reading file after write: {}", err)),
+        }
+    }
+
+    // --- AsyncDATFile
+
+    #[tokio::test]
+    async fn test_async_datfile_open() -> Result<(), String> {
+        match AsyncDATFile::open(TEST_XOR_PATH).await {
+            Ok(dat_file) => {
+                assert_eq!(dat_file.content_size(), TEST_XOR_CONTENTS.len() as u32 + 1);
+                assert_eq!(dat_file.file_type(), DATType::Macro);
+                assert_eq!(dat_file.header_end_byte(), 0xFF);
+                Ok(())
+            }
+            Err(err) => Err(format!("Open error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_read() -> Result<(), String> {
+        for (path, contents) in [(TEST_PATH, &TEST_CONTENTS[..]), (TEST_XOR_PATH, &TEST_XOR_CONTENTS[..])].iter() {
+            let mut dat_file = match AsyncDATFile::open(path).await {
+                Ok(dat_file) => dat_file,
+                Err(err) => return Err(format!("Open error: {}", err)),
+            };
+            let mut buf = Vec::new();
+            match dat_file.read_to_end(&mut buf).await {
+                Ok(count) => assert_eq!(count, contents.len()),
+                Err(err) => return Err(format!("Read error: {}", err)),
+            };
+            assert_eq!(&buf, contents);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_seek() -> Result<(), String> {
+        let mut dat_file = match AsyncDATFile::open(TEST_XOR_PATH).await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        match dat_file.seek(SeekFrom::Start(1)).await {
+            Ok(pos) => assert_eq!(pos, 1),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        };
+        match dat_file.seek(SeekFrom::Current(2)).await {
+            Ok(pos) => assert_eq!(pos, 3),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        };
+        let mut buf = [0u8; 2];
+        match dat_file.read_exact(&mut buf).await {
+            Ok(_) => assert_eq!(&buf, b"ro"),
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match dat_file.stream_position().await {
+            Ok(pos) => assert_eq!(pos, 5),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        };
+        match dat_file.seek(SeekFrom::End(-1)).await {
+            Ok(pos) => Ok(assert_eq!(pos, TEST_XOR_CONTENTS.len() as u64 - 1)),
+            Err(err) => Err(format!("Seek error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_async_datfile_read_after_cancel() -> Result<(), String> {
+        let contents = match dat_file::read_content(TEST_LARGE_PATH) {
+            Ok(contents) => contents,
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        // Use a single blocking thread, so that a blocked task can hold back the read below.
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .max_blocking_threads(1)
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(err) => return Err(format!("Error building runtime: {}", err)),
+        };
+        runtime.block_on(async {
+            let mut dat_file = match AsyncDATFile::open(TEST_LARGE_PATH).await {
+                Ok(dat_file) => dat_file,
+                Err(err) => return Err(format!("Open error: {}", err)),
+            };
+            let (unblock, blocked) = std::sync::mpsc::channel::<()>();
+            let blocker = spawn_blocking(move || blocked.recv());
+            // Start a large read, then drop its future while it is still in flight.
+            let mut large_buf = [0u8; 1000];
+            assert!(poll_once(dat_file.read(&mut large_buf)).is_pending());
+            match unblock.send(()) {
+                Ok(_) => (),
+                Err(err) => return Err(format!("Error unblocking: {}", err)),
+            };
+            match blocker.await {
+                Ok(_) => (),
+                Err(err) => return Err(format!("Error joining blocker: {}", err)),
+            };
+            // A smaller read must not overflow its buffer, and no content may be skipped or repeated.
+            let mut small_buf = [0u8; 4];
+            match dat_file.read_exact(&mut small_buf).await {
+                Ok(_) => assert_eq!(&small_buf, &contents[..4]),
+                Err(err) => return Err(format!("Read error: {}", err)),
+            };
+            match dat_file.stream_position().await {
+                Ok(pos) => assert_eq!(pos, 4),
+                Err(err) => return Err(format!("Seek error: {}", err)),
+            };
+            let mut rest = Vec::new();
+            match dat_file.read_to_end(&mut rest).await {
+                Ok(_) => Ok(assert_eq!(&rest, &contents[4..])),
+                Err(err) => Err(format!("Read error: {}", err)),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_write_after_partial_read() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_XOR_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let mut dat_file = match AsyncDATFile::open_options(&tmp_path, &opts).await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        // Reads more than requested into the buffer, so the write must start at the logical position.
+        let mut buf = [0u8; 2];
+        match dat_file.read_exact(&mut buf).await {
+            Ok(_) => assert_eq!(&buf, b"Ma"),
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match dat_file.write_all(b"CR").await.and(dat_file.flush().await) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing content: {}", err)),
+        };
+        match dat_file::read_content(&tmp_path) {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, b"MaCRo!")),
+            Err(err) => Err(format!("Error reading file after write: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_write_error_on_flush() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_XOR_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let mut dat_file = match AsyncDATFile::open_options(&tmp_path, &opts).await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        // The write is queued, so exceeding the max size is reported by the next operation.
+        let too_long = vec![b'X'; dat_file.max_size() as usize];
+        match dat_file.write_all(&too_long).await {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error queueing write: {}", err)),
+        };
+        match dat_file.flush().await {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(_) => Ok(()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_write_extend_content_size() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_EMPTY_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let mut dat_file = match AsyncDATFile::open_options(&tmp_path, &opts).await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        let new_content = b"Long!";
+        match dat_file.write_all(new_content).await {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing content: {}", err)),
+        };
+        match dat_file.flush().await {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error flushing content: {}", err)),
+        };
+        assert_eq!(dat_file.content_size(), new_content.len() as u32 + 1);
+        match dat_file::read_content(&tmp_path) {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, new_content)),
+            Err(err) => Err(format!("Error reading file after write: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_into_dat_file() -> Result<(), String> {
+        let mut dat_file = match AsyncDATFile::open(TEST_XOR_PATH).await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        match dat_file.seek(SeekFrom::Start(2)).await {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        };
+        let mut dat_file = match dat_file.into_dat_file().await {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Conversion error: {}", err)),
+        };
+        let mut buf = [0u8; 4];
+        match dat_file.read_exact(&mut buf) {
+            Ok(_) => Ok(assert_eq!(&buf, b"cro!")),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_datfile_from_dat_file() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_XOR_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        match dat_file.seek(SeekFrom::Start(4)) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        };
+        let mut dat_file = AsyncDATFile::from(dat_file);
+        match dat_file.stream_position().await {
+            Ok(pos) => Ok(assert_eq!(pos, 4)),
+            Err(err) => Err(format!("Seek error: {}", err)),
+        }
+    }
+}
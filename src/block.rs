@@ -0,0 +1,861 @@
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::dat_error::DATError;
+use crate::dat_file::{read_content, DATFile};
+use crate::dat_type::DATType;
+
+/// Array of [`DATTypes`](crate::dat_type::DATType) that have `Block`-based contents. [`DATType::Unknown`] is allowed,
+/// since its contents are not known.
+pub const BLOCK_BASED_TYPES: [DATType; 8] = [
+    DATType::Gearset,
+    DATType::GoldSaucer,
+    DATType::Hotbar,
+    DATType::ItemFinder,
+    DATType::ItemOrder,
+    DATType::LogFilter,
+    DATType::UISave,
+    DATType::Unknown,
+];
+
+/// A `BlockLayout` describes the fixed-size records contained in a block-based DAT file.
+/// A `Resource` (ie, a Gearset or Triad deck) is stored as a single record, with each record
+/// immediately following the last.
+///
+/// Records are not interpreted by this module; they are returned as raw byte slices.
+///
+/// # Block-using file types
+/// `GEARSET`, `GS`, `HOTBAR`, `ITEMFDR`, `ITEMODR`, `LOGFLTR`, and `UISAVE`. See [`BLOCK_BASED_TYPES`].
+///
+/// # Data Structure
+/// ```text
+/// 0          offset     offset + record_size
+/// |- ...     |- ...     |- ...
+/// |          |          \_ record 1
+/// |          \_ record 0
+/// \_ file-specific leading data (may be empty)
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockLayout {
+    /// Offset of the first record from the start of the content block in bytes.
+    pub offset: u32,
+    /// Number of records contained in the content block.
+    pub record_count: u32,
+    /// Size of a single record in bytes.
+    pub record_size: u32,
+}
+
+impl BlockLayout {
+    /// Builds a new [`BlockLayout`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::BlockLayout;
+    ///
+    /// let layout = BlockLayout::new(0, 58, 10);
+    /// assert_eq!(layout.offset, 0);
+    /// assert_eq!(layout.record_size, 58);
+    /// assert_eq!(layout.record_count, 10);
+    /// ```
+    pub const fn new(offset: u32, record_size: u32, record_count: u32) -> Self {
+        BlockLayout {
+            offset,
+            record_count,
+            record_size,
+        }
+    }
+
+    /// Returns the content offset of the record at a given index, or [`None`] if the index
+    /// is out of bounds or the offset would exceed [`u32::MAX`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::BlockLayout;
+    ///
+    /// let layout = BlockLayout::new(4, 444, 101);
+    /// assert_eq!(layout.record_offset(0), Some(4));
+    /// assert_eq!(layout.record_offset(2), Some(892));
+    /// assert_eq!(layout.record_offset(101), None);
+    /// ```
+    pub fn record_offset(&self, index: u32) -> Option<u32> {
+        if index >= self.record_count {
+            return None;
+        }
+        index.checked_mul(self.record_size)?.checked_add(self.offset)
+    }
+
+    /// Returns the minimum content length in bytes required to hold every record described by the layout,
+    /// or [`None`] if it would exceed [`u32::MAX`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::BlockLayout;
+    ///
+    /// let layout = BlockLayout::new(4, 444, 101);
+    /// assert_eq!(layout.size(), Some(44848));
+    /// ```
+    pub fn size(&self) -> Option<u32> {
+        self.record_count
+            .checked_mul(self.record_size)?
+            .checked_add(self.offset)
+    }
+}
+
+/// Gets the known [`BlockLayout`] of a block-based DAT file for a given type.
+/// Returns `None` if the file is not block-based or its record layout is not yet known.
+/// Custom layouts for other files can be built with [`BlockLayout::new()`].
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::get_block_layout_for_type;
+/// use libxivdat::dat_type::DATType;
+///
+/// let layout = get_block_layout_for_type(&DATType::GoldSaucer).unwrap();
+/// assert_eq!(layout.record_count, 10);
+/// ```
+pub fn get_block_layout_for_type(file_type: &DATType) -> Option<BlockLayout> {
+    match file_type {
+        DATType::Gearset => Some(BlockLayout::new(4, 444, 101)),
+        DATType::GoldSaucer => Some(BlockLayout::new(0, 58, 10)),
+        _ => None,
+    }
+}
+
+/// Interprets a byte slice as a single record of a block-based DAT file.
+///
+/// # Errors
+///
+/// Returns a [`DATError::InvalidInput`](crate::dat_error::DATError::InvalidInput) if the index is out of bounds for the layout.
+///
+/// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the slice is too short to
+/// contain the record.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::{as_block, get_block_layout_for_type};
+/// use libxivdat::dat_file::read_content;
+/// use libxivdat::dat_type::DATType;
+///
+/// let content_bytes = read_content("./resources/TEST_BLOCK.DAT").unwrap();
+/// let layout = get_block_layout_for_type(&DATType::GoldSaucer).unwrap();
+/// let record = as_block(&content_bytes, &layout, 1).unwrap();
+///
+/// assert_eq!(record.len(), 58);
+/// assert_eq!(&record[..6], b"Deck 2");
+/// ```
+pub fn as_block<'a>(bytes: &'a [u8], layout: &BlockLayout, index: u32) -> Result<&'a [u8], DATError> {
+    let start = match layout.record_offset(index) {
+        Some(start) => usize::try_from(start)?,
+        None => {
+            return Err(DATError::InvalidInput(
+                "Record index is out of bounds for the block layout.".into(),
+            ))
+        }
+    };
+    let end = start + usize::try_from(layout.record_size)?;
+    if end > bytes.len() {
+        return Err(DATError::Underflow(
+            "Data buffer is too small to contain the requested record.".into(),
+        ));
+    }
+    Ok(&bytes[start..end])
+}
+
+/// Interprets a byte slice as the content block of a block-based DAT file, returning a [`Vec`]
+/// of record slices. No record data is copied.
+///
+/// # Errors
+///
+/// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the slice is too short to
+/// contain every record described by the layout.
+///
+/// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) if the layout describes a block larger
+/// than the maximum possible content size.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::{as_block_vec, get_block_layout_for_type};
+/// use libxivdat::dat_file::read_content;
+/// use libxivdat::dat_type::DATType;
+///
+/// let content_bytes = read_content("./resources/TEST_BLOCK.DAT").unwrap();
+/// let layout = get_block_layout_for_type(&DATType::GoldSaucer).unwrap();
+/// let records = as_block_vec(&content_bytes, &layout).unwrap();
+///
+/// assert_eq!(records.len(), 10);
+/// assert_eq!(&records[0][..6], b"Deck 1");
+/// assert_eq!(&records[9][..7], b"Deck 10");
+/// ```
+pub fn as_block_vec<'a>(bytes: &'a [u8], layout: &BlockLayout) -> Result<Vec<&'a [u8]>, DATError> {
+    let block_size = match layout.size() {
+        Some(block_size) => usize::try_from(block_size)?,
+        None => {
+            return Err(DATError::Overflow(
+                "Block layout exceeds maximum possible size (u32::MAX).".into(),
+            ))
+        }
+    };
+    if block_size > bytes.len() {
+        return Err(DATError::Underflow(
+            "Data buffer is too small for the block layout.".into(),
+        ));
+    }
+    let start = usize::try_from(layout.offset)?;
+    let record_size = usize::try_from(layout.record_size)?;
+    if record_size == 0 {
+        return Ok(vec![&bytes[start..start]; usize::try_from(layout.record_count)?]);
+    }
+    Ok(bytes[start..block_size].chunks_exact(record_size).collect())
+}
+
+/// Reads a single record from a [`DATFile`](crate::dat_file::DATFile) at a given index, returning its bytes.
+/// Only the requested record is read from disk. The file cursor is left at the end of the record.
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain blocks.
+///
+/// Returns a [`DATError::InvalidInput`] if the index is out of bounds for the layout.
+///
+/// Returns [`DATError::EndOfFile`] if the record extends past the end of the file content.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`]
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::{get_block_layout_for_type, read_block};
+/// use libxivdat::dat_file::DATFile;
+///
+/// let mut dat_file = DATFile::open("./resources/TEST_BLOCK.DAT").unwrap();
+/// let layout = get_block_layout_for_type(&dat_file.file_type()).unwrap();
+/// let record = read_block(&mut dat_file, &layout, 2).unwrap();
+///
+/// assert_eq!(&record[..6], b"Deck 3");
+/// ```
+pub fn read_block(dat_file: &mut DATFile, layout: &BlockLayout, index: u32) -> Result<Vec<u8>, DATError> {
+    if !BLOCK_BASED_TYPES.contains(&dat_file.file_type()) {
+        return Err(DATError::IncorrectType(
+            "Target file is of a type that should not contain blocks.".into(),
+        ));
+    }
+    let start = match layout.record_offset(index) {
+        Some(start) => start,
+        None => {
+            return Err(DATError::InvalidInput(
+                "Record index is out of bounds for the block layout.".into(),
+            ))
+        }
+    };
+    dat_file.seek(SeekFrom::Start(u64::from(start)))?;
+    let mut record = vec![0u8; usize::try_from(layout.record_size)?];
+    // Manually wrap EOF into DATError EOF
+    match dat_file.read_exact(&mut record) {
+        Ok(_) => Ok(record),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            Err(DATError::EndOfFile("Found EOF reading requested record.".into()))
+        }
+        Err(err) => Err(DATError::from(err)),
+    }
+}
+
+/// Reads the content block of a block-based DAT file into memory, returning it as a [`BlockContent`].
+/// This performs only one read operation on the underlying file. Records can then be accessed without
+/// further copies using [`BlockContent::get()`] or [`BlockContent::as_block_vec()`].
+///
+/// The layout is determined by [`get_block_layout_for_type()`].
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain blocks, or if no layout is known for the type. Use
+/// [`read_block_content_with_layout()`] to provide a custom layout.
+///
+/// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the content is too short
+/// for the layout.
+///
+/// Returns a [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) if the specified file does not
+/// have a valid DAT header.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::read_block_content;
+///
+/// let content = read_block_content("./resources/TEST_BLOCK.DAT").unwrap();
+/// let records = content.as_block_vec().unwrap();
+///
+/// assert_eq!(records.len(), 10);
+/// assert_eq!(&records[0][..6], b"Deck 1");
+/// ```
+pub fn read_block_content<P: AsRef<Path>>(path: P) -> Result<BlockContent, DATError> {
+    let dat_file = DATFile::open(&path)?;
+    let file_type = dat_file.file_type();
+    drop(dat_file);
+    if !BLOCK_BASED_TYPES.contains(&file_type) {
+        return Err(DATError::IncorrectType(
+            "Target file is of a type that should not contain blocks.".into(),
+        ));
+    }
+    match get_block_layout_for_type(&file_type) {
+        Some(layout) => read_block_content_with_layout(path, layout),
+        None => Err(DATError::IncorrectType(
+            "No block layout is known for the target file type.".into(),
+        )),
+    }
+}
+
+/// Reads the content block of a DAT file into memory using a specified [`BlockLayout`], returning it as a
+/// [`BlockContent`]. This does not check that the file is of a type that should contain blocks.
+///
+/// # Errors
+///
+/// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the content is too short
+/// for the layout.
+///
+/// Returns a [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) if the specified file does not
+/// have a valid DAT header.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::{read_block_content_with_layout, BlockLayout};
+///
+/// let content = read_block_content_with_layout("./resources/TEST_BLOCK.DAT", BlockLayout::new(0, 58, 2)).unwrap();
+/// assert_eq!(content.len(), 2);
+/// assert_eq!(&content.get(1).unwrap()[..6], b"Deck 2");
+/// ```
+pub fn read_block_content_with_layout<P: AsRef<Path>>(path: P, layout: BlockLayout) -> Result<BlockContent, DATError> {
+    let bytes = read_content(&path)?;
+    // Validate the layout against the content before handing it out.
+    as_block_vec(&bytes, &layout).map_err(|err| err.with_path(&path))?;
+    Ok(BlockContent { bytes, layout })
+}
+
+/// Overwrites a single record of a [`DATFile`](crate::dat_file::DATFile) in-place.
+/// Because records are fixed-size, no other content is moved and the content size of the file is unchanged.
+/// The file must be opened with write access. The file cursor is left at the end of the record.
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain blocks.
+///
+/// Returns a [`DATError::InvalidInput`] if the index is out of bounds for the layout or the
+/// record length does not match the layout's record size.
+///
+/// Returns a [`DATError::Overflow`] if the record would extend past the end of the current file content.
+///
+/// If an I/O error occurs while writing the file, a [`DATError::FileIO`]
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::block::{get_block_layout_for_type, read_block, write_block};
+/// use libxivdat::dat_file::DATFile;
+/// use std::fs::OpenOptions;
+///
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let temp_dir = tempdir().unwrap();
+/// # let path = temp_dir.path().join("GS.DAT");
+/// # std::fs::copy("./resources/TEST_BLOCK.DAT", &path).unwrap();
+///
+/// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+/// let layout = get_block_layout_for_type(&dat_file.file_type()).unwrap();
+///
+/// let mut record = read_block(&mut dat_file, &layout, 0).unwrap();
+/// record[..6].copy_from_slice(b"Deck A");
+/// write_block(&mut dat_file, &layout, 0, &record).unwrap();
+///
+/// assert_eq!(&read_block(&mut dat_file, &layout, 0).unwrap()[..6], b"Deck A");
+/// ```
+pub fn write_block(dat_file: &mut DATFile, layout: &BlockLayout, index: u32, record: &[u8]) -> Result<(), DATError> {
+    if !BLOCK_BASED_TYPES.contains(&dat_file.file_type()) {
+        return Err(DATError::IncorrectType(
+            "Target file is of a type that should not contain blocks.".into(),
+        ));
+    }
+    if u32::try_from(record.len()).ok() != Some(layout.record_size) {
+        return Err(DATError::InvalidInput(
+            "Record length does not match the layout record size.".into(),
+        ));
+    }
+    let start = match layout.record_offset(index) {
+        Some(start) => start,
+        None => {
+            return Err(DATError::InvalidInput(
+                "Record index is out of bounds for the block layout.".into(),
+            ))
+        }
+    };
+    // Readable content is 1 byte shorter than content_size.
+    match start.checked_add(layout.record_size) {
+        Some(end) if end < dat_file.content_size() => (),
+        _ => {
+            return Err(DATError::Overflow(
+                "Record would extend past the end of the file content.".into(),
+            ))
+        }
+    };
+    dat_file.seek(SeekFrom::Start(u64::from(start)))?;
+    dat_file.write_all(record)?;
+    Ok(())
+}
+
+/// The content block of a block-based DAT file, along with the [`BlockLayout`] used to interpret it.
+/// [`BlockContent`] owns the content bytes and is returned from [`read_block_content()`].
+/// Records are borrowed from the owned buffer, so accessing them does not copy any data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockContent {
+    /// The raw content block of the file.
+    bytes: Vec<u8>,
+    /// The layout used to interpret the content.
+    layout: BlockLayout,
+}
+
+impl BlockContent {
+    /// Returns a [`Vec`] of every record in the content block. No record data is copied.
+    ///
+    /// # Errors
+    ///
+    /// This function can only fail if the content has been altered to no longer match the layout,
+    /// in which case it returns the same errors as [`as_block_vec()`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::read_block_content;
+    ///
+    /// let content = read_block_content("./resources/TEST_BLOCK.DAT").unwrap();
+    /// for record in content.as_block_vec().unwrap() {
+    ///     assert_eq!(&record[..4], b"Deck");
+    /// }
+    /// ```
+    pub fn as_block_vec(&self) -> Result<Vec<&[u8]>, DATError> {
+        as_block_vec(&self.bytes, &self.layout)
+    }
+
+    /// Returns the raw content block, including any data not contained in a record.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::read_block_content;
+    ///
+    /// let content = read_block_content("./resources/TEST_BLOCK.DAT").unwrap();
+    /// assert_eq!(content.bytes().len(), 648);
+    /// ```
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the record at a given index, or [`None`] if the index is out of bounds.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::block::read_block_content;
+    ///
+    /// let content = read_block_content("./resources/TEST_BLOCK.DAT").unwrap();
+    /// assert_eq!(&content.get(0).unwrap()[..6], b"Deck 1");
+    /// assert!(content.get(10).is_none());
+    /// ```
+    pub fn get(&self, index: u32) -> Option<&[u8]> {
+        as_block(&self.bytes, &self.layout, index).ok()
+    }
+
+    /// Returns true if the layout contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.layout.record_count == 0
+    }
+
+    /// Returns the [`BlockLayout`] used to interpret the content.
+    pub fn layout(&self) -> BlockLayout {
+        self.layout
+    }
+
+    /// Returns the number of records in the content block.
+    pub fn len(&self) -> usize {
+        self.layout.record_count as usize
+    }
+}
+
+// --- Unit Tests
+
+#[cfg(test)]
+mod tests {
+    extern crate tempfile;
+    use tempfile::tempdir;
+
+    use super::*;
+    use std::fs::{copy, OpenOptions};
+
+    const TEST_FILE_PATH: &str = "./resources/TEST_BLOCK.DAT";
+    const TEST_NON_BLOCK_PATH: &str = "./resources/TEST_MACRO.DAT";
+    const TEST_LAYOUT: BlockLayout = BlockLayout::new(0, 58, 10);
+
+    // --- Module Functions
+
+    #[test]
+    fn test_as_block() -> Result<(), String> {
+        let bytes = match read_content(TEST_FILE_PATH) {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        match as_block(&bytes, &TEST_LAYOUT, 9) {
+            Ok(record) => {
+                assert_eq!(record.len(), 58);
+                assert_eq!(&record[..7], b"Deck 10");
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_as_block_error_index() -> Result<(), String> {
+        let bytes = [0u8; 580];
+        match as_block(&bytes, &TEST_LAYOUT, 10) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_as_block_vec() -> Result<(), String> {
+        let bytes = match read_content(TEST_FILE_PATH) {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        match as_block_vec(&bytes, &TEST_LAYOUT) {
+            Ok(records) => {
+                assert_eq!(records.len(), 10);
+                for (i, record) in records.iter().enumerate() {
+                    assert_eq!(record.len(), 58);
+                    assert!(record.starts_with(format!("Deck {}", i + 1).as_bytes()));
+                }
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_as_block_vec_error_underflow() -> Result<(), String> {
+        let bytes = [0u8; 579];
+        match as_block_vec(&bytes, &TEST_LAYOUT) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Underflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_read_block() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_FILE_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match read_block(&mut dat_file, &TEST_LAYOUT, 4) {
+            Ok(record) => Ok(assert_eq!(&record[..6], b"Deck 5")),
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_read_block_error_type() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_NON_BLOCK_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match read_block(&mut dat_file, &TEST_LAYOUT, 0) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_read_block_error_eof() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_FILE_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match read_block(&mut dat_file, &BlockLayout::new(600, 58, 1), 0) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::EndOfFile(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_read_block_content() -> Result<(), String> {
+        match read_block_content(TEST_FILE_PATH) {
+            Ok(content) => {
+                assert_eq!(content.len(), 10);
+                assert_eq!(content.layout(), TEST_LAYOUT);
+                assert_eq!(&content.get(0).unwrap()[..6], b"Deck 1");
+                assert!(content.get(10).is_none());
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_read_block_content_error_type() -> Result<(), String> {
+        match read_block_content(TEST_NON_BLOCK_PATH) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_write_block() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("GS.DAT");
+        match copy(TEST_FILE_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut dat_file = match DATFile::open_options(&tmp_path, OpenOptions::new().read(true).write(true)) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        let content_size = dat_file.content_size();
+        let new_record = [0x41u8; 58];
+        match write_block(&mut dat_file, &TEST_LAYOUT, 3, &new_record) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing record: {}", err)),
+        };
+        assert_eq!(dat_file.content_size(), content_size);
+        // Check that only the target record changed.
+        let bytes = match read_content(&tmp_path) {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(format!("Error reading file after write: {}", err)),
+        };
+        let records = match as_block_vec(&bytes, &TEST_LAYOUT) {
+            Ok(records) => records,
+            Err(err) => return Err(format!("Error reading records after write: {}", err)),
+        };
+        assert_eq!(records[3], &new_record[..]);
+        assert_eq!(&records[2][..6], b"Deck 3");
+        assert_eq!(&records[4][..6], b"Deck 5");
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_block_error_record_size() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("GS.DAT");
+        match copy(TEST_FILE_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut dat_file = match DATFile::open_options(&tmp_path, OpenOptions::new().read(true).write(true)) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        match write_block(&mut dat_file, &TEST_LAYOUT, 0, &[0u8; 57]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_write_block_error_overflow() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("GS.DAT");
+        match copy(TEST_FILE_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut dat_file = match DATFile::open_options(&tmp_path, OpenOptions::new().read(true).write(true)) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        match write_block(&mut dat_file, &BlockLayout::new(600, 58, 1), 0, &[0u8; 58]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    // --- BlockLayout
+
+    #[test]
+    fn test_blocklayout_record_offset() -> Result<(), String> {
+        assert_eq!(TEST_LAYOUT.record_offset(0), Some(0));
+        assert_eq!(TEST_LAYOUT.record_offset(9), Some(522));
+        assert_eq!(TEST_LAYOUT.record_offset(10), None);
+        assert_eq!(BlockLayout::new(u32::MAX, 1, 2).record_offset(1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_block_layout_for_type() -> Result<(), String> {
+        for file_type in BLOCK_BASED_TYPES.iter() {
+            if let Some(layout) = get_block_layout_for_type(file_type) {
+                match crate::dat_type::get_default_max_size_for_type(file_type) {
+                    // Content must fit in the writeable content region.
+                    Some(max_size) => assert!(layout.size().unwrap() < max_size),
+                    None => return Err(format!("No max size for {:?}.", file_type)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // --- DatRecord Derive
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use super::*;
+        use crate::high_level::{AsBytes, DatRecord, Validate};
+
+        #[derive(DatRecord, Debug, PartialEq)]
+        #[dat(size = 58)]
+        struct TestDeck {
+            #[dat(string = 48)]
+            name: String,
+            cards: [u16; 5],
+        }
+
+        #[derive(DatRecord, Debug, PartialEq)]
+        struct TestNested(u8, #[dat(pad = 1)] [TestPair; 2]);
+
+        #[derive(DatRecord, Debug, PartialEq)]
+        struct TestPair {
+            a: i16,
+            b: u32,
+        }
+
+        #[test]
+        fn test_derive_read() -> Result<(), String> {
+            let content = match read_block_content(TEST_FILE_PATH) {
+                Ok(content) => content,
+                Err(err) => return Err(format!("Error reading file: {}", err)),
+            };
+            assert_eq!(TestDeck::SIZE, 58);
+            match TestDeck::try_from(content.get(9).unwrap()) {
+                Ok(deck) => Ok(assert_eq!(
+                    deck,
+                    TestDeck {
+                        name: "Deck 10".to_owned(),
+                        cards: [0; 5]
+                    }
+                )),
+                Err(err) => Err(format!("Error: {}", err)),
+            }
+        }
+
+        #[test]
+        fn test_derive_round_trip() -> Result<(), String> {
+            let deck = TestDeck {
+                name: "Deck A".to_owned(),
+                cards: [1, 2, 3, 0x0100, 0xFFFF],
+            };
+            let bytes = match deck.as_bytes() {
+                Ok(bytes) => bytes,
+                Err(err) => return Err(format!("Error: {}", err)),
+            };
+            assert_eq!(bytes.len(), 58);
+            assert_eq!(&bytes[..7], b"Deck A\0");
+            assert_eq!(&bytes[48..52], &[1, 0, 2, 0]);
+            assert_eq!(&bytes[56..], &[0xFF, 0xFF]);
+            match TestDeck::try_from(&bytes[..]) {
+                Ok(res) => Ok(assert_eq!(res, deck)),
+                Err(err) => Err(format!("Error: {}", err)),
+            }
+        }
+
+        #[test]
+        fn test_derive_nested() -> Result<(), String> {
+            let nested = TestNested(7, [TestPair { a: -1, b: 2 }, TestPair { a: 3, b: 0x01020304 }]);
+            assert_eq!(TestNested::SIZE, 14);
+            let bytes = match nested.as_bytes() {
+                Ok(bytes) => bytes,
+                Err(err) => return Err(format!("Error: {}", err)),
+            };
+            assert_eq!(bytes, [7, 0, 0xFF, 0xFF, 2, 0, 0, 0, 3, 0, 4, 3, 2, 1]);
+            match TestNested::try_from(&bytes[..]) {
+                Ok(res) => Ok(assert_eq!(res, nested)),
+                Err(err) => Err(format!("Error: {}", err)),
+            }
+        }
+
+        #[test]
+        fn test_derive_validate() -> Result<(), String> {
+            let deck = TestDeck {
+                name: "X".repeat(48),
+                cards: [0; 5],
+            };
+            match deck.validate() {
+                Some(DATError::Overflow(_)) => (),
+                _ => return Err("Validation did not fail on a long string.".to_owned()),
+            };
+            match deck.as_bytes() {
+                Ok(_) => Err("No error returned.".to_owned()),
+                Err(err) => match err {
+                    DATError::Overflow(_) => Ok(()),
+                    _ => Err(format!("Incorrect error: {}", err)),
+                },
+            }
+        }
+
+        #[test]
+        fn test_derive_error_size() -> Result<(), String> {
+            match TestDeck::try_from(&[0u8; 57][..]) {
+                Ok(_) => return Err("No error returned for short buffer.".to_owned()),
+                Err(DATError::Overflow(_)) => (),
+                Err(err) => return Err(format!("Incorrect error: {}", err)),
+            };
+            match TestDeck::try_from(&[0u8; 59][..]) {
+                Ok(_) => Err("No error returned for long buffer.".to_owned()),
+                Err(DATError::Underflow(_)) => Ok(()),
+                Err(err) => Err(format!("Incorrect error: {}", err)),
+            }
+        }
+
+        #[test]
+        fn test_derive_error_encoding() -> Result<(), String> {
+            let mut bytes = [0u8; 58];
+            bytes[0] = 0xFF;
+            match TestDeck::try_from(&bytes[..]) {
+                Ok(_) => Err("No error returned.".to_owned()),
+                Err(DATError::BadEncoding(_)) => Ok(()),
+                Err(err) => Err(format!("Incorrect error: {}", err)),
+            }
+        }
+    }
+}
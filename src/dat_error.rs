@@ -1,86 +1,413 @@
 use std::array;
+use std::borrow::Cow;
 use std::convert::From;
 use std::error::Error;
 use std::fmt;
 use std::io;
 use std::num;
+use std::path::{Path, PathBuf};
 
 /// Wrapper error for any error related to processing a binary DAT file.
+///
+/// Each variant contains an [`ErrorContext`] describing the error and, where known, the file path,
+/// content offset, section, and sizes involved.
+///
+/// New variants may be added in future versions, so matches on this type should include a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DATError {
     /// Attempted to read a byte stream as UTF-8 text when it didn't contain
     /// valid UTF-8.
-    BadEncoding(&'static str),
+    BadEncoding(ErrorContext),
     /// The header data is incorrect. The file is probably not a binary DAT file,
     /// but may be a plaintext DAT.
-    BadHeader(&'static str),
+    BadHeader(ErrorContext),
     /// Data provided exceeds the maximum length specified in the header or the
     /// maximum possible length.
-    Overflow(&'static str),
+    Overflow(ErrorContext),
     /// Data provided is shorter than the content_size specified in the header or
     /// the minimum possible length.
-    Underflow(&'static str),
+    Underflow(ErrorContext),
     /// Unexpectedly hit the EOF when attempting to read a block of data.
-    EndOfFile(&'static str),
+    EndOfFile(ErrorContext),
     /// Wrapper for various `std::io::Error` errors. Represents an error reading or writing a
-    /// file on disk.
-    FileIO(io::Error),
+    /// file on disk. The underlying [`std::io::Error`] is available from [`ErrorContext::source()`]
+    /// or [`Error::source()`].
+    FileIO(ErrorContext),
     /// Attempted to use a type-specific function on the incorrect [`DATType`](crate::dat_type::DATType)
-    IncorrectType(&'static str),
+    IncorrectType(ErrorContext),
     /// Invalid input for a function
-    InvalidInput(&'static str),
+    InvalidInput(ErrorContext),
+    /// An advisory lock on the file could not be acquired before the timeout because it is held elsewhere,
+    /// such as by another process.
+    Locked(ErrorContext),
+}
+
+/// Describes the circumstances of a [`DATError`]. Only the message is always present; other fields
+/// are populated when they are known at the point the error occurs.
+///
+/// An [`ErrorContext`] can be built from a static message and extended with builder methods.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_error::{DATError, ErrorContext};
+///
+/// let err = DATError::Overflow(ErrorContext::from("Section is too long.").with_offset(27).with_sizes(22, 30));
+/// assert_eq!(err.context().offset(), Some(27));
+/// assert_eq!(err.context().expected_size(), Some(22));
+/// assert_eq!(err.context().actual_size(), Some(30));
+/// ```
+#[derive(Debug, Default)]
+pub struct ErrorContext(Box<ContextData>);
+
+/// Data of an [`ErrorContext`]. Boxed to keep [`DATError`] small.
+#[derive(Debug, Default)]
+struct ContextData {
+    /// Actual size of the data, in bytes.
+    actual_size: Option<u64>,
+    /// Expected size of the data, in bytes.
+    expected_size: Option<u64>,
+    /// Line number (1-based) of the text being processed, such as a macro template.
+    line: Option<usize>,
+    /// Human-readable description of the error.
+    message: Cow<'static, str>,
+    /// Offset into the content block of the file or buffer where the error occurred.
+    offset: Option<u64>,
+    /// Path of the file being processed.
+    path: Option<PathBuf>,
+    /// Index of the section being processed.
+    section_index: Option<usize>,
+    /// The underlying error that caused this error.
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+    /// Tag of the section being processed.
+    tag: Option<String>,
 }
 
 impl fmt::Display for DATError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            DATError::BadEncoding(desc) => write!(f, "Invalid text encoding: {}", desc),
-            DATError::BadHeader(desc) => write!(f, "Invalid header data: {}", desc),
-            DATError::Overflow(desc) => write!(f, "Content overflow: {}", desc),
-            DATError::Underflow(desc) => write!(f, "Content underflow: {}", desc),
-            DATError::EndOfFile(desc) => write!(f, "Unexpected EOF: {}", desc),
-            DATError::FileIO(e) => write!(f, "File IO error: {:?}", e.source()),
-            DATError::IncorrectType(desc) => write!(f, "Incorrect DAT file type: {}", desc),
-            DATError::InvalidInput(desc) => write!(f, "Invalid input: {}", desc),
+            DATError::BadEncoding(ctx) => write!(f, "Invalid text encoding: {}", ctx),
+            DATError::BadHeader(ctx) => write!(f, "Invalid header data: {}", ctx),
+            DATError::Overflow(ctx) => write!(f, "Content overflow: {}", ctx),
+            DATError::Underflow(ctx) => write!(f, "Content underflow: {}", ctx),
+            DATError::EndOfFile(ctx) => write!(f, "Unexpected EOF: {}", ctx),
+            DATError::FileIO(ctx) => write!(f, "File IO error: {}", ctx),
+            DATError::IncorrectType(ctx) => write!(f, "Incorrect DAT file type: {}", ctx),
+            DATError::InvalidInput(ctx) => write!(f, "Invalid input: {}", ctx),
+            DATError::Locked(ctx) => write!(f, "File locked: {}", ctx),
         }
     }
 }
 
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.message)?;
+        let mut details = Vec::<String>::new();
+        if let Some(path) = &self.0.path {
+            details.push(format!("path: {}", path.display()));
+        }
+        if let Some(line) = self.0.line {
+            details.push(format!("line: {}", line));
+        }
+        if let Some(offset) = self.0.offset {
+            details.push(format!("offset: {}", offset));
+        }
+        if let Some(section_index) = self.0.section_index {
+            details.push(format!("section: {}", section_index));
+        }
+        if let Some(tag) = &self.0.tag {
+            details.push(format!("tag: {:?}", tag));
+        }
+        if let Some(expected_size) = self.0.expected_size {
+            details.push(format!("expected size: {}", expected_size));
+        }
+        if let Some(actual_size) = self.0.actual_size {
+            details.push(format!("actual size: {}", actual_size));
+        }
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 impl Error for DATError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        match *self {
-            DATError::FileIO(ref e) => Some(e),
-            _ => None,
-        }
+        self.context().source()
+    }
+}
+
+impl From<&'static str> for ErrorContext {
+    fn from(message: &'static str) -> ErrorContext {
+        ErrorContext(Box::new(ContextData {
+            message: Cow::Borrowed(message),
+            ..Default::default()
+        }))
+    }
+}
+
+impl From<String> for ErrorContext {
+    fn from(message: String) -> ErrorContext {
+        ErrorContext(Box::new(ContextData {
+            message: Cow::Owned(message),
+            ..Default::default()
+        }))
     }
 }
 
 impl From<io::Error> for DATError {
     fn from(e: io::Error) -> DATError {
-        DATError::FileIO(e)
+        DATError::FileIO(ErrorContext::from(e.to_string()).with_source(e))
     }
 }
 
 impl From<num::TryFromIntError> for DATError {
     fn from(_: num::TryFromIntError) -> DATError {
-        DATError::Overflow("Could not index full file content on a 16-bit platform.")
+        DATError::Overflow("Could not index full file content on a 16-bit platform.".into())
     }
 }
 
 impl From<array::TryFromSliceError> for DATError {
     fn from(_: array::TryFromSliceError) -> DATError {
-        DATError::BadHeader("Header data is absent or unreadable.")
+        DATError::BadHeader("Header data is absent or unreadable.".into())
     }
 }
 
 impl From<std::str::Utf8Error> for DATError {
-    fn from(_: std::str::Utf8Error) -> DATError {
-        DATError::BadEncoding("Text data block did not contain valid utf-8.")
+    fn from(e: std::str::Utf8Error) -> DATError {
+        DATError::BadEncoding(ErrorContext::from("Text data block did not contain valid utf-8.").with_source(e))
     }
 }
 
 impl From<std::string::FromUtf8Error> for DATError {
-    fn from(_: std::string::FromUtf8Error) -> DATError {
-        DATError::BadEncoding("Text data block did not contain valid utf-8.")
+    fn from(e: std::string::FromUtf8Error) -> DATError {
+        DATError::BadEncoding(ErrorContext::from("Text data block did not contain valid utf-8.").with_source(e))
+    }
+}
+
+impl DATError {
+    /// Returns the [`ErrorContext`] describing the error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::read_content;
+    ///
+    /// let err = read_content("./resources/DOES_NOT_EXIST.DAT").unwrap_err();
+    /// assert!(err.context().path().is_some());
+    /// ```
+    pub fn context(&self) -> &ErrorContext {
+        match self {
+            DATError::BadEncoding(ctx)
+            | DATError::BadHeader(ctx)
+            | DATError::Overflow(ctx)
+            | DATError::Underflow(ctx)
+            | DATError::EndOfFile(ctx)
+            | DATError::FileIO(ctx)
+            | DATError::IncorrectType(ctx)
+            | DATError::InvalidInput(ctx)
+            | DATError::Locked(ctx) => ctx,
+        }
+    }
+
+    /// Returns a mutable reference to the [`ErrorContext`] describing the error.
+    pub fn context_mut(&mut self) -> &mut ErrorContext {
+        match self {
+            DATError::BadEncoding(ctx)
+            | DATError::BadHeader(ctx)
+            | DATError::Overflow(ctx)
+            | DATError::Underflow(ctx)
+            | DATError::EndOfFile(ctx)
+            | DATError::FileIO(ctx)
+            | DATError::IncorrectType(ctx)
+            | DATError::InvalidInput(ctx)
+            | DATError::Locked(ctx) => ctx,
+        }
+    }
+
+    /// Sets the line number (1-based) of the error, if one is not already set.
+    pub fn with_line(mut self, line: usize) -> Self {
+        let ctx = self.context_mut();
+        if ctx.0.line.is_none() {
+            ctx.0.line = Some(line);
+        }
+        self
+    }
+
+    /// Sets the content offset of the error, if one is not already set.
+    /// Offsets set closer to the source of an error are more precise, so they are never overwritten.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        let ctx = self.context_mut();
+        if ctx.0.offset.is_none() {
+            ctx.0.offset = Some(offset);
+        }
+        self
+    }
+
+    /// Sets the file path of the error, if one is not already set.
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let ctx = self.context_mut();
+        if ctx.0.path.is_none() {
+            ctx.0.path = Some(path.as_ref().to_path_buf());
+        }
+        self
+    }
+
+    /// Sets the section index of the error, if one is not already set.
+    pub fn with_section_index(mut self, section_index: usize) -> Self {
+        let ctx = self.context_mut();
+        if ctx.0.section_index.is_none() {
+            ctx.0.section_index = Some(section_index);
+        }
+        self
+    }
+}
+
+impl ErrorContext {
+    /// Returns the actual size of the data in bytes, if known.
+    pub fn actual_size(&self) -> Option<u64> {
+        self.0.actual_size
+    }
+
+    /// Returns the expected size of the data in bytes, if known.
+    pub fn expected_size(&self) -> Option<u64> {
+        self.0.expected_size
+    }
+
+    /// Returns the line number (1-based) of the text being processed, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.0.line
+    }
+
+    /// Returns the human-readable description of the error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_error::ErrorContext;
+    ///
+    /// assert_eq!(ErrorContext::from("Something broke.").message(), "Something broke.");
+    /// ```
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    /// Returns the offset into the content block of the file or buffer where the error occurred, if known.
+    pub fn offset(&self) -> Option<u64> {
+        self.0.offset
+    }
+
+    /// Returns the path of the file being processed, if known.
+    pub fn path(&self) -> Option<&Path> {
+        self.0.path.as_deref()
+    }
+
+    /// Returns the index of the section being processed, if known.
+    pub fn section_index(&self) -> Option<usize> {
+        self.0.section_index
+    }
+
+    /// Returns the underlying error that caused this error, if any.
+    pub fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.0.source {
+            Some(source) => Some(source.as_ref()),
+            None => None,
+        }
+    }
+
+    /// Returns the tag of the section being processed, if known.
+    pub fn tag(&self) -> Option<&str> {
+        self.0.tag.as_deref()
+    }
+
+    /// Sets the line number (1-based) of the text being processed.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.0.line = Some(line);
+        self
+    }
+
+    /// Sets the content offset where the error occurred.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.0.offset = Some(offset);
+        self
+    }
+
+    /// Sets the path of the file being processed.
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.0.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the index of the section being processed.
+    pub fn with_section_index(mut self, section_index: usize) -> Self {
+        self.0.section_index = Some(section_index);
+        self
+    }
+
+    /// Sets the expected and actual sizes of the data in bytes.
+    pub fn with_sizes(mut self, expected_size: u64, actual_size: u64) -> Self {
+        self.0.expected_size = Some(expected_size);
+        self.0.actual_size = Some(actual_size);
+        self
+    }
+
+    /// Sets the underlying error that caused this error.
+    pub fn with_source<E: Error + Send + Sync + 'static>(mut self, source: E) -> Self {
+        self.0.source = Some(Box::new(source));
+        self
+    }
+
+    /// Sets the tag of the section being processed.
+    pub fn with_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.0.tag = Some(tag.into());
+        self
+    }
+}
+
+// --- Unit Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_context() -> Result<(), String> {
+        let err = DATError::Overflow(
+            ErrorContext::from("Section is too long.")
+                .with_path("TEST.DAT")
+                .with_offset(27)
+                .with_section_index(1)
+                .with_tag("A")
+                .with_sizes(22, 30),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Content overflow: Section is too long. \
+             (path: TEST.DAT, offset: 27, section: 1, tag: \"A\", expected size: 22, actual size: 30)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_file_io() -> Result<(), String> {
+        let err = DATError::from(io::Error::new(io::ErrorKind::NotFound, "File is missing."));
+        assert_eq!(err.to_string(), "File IO error: File is missing.");
+        match err.source() {
+            Some(source) => Ok(assert_eq!(source.to_string(), "File is missing.")),
+            None => Err("No source error.".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_with_offset_keeps_existing() -> Result<(), String> {
+        let err = DATError::Underflow(ErrorContext::from("Too short.").with_offset(3)).with_offset(10);
+        assert_eq!(err.context().offset(), Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_line() -> Result<(), String> {
+        let err = DATError::InvalidInput(ErrorContext::from("Bad line.").with_line(4)).with_line(9);
+        assert_eq!(err.context().line(), Some(4));
+        assert_eq!(err.to_string(), "Invalid input: Bad line. (line: 4)");
+        Ok(())
     }
 }
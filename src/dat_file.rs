@@ -2,10 +2,21 @@ use std::convert::{TryFrom, TryInto};
 use std::fs::{File, Metadata, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
+#[cfg(feature = "lock")]
+use std::time::{Duration, Instant};
 
 use crate::dat_error::DATError;
 use crate::dat_type::*;
-
+#[cfg(feature = "lock")]
+use fs2::FileExt;
+#[cfg(feature = "mmap")]
+use memmap2::{MmapMut, MmapOptions};
+#[cfg(feature = "mmap")]
+use std::ops::Deref;
+
+/// The prefix of the character directories within the game's config directory.
+/// Character-specific DAT files are stored in these directories, while global ones are stored in the config directory itself.
+pub const CHARACTER_DIR_PREFIX: &str = "FFXIV_CHR";
 /// Header size in bytes.
 pub const HEADER_SIZE: u32 = 0x11;
 /// Offset of the max_size header value from the actual file size on disk.
@@ -18,6 +29,32 @@ const INDEX_FILE_TYPE: usize = 0x00;
 const INDEX_MAX_SIZE: usize = 0x04;
 /// Index of the `content_size` header record.
 const INDEX_CONTENT_SIZE: usize = 0x08;
+/// Capacity in bytes of the internal read buffer of a [`DATFile`]. Reads at least this long bypass the buffer.
+const READ_BUFFER_SIZE: usize = 8192;
+/// Interval between attempts to acquire a contended file lock.
+#[cfg(feature = "lock")]
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+/// Time [`write_content()`] waits for an exclusive lock on the target file before giving up.
+///
+/// Enabled by feature `lock`.
+#[cfg(feature = "lock")]
+pub const WRITE_CONTENT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The kind of advisory lock taken on a DAT file by [`DATFile::open_locked()`] and
+/// [`DATFile::open_options_locked()`].
+///
+/// Locks are advisory: they only exclude other handles that also lock the file. On Linux this uses `flock`,
+/// so locks conflict between separate opens of the same file even within one process.
+///
+/// Enabled by feature `lock`.
+#[cfg(feature = "lock")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockMode {
+    /// An exclusive lock, which excludes all other locks. This should be used when writing to the file.
+    Exclusive,
+    /// A shared lock, which may be held by any number of readers at once, but excludes exclusive locks.
+    Shared,
+}
 
 /// A reference to an open DAT file on the system. This emulates the standard lib
 /// [`std::fs::File`] but provides additional DAT-specific functionality.
@@ -25,6 +62,11 @@ const INDEX_CONTENT_SIZE: usize = 0x08;
 /// Reads and writes to DAT files are performed only on the data contents of the file.
 /// XOR masks are automatically applied as necessary.
 ///
+/// Small reads are served from an internal buffer of unmasked content, so loops over many small
+/// records (such as [`read_section()`](crate::section::read_section)) do not hit the OS on every call.
+/// Seeks that land within the buffered region do not touch the underlying file. The buffer is discarded
+/// before any write, so reads and writes may be freely interleaved.
+///
 /// # Examples
 /// ```rust
 /// use libxivdat::dat_file::DATFile;
@@ -65,10 +107,52 @@ pub struct DATFile {
     max_size: u32,
     /// The underlying [`std::fs::File`].
     raw_file: File,
+    /// Unmasked content bytes read ahead from `raw_file`. While this is not empty, the cursor of `raw_file`
+    /// is at the end of the buffered region.
+    read_buf: Vec<u8>,
+    /// Index of the next unread byte in `read_buf`.
+    read_buf_pos: usize,
+    /// Content offset of the first byte in `read_buf`.
+    read_buf_start: u64,
+    /// Scratch buffer reused by [`write()`](Self::write) to mask content without allocating on every call.
+    write_buf: Vec<u8>,
+}
+
+/// The unmasked content block of a DAT file, held in a private copy-on-write memory map.
+/// Returned by [`DATFile::read_content_mapped()`] and [`read_content_mapped()`].
+///
+/// Dereferences to a byte slice, so it can be passed directly to borrowed parsers such as
+/// [`as_section_vec()`](crate::section::as_section_vec). The map is released when this is dropped.
+///
+/// Enabled by feature `mmap`.
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MappedContent {
+    /// The private map of the content block, already unmasked.
+    map: MmapMut,
+}
+
+#[cfg(feature = "mmap")]
+impl AsRef<[u8]> for MappedContent {
+    fn as_ref(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Deref for MappedContent {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.map
+    }
 }
 
 impl Read for DATFile {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Serve from the internal buffer while it has unread bytes.
+        if self.read_buf_pos < self.read_buf.len() {
+            return Ok(self.read_from_buf(buf));
+        }
         // Limit read size to content region of the DAT file.
         let cur_pos = self.stream_position()? as u32;
         let max_end = self.content_size - 1;
@@ -82,23 +166,49 @@ impl Read for DATFile {
         if read_len < 1 {
             return Ok(0);
         }
-        // Initialize a temporary buffer used for applying masks
-        let mut internal_buf = vec![0u8; read_len];
-        let count = self.raw_file.read(&mut internal_buf)?;
-        // Apply XOR mask to content data if needed.
-        if let Some(mask_val) = get_mask_for_type(&self.file_type) {
-            for byte in internal_buf.iter_mut() {
-                *byte ^= mask_val;
+        let mask = get_mask_for_type(&self.file_type);
+        // Large reads go directly into the input buffer and are unmasked in place.
+        if read_len >= READ_BUFFER_SIZE {
+            self.discard_read_buf()?;
+            let count = self.raw_file.read(&mut buf[..read_len])?;
+            if let Some(mask_val) = mask {
+                apply_mask(&mut buf[..count], mask_val);
             }
+            return Ok(count);
+        }
+        // Small reads refill the internal buffer, up to the end of content.
+        let fill_len = READ_BUFFER_SIZE.min((max_end - cur_pos) as usize);
+        self.read_buf.resize(fill_len, 0);
+        let filled = self.raw_file.read(&mut self.read_buf)?;
+        self.read_buf.truncate(filled);
+        if let Some(mask_val) = mask {
+            apply_mask(&mut self.read_buf, mask_val);
         }
-        // Copy internal buffer into input buffer.
-        buf[..read_len].clone_from_slice(&internal_buf);
-        Ok(count)
+        self.read_buf_start = u64::from(cur_pos);
+        self.read_buf_pos = 0;
+        Ok(self.read_from_buf(buf))
     }
 }
 
 impl Seek for DATFile {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        // Seeks that land within the read buffer only move the buffer position.
+        if !self.read_buf.is_empty() {
+            let buf_start = self.read_buf_start as i64;
+            let buf_end = buf_start + self.read_buf.len() as i64;
+            let target = match pos {
+                SeekFrom::Current(offset) => Some(buf_start + self.read_buf_pos as i64 + offset),
+                SeekFrom::Start(offset) => i64::try_from(offset).ok(),
+                SeekFrom::End(_) => None,
+            };
+            match target {
+                Some(target) if target >= buf_start && target <= buf_end => {
+                    self.read_buf_pos = (target - buf_start) as usize;
+                    return Ok(target as u64);
+                }
+                _ => self.discard_read_buf()?,
+            }
+        }
         let cursor = match pos {
             // Match `File` behavior of complaining if cursor goes negative relative to start.
             SeekFrom::Current(offset) => {
@@ -127,6 +237,8 @@ impl Seek for DATFile {
 
 impl Write for DATFile {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Return the file cursor to the logical position before writing.
+        self.discard_read_buf()?;
         // Get current cursor position for length checking.
         let content_cursor = self.stream_position()? as u32;
 
@@ -136,7 +248,7 @@ impl Write for DATFile {
             Err(_) => {
                 return Err(std::io::Error::new(
                     std::io::ErrorKind::InvalidInput,
-                    DATError::Overflow("Content too long to write."),
+                    DATError::Overflow("Content too long to write.".into()),
                 ))
             }
         };
@@ -149,7 +261,7 @@ impl Write for DATFile {
                 if new_content_size > self.max_size {
                     return Err(std::io::Error::new(
                         std::io::ErrorKind::InvalidInput,
-                        DATError::Overflow("Content size would exdeed maximum size after write."),
+                        DATError::Overflow("Content size would exdeed maximum size after write.".into()),
                     ));
                 }
                 // Write the new content size
@@ -159,20 +271,20 @@ impl Write for DATFile {
             None => {
                 return Err(std::io::Error::new(
                     std::io::ErrorKind::InvalidInput,
-                    DATError::Overflow("Content size would exceed maximum possible size (u32::MAX) after write."),
+                    DATError::Overflow(
+                        "Content size would exceed maximum possible size (u32::MAX) after write.".into(),
+                    ),
                 ))
             }
         };
 
-        // Copy write buffer and apply XOR mask if needed.
+        // Copy write buffer into the reused scratch buffer and apply XOR mask if needed.
         match get_mask_for_type(&self.file_type) {
             Some(mask_val) => {
-                let mut masked_bytes = vec![0u8; buf.len()];
-                masked_bytes.copy_from_slice(buf);
-                for byte in masked_bytes.iter_mut() {
-                    *byte ^= mask_val;
-                }
-                Ok(self.raw_file.write(&masked_bytes)?)
+                self.write_buf.clear();
+                self.write_buf.extend_from_slice(buf);
+                apply_mask(&mut self.write_buf, mask_val);
+                Ok(self.raw_file.write(&self.write_buf)?)
             }
             None => Ok(self.raw_file.write(buf)?),
         }
@@ -232,6 +344,55 @@ impl DATFile {
         Self::create_unsafe(path, dat_type, 1, max_size, end_byte)
     }
 
+    /// Creates a new DAT file with the default contents written by the game client for its type, in read/write mode.
+    /// This will truncate an existing file if one exists at the specified path.
+    ///
+    /// The default contents are bundled with this library (see
+    /// [`get_default_file_for_type()`](crate::dat_type::get_default_file_for_type())). Unlike
+    /// [`create()`](Self::create()), which creates an empty content block, this produces the same file a fresh
+    /// client install would, including any default resources (such as empty gearset or UI config records).
+    ///
+    /// No default file is bundled for [`DATType::Hotbar`](crate::dat_type::DATType::Hotbar) or
+    /// [`DATType::Unknown`](crate::dat_type::DATType::Unknown); use [`create()`](Self::create()) for these
+    /// types instead. To create a default plaintext DAT file, use
+    /// [`create_default_plaintext()`](crate::dat_file::create_default_plaintext()).
+    ///
+    /// Enabled by feature `default-dats`.
+    ///
+    /// # Errors
+    ///
+    /// If an I/O error creating the file occurs, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// A [`DATError::InvalidInput`](crate::dat_error::DATError::InvalidInput) is returned if no default file
+    /// is bundled for `dat_type`. No file is created in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::dat_type::DATType;
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("GEARSET.DAT");
+    ///
+    /// let dat_file = DATFile::create_default(&path, DATType::Gearset).unwrap();
+    /// assert_eq!(dat_file.file_type(), DATType::Gearset);
+    /// ```
+    #[cfg(feature = "default-dats")]
+    pub fn create_default<P: AsRef<Path>>(path: P, dat_type: DATType) -> Result<Self, DATError> {
+        match get_default_file_for_type(&dat_type) {
+            Some(default_bytes) => {
+                std::fs::write(&path, default_bytes).map_err(|err| DATError::from(err).with_path(&path))?;
+                DATFile::open_options(path, OpenOptions::new().read(true).write(true))
+            }
+            None => {
+                Err(DATError::InvalidInput("No default file is bundled for this DAT type.".into()).with_path(&path))
+            }
+        }
+    }
+
     /// Creates a new DAT file with a null-padded content bock of the specifed size in read/write mode.
     /// This will truncate an existing file if one exists at the specified path.
     ///
@@ -262,7 +423,7 @@ impl DATFile {
     ) -> Result<Self, DATError> {
         // Create a minimal content size 0 DAT file, then reopen it as a DATFile.
         {
-            let mut raw_file = File::create(&path)?;
+            let mut raw_file = File::create(&path).map_err(|err| DATError::from(err).with_path(&path))?;
             raw_file.set_len((max_size + MAX_SIZE_OFFSET) as u64)?;
             // Write header type
             raw_file.seek(SeekFrom::Start(INDEX_FILE_TYPE as u64))?;
@@ -322,7 +483,7 @@ impl DATFile {
         let max_size = get_default_max_size_for_type(&dat_type).unwrap_or(0);
         let end_byte = get_default_end_byte_for_type(&dat_type).unwrap_or(0);
         let mut dat_file = Self::create_unsafe(path, dat_type, 1, max_size, end_byte)?;
-        dat_file.write_all(&content)?;
+        dat_file.write_all(content)?;
         dat_file.seek(SeekFrom::Start(0))?;
         Ok(dat_file)
     }
@@ -407,17 +568,40 @@ impl DATFile {
     /// let mut dat_file = DATFile::open("./resources/TEST.DAT");
     /// ```
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DATError> {
-        let mut raw_file = File::open(path)?;
-        let mut header_bytes = [0u8; HEADER_SIZE as usize];
-        raw_file.read_exact(&mut header_bytes)?;
-        let (file_type, max_size, content_size, header_end_byte) = get_header_contents(&header_bytes)?;
-        Ok(DATFile {
-            content_size,
-            file_type,
-            header_end_byte,
-            max_size,
-            raw_file,
-        })
+        File::open(&path)
+            .map_err(DATError::from)
+            .and_then(Self::from_raw_file)
+            .map_err(|err| err.with_path(&path))
+    }
+
+    /// Attempts to open a DAT file in read-only mode, holding a [shared](LockMode::Shared) advisory lock
+    /// on it until the [`DATFile`](Self) is dropped. The lock is acquired before the header is read.
+    ///
+    /// If the file is exclusively locked elsewhere, acquiring the lock is retried until `timeout` has elapsed.
+    /// A zero `timeout` attempts to acquire the lock only once.
+    ///
+    /// Enabled by feature `lock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::Locked`](crate::dat_error::DATError::Locked) if the lock could not be acquired
+    /// before the timeout. Otherwise, returns the same errors as [`open()`](Self::open()).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use std::time::Duration;
+    ///
+    /// let mut dat_file = DATFile::open_locked("./resources/TEST.DAT", Duration::from_secs(1)).unwrap();
+    /// ```
+    #[cfg(feature = "lock")]
+    pub fn open_locked<P: AsRef<Path>>(path: P, timeout: Duration) -> Result<Self, DATError> {
+        File::open(&path)
+            .map_err(DATError::from)
+            .and_then(|raw_file| lock_file(raw_file, LockMode::Shared, timeout))
+            .and_then(Self::from_raw_file)
+            .map_err(|err| err.with_path(&path))
     }
 
     /// Attempts to open a DAT file using an [`OpenOptions`](std::fs::OpenOptions) builder.
@@ -443,17 +627,60 @@ impl DATFile {
     /// let mut dat_file = DATFile::open_options("./resources/TEST.DAT", &mut open_opts);
     /// ```
     pub fn open_options<P: AsRef<Path>>(path: P, options: &mut OpenOptions) -> Result<Self, DATError> {
-        let mut raw_file = options.open(path)?;
-        let mut header_bytes = [0u8; HEADER_SIZE as usize];
-        raw_file.read_exact(&mut header_bytes)?;
-        let (file_type, max_size, content_size, header_end_byte) = get_header_contents(&header_bytes)?;
-        Ok(DATFile {
-            content_size,
-            file_type,
-            header_end_byte,
-            max_size,
-            raw_file,
-        })
+        options
+            .open(&path)
+            .map_err(DATError::from)
+            .and_then(Self::from_raw_file)
+            .map_err(|err| err.with_path(&path))
+    }
+
+    /// Attempts to open a DAT file using an [`OpenOptions`](std::fs::OpenOptions) builder, holding an advisory
+    /// lock of the given [`LockMode`] on it until the [`DATFile`](Self) is dropped. The lock is acquired before
+    /// the header is read. Writable opens should use [`LockMode::Exclusive`] so that concurrent header updates
+    /// cannot interleave.
+    ///
+    /// If a conflicting lock is held elsewhere, acquiring the lock is retried until `timeout` has elapsed.
+    /// A zero `timeout` attempts to acquire the lock only once.
+    ///
+    /// Enabled by feature `lock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::Locked`](crate::dat_error::DATError::Locked) if the lock could not be acquired
+    /// before the timeout. Otherwise, returns the same errors as [`open_options()`](Self::open_options()).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::dat_error::DATError;
+    /// use libxivdat::dat_file::{DATFile, LockMode};
+    /// use std::fs::OpenOptions;
+    /// use std::time::Duration;
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("TEST.DAT");
+    /// # std::fs::copy("./resources/TEST.DAT", &path).unwrap();
+    ///
+    /// let mut open_opts = OpenOptions::new();
+    /// open_opts.read(true).write(true);
+    /// let dat_file = DATFile::open_options_locked(&path, &mut open_opts, LockMode::Exclusive, Duration::from_secs(1));
+    /// assert!(dat_file.is_ok());
+    ///
+    /// // A second writer cannot lock the file while the first is open.
+    /// let err = DATFile::open_options_locked(&path, &mut open_opts, LockMode::Exclusive, Duration::from_secs(0)).unwrap_err();
+    /// assert!(matches!(err, DATError::Locked(_)));
+    /// ```
+    #[cfg(feature = "lock")]
+    pub fn open_options_locked<P: AsRef<Path>>(
+        path: P, options: &mut OpenOptions, mode: LockMode, timeout: Duration,
+    ) -> Result<Self, DATError> {
+        options
+            .open(&path)
+            .map_err(DATError::from)
+            .and_then(|raw_file| lock_file(raw_file, mode, timeout))
+            .and_then(Self::from_raw_file)
+            .map_err(|err| err.with_path(&path))
     }
 
     /// Truncates or extends the readable content section of the DAT file.
@@ -477,13 +704,14 @@ impl DATFile {
         }
         // Check for valid size
         if new_size == 0 {
-            return Err(DATError::InvalidInput("Content size must be > 0."));
+            return Err(DATError::InvalidInput("Content size must be > 0.".into()));
         }
         if new_size > self.max_size {
-            return Err(DATError::Overflow("Content size would exceed maximum size."));
+            return Err(DATError::Overflow("Content size would exceed maximum size.".into()));
         }
         // Save pre-run cursor.
-        let pre_cursor = self.raw_file.seek(SeekFrom::Current(0))?;
+        self.discard_read_buf()?;
+        let pre_cursor = self.raw_file.stream_position()?;
         // For shrinks, fill with actual null bytes starting at new content end.
         // For grows, pad with the the content mask byte (null ^ mask) starting at old content end to new end.
         let (padding_byte, write_size) = if new_size > self.content_size {
@@ -545,11 +773,11 @@ impl DATFile {
             return Ok(());
         }
         if new_size == 0 {
-            return Err(DATError::InvalidInput("Content size must be > 0."));
+            return Err(DATError::InvalidInput("Content size must be > 0.".into()));
         }
         // Check for valid size
         if new_size < self.content_size {
-            return Err(DATError::Overflow("Content size would exceed maximum size."));
+            return Err(DATError::Overflow("Content size would exceed maximum size.".into()));
         }
         // Safe to resize
         self.raw_file.set_len((new_size + MAX_SIZE_OFFSET) as u64)?;
@@ -578,6 +806,109 @@ impl DATFile {
         Ok(self.raw_file.sync_data()?)
     }
 
+    /// Maps the entire content block of the underlying file into memory, unmasking it in place.
+    /// The file cursor is not moved.
+    ///
+    /// The map is private and copy-on-write, so unmasking never modifies the file. Content is not copied by
+    /// this library: for file types without a mask, the returned [`MappedContent`] reads directly from the
+    /// page cache, and for masked types the OS copies each page as it is unmasked. Compared to
+    /// [`read_content()`](crate::dat_file::read_content), this avoids the read buffer and its syscalls.
+    ///
+    /// The file must not be truncated or modified by another process while it is mapped.
+    ///
+    /// Enabled by feature `mmap`.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be mapped, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the file is shorter than the
+    /// content size specified in the header.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::dat_file::{read_content, DATFile};
+    ///
+    /// let dat_file = DATFile::open("./resources/TEST_XOR.DAT").unwrap();
+    /// let content = dat_file.read_content_mapped().unwrap();
+    ///
+    /// assert_eq!(&content[..], &read_content("./resources/TEST_XOR.DAT").unwrap()[..]);
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn read_content_mapped(&self) -> Result<MappedContent, DATError> {
+        let content_len = usize::try_from(self.content_size.saturating_sub(1))?;
+        // Accessing a mapped page past the end of the file is a fatal signal, not an error, so check first.
+        if self.raw_file.metadata()?.len() < u64::from(HEADER_SIZE) + content_len as u64 {
+            return Err(DATError::Underflow(
+                "File is shorter than the content size in its header.".into(),
+            ));
+        }
+        // Safety: The map is private, so writes to it never reach the file. Concurrent modification of the
+        // file is documented as unsupported.
+        let mut map = unsafe {
+            MmapOptions::new()
+                .offset(u64::from(HEADER_SIZE))
+                .len(content_len)
+                .map_copy(&self.raw_file)?
+        };
+        if let Some(mask_val) = get_mask_for_type(&self.file_type) {
+            apply_mask(&mut map, mask_val);
+        }
+        Ok(MappedContent { map })
+    }
+
+    /// Builds a [`DATFile`](Self) from an open [`std::fs::File`] by reading its header.
+    /// The file cursor is left at the start of the content block.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::FileIO`](crate::dat_error::DATError::FileIO) if the header cannot be read, or a
+    /// [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) if it cannot be validated.
+    fn from_raw_file(mut raw_file: File) -> Result<Self, DATError> {
+        let mut header_bytes = [0u8; HEADER_SIZE as usize];
+        raw_file.read_exact(&mut header_bytes)?;
+        let (file_type, max_size, content_size, header_end_byte) = get_header_contents(&header_bytes)?;
+        Ok(DATFile {
+            content_size,
+            file_type,
+            header_end_byte,
+            max_size,
+            raw_file,
+            read_buf: Vec::new(),
+            read_buf_pos: 0,
+            read_buf_start: 0,
+            write_buf: Vec::new(),
+        })
+    }
+
+    /// Drops the internal read buffer, moving the cursor of the underlying file back to the
+    /// logical position if any buffered bytes were left unread.
+    ///
+    /// # Errors
+    ///
+    /// May return a [`std::io::Error`] if one is returned by an underlying fs operation.
+    fn discard_read_buf(&mut self) -> Result<(), std::io::Error> {
+        let unread = self.read_buf.len() - self.read_buf_pos;
+        if unread > 0 {
+            self.raw_file.seek(SeekFrom::Current(-(unread as i64)))?;
+        }
+        self.read_buf.clear();
+        self.read_buf_pos = 0;
+        Ok(())
+    }
+
+    /// Copies as many unread bytes as fit from the internal read buffer into `buf`,
+    /// returning the number of bytes copied.
+    fn read_from_buf(&mut self, buf: &mut [u8]) -> usize {
+        let unread = &self.read_buf[self.read_buf_pos..];
+        let count = unread.len().min(buf.len());
+        buf[..count].copy_from_slice(&unread[..count]);
+        self.read_buf_pos += count;
+        count
+    }
+
     /// Writes a new content size value to the [`DATFile`](Self) header.
     /// This updates both the struct and the header of the file on disk.
     /// This does not modify the actual content of the file.
@@ -588,7 +919,7 @@ impl DATFile {
     ///
     /// May return a [`std::io::Error`] if one is returned by an underlying fs operation.
     fn write_content_size_header(&mut self, size: u32) -> Result<(), std::io::Error> {
-        let pre_cursor = self.raw_file.seek(SeekFrom::Current(0))?;
+        let pre_cursor = self.raw_file.stream_position()?;
         self.raw_file.seek(SeekFrom::Start(INDEX_CONTENT_SIZE as u64))?;
         self.raw_file.write_all(&size.to_le_bytes())?;
         self.raw_file.seek(SeekFrom::Start(pre_cursor))?;
@@ -606,7 +937,7 @@ impl DATFile {
     ///
     /// May return a [`std::io::Error`] if one is returned by an underlying fs operation.
     fn write_max_size_header(&mut self, size: u32) -> Result<(), std::io::Error> {
-        let pre_cursor = self.raw_file.seek(SeekFrom::Current(0))?;
+        let pre_cursor = self.raw_file.stream_position()?;
         self.raw_file.seek(SeekFrom::Start(INDEX_MAX_SIZE as u64))?;
         self.raw_file.write_all(&size.to_le_bytes())?;
         self.raw_file.seek(SeekFrom::Start(pre_cursor))?;
@@ -640,6 +971,59 @@ pub fn check_type<P: AsRef<Path>>(path: P) -> Result<DATType, DATError> {
     Ok(dat_file.file_type())
 }
 
+/// Creates a new plaintext DAT file with the default contents written by the game client for its type.
+/// This will truncate an existing file if one exists at the specified path.
+///
+/// Plaintext DAT files cannot be opened as a [`DATFile`], so this only writes the file. See
+/// [`DATFile::create_default()`](crate::dat_file::DATFile::create_default()) for binary DAT files.
+///
+/// Enabled by feature `default-dats`.
+///
+/// # Errors
+///
+/// If an I/O error creating the file occurs, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::dat_file::create_default_plaintext;
+/// use libxivdat::dat_type::PlaintextDATType;
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let temp_dir = tempdir().unwrap();
+/// # let path = temp_dir.path().join("COMMON.DAT");
+///
+/// create_default_plaintext(&path, PlaintextDATType::Common).unwrap();
+/// ```
+#[cfg(feature = "default-dats")]
+pub fn create_default_plaintext<P: AsRef<Path>>(path: P, dat_type: PlaintextDATType) -> Result<(), DATError> {
+    let default_bytes = get_default_file_for_plaintext_type(&dat_type);
+    std::fs::write(&path, default_bytes).map_err(|err| DATError::from(err).with_path(&path))
+}
+
+/// Returns the name of the character directory containing a DAT file, or `None` if the file is not
+/// in a character directory (such as the global files in the config directory itself).
+/// The path is not accessed, so the file does not need to exist.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::dat_file::get_character_dir_name;
+///
+/// let character = get_character_dir_name("config/FFXIV_CHR0040000000000000/MACRO.DAT");
+/// assert_eq!(character.as_deref(), Some("FFXIV_CHR0040000000000000"));
+/// assert_eq!(get_character_dir_name("config/MACROSYS.DAT"), None);
+/// ```
+pub fn get_character_dir_name<P: AsRef<Path>>(path: P) -> Option<String> {
+    let dir_name = path.as_ref().parent()?.file_name()?.to_string_lossy();
+    if dir_name.to_uppercase().starts_with(CHARACTER_DIR_PREFIX) {
+        Some(dir_name.into_owned())
+    } else {
+        None
+    }
+}
+
 /// Tries to read an 0x11 length byte array as a DAT file header.
 /// Returns a tuple containing (`file_type`, `max_size`, `content_size`, `header_end_byte`).
 ///
@@ -686,12 +1070,12 @@ pub fn get_header_contents(header: &[u8; HEADER_SIZE as usize]) -> Result<(DATTy
 
     // Validate that file type id bytes are present.
     if 0xff00ff00 & file_type_id > 0 {
-        return Err(DATError::BadHeader("File type ID bytes are absent."));
+        return Err(DATError::BadHeader("File type ID bytes are absent.".into()));
     }
 
     // Validate that sizes make sense.
     if content_size > max_size {
-        return Err(DATError::BadHeader("Content size exceeds max size in header."));
+        return Err(DATError::BadHeader("Content size exceeds max size in header.".into()));
     }
 
     Ok((DATType::from(file_type_id), max_size, content_size, end_byte))
@@ -724,13 +1108,45 @@ pub fn get_header_contents(header: &[u8; HEADER_SIZE as usize]) -> Result<(DATTy
 /// let dat_bytes = read_content("./resources/TEST.DAT").unwrap();
 /// ```
 pub fn read_content<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, DATError> {
-    let mut dat_file = DATFile::open(path)?;
+    let mut dat_file = DATFile::open(&path)?;
     let safe_content_size = usize::try_from(dat_file.content_size - 1)?;
     let mut buf = vec![0u8; safe_content_size];
-    dat_file.read_exact(&mut buf)?;
+    dat_file
+        .read_exact(&mut buf)
+        .map_err(|err| DATError::from(err).with_path(&path))?;
     Ok(buf)
 }
 
+/// Attempts to map the entire content block of a DAT file into memory, returning the unmasked content.
+/// This is equivalent to [`read_content()`], but uses [`DATFile::read_content_mapped()`]. The result can be
+/// parsed in place by borrowed parsers such as [`as_section_vec()`](crate::section::as_section_vec).
+///
+/// Enabled by feature `mmap`.
+///
+/// # Errors
+///
+/// If an I/O error occurs while opening or mapping the file, a
+/// [`DATError::FileIO`](crate::dat_error::DATError::FileIO) error will be returned wrapping the underlying FS error.
+///
+/// A [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) will be returned if the file header
+/// cannot be validated, indicating a non-DAT or corrupt file.
+///
+/// Returns a [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the file is shorter than the
+/// content size specified in the header.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::dat_file::read_content_mapped;
+///
+/// let dat_bytes = read_content_mapped("./resources/TEST.DAT").unwrap();
+/// ```
+#[cfg(feature = "mmap")]
+pub fn read_content_mapped<P: AsRef<Path>>(path: P) -> Result<MappedContent, DATError> {
+    let dat_file = DATFile::open(&path)?;
+    dat_file.read_content_mapped().map_err(|err| err.with_path(&path))
+}
+
 /// Attempts to write an input buffer as the content block of a DAT File,
 /// replacing the entire existing contents and returning the number of bytes written.
 /// This is a convenience function that automatically handles opening and closing the underlying file.
@@ -738,6 +1154,10 @@ pub fn read_content<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, DATError> {
 /// This will only write to an existing DAT file. Use [`DATFile::create()`](crate::dat_file::DATFile::create())
 /// to create a new DAT file.
 ///
+/// With feature `lock` enabled, an exclusive lock is held on the file for the duration of the write,
+/// waiting up to [`WRITE_CONTENT_LOCK_TIMEOUT`] for it to be released elsewhere. This keeps the header
+/// update and content write from interleaving with other locked readers or writers.
+///
 /// # Errors
 ///
 /// If an I/O error occurs while reading the file, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
@@ -749,6 +1169,9 @@ pub fn read_content<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, DATError> {
 /// A [`DATError::Overflow`](crate::dat_error::DATError::Overflow) is returned if the content
 /// would exceed the maximum size specified in the header or the maximum possible size (u32::MAX).
 ///
+/// With feature `lock` enabled, a [`DATError::Locked`](crate::dat_error::DATError::Locked) is returned if
+/// the exclusive lock could not be acquired before the timeout.
+///
 /// # Examples
 ///
 /// ```rust
@@ -765,19 +1188,61 @@ pub fn read_content<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, DATError> {
 /// write_content(&path, b"Who's awesome? You're awesome!").unwrap();
 /// ```
 pub fn write_content<P: AsRef<Path>>(path: P, buf: &[u8]) -> Result<usize, DATError> {
-    let mut dat_file = DATFile::open_options(path, OpenOptions::new().read(true).write(true))?;
+    #[cfg(feature = "lock")]
+    let mut dat_file = DATFile::open_options_locked(
+        &path,
+        OpenOptions::new().read(true).write(true),
+        LockMode::Exclusive,
+        WRITE_CONTENT_LOCK_TIMEOUT,
+    )?;
+    #[cfg(not(feature = "lock"))]
+    let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true))?;
     if let Ok(safe_content_size) = u32::try_from(buf.len() + 1) {
         if safe_content_size != dat_file.content_size() {
-            dat_file.set_content_size(safe_content_size)?;
+            dat_file
+                .set_content_size(safe_content_size)
+                .map_err(|err| err.with_path(&path))?;
         }
-        Ok(dat_file.write(&buf)?)
+        Ok(dat_file
+            .write(buf)
+            .map_err(|err| DATError::from(err).with_path(&path))?)
     } else {
         Err(DATError::Overflow(
-            "Content size would exceed maximum possible size (u32::MAX).",
+            "Content size would exceed maximum possible size (u32::MAX).".into(),
         ))
     }
 }
 
+/// Acquires an advisory lock of the given [`LockMode`] on a raw file, retrying until `timeout` has elapsed
+/// while the lock is held elsewhere. Returns the file on success.
+///
+/// # Errors
+///
+/// Returns a [`DATError::Locked`] if the lock could not be acquired before the timeout, or a
+/// [`DATError::FileIO`] if locking failed for another reason.
+#[cfg(feature = "lock")]
+fn lock_file(raw_file: File, mode: LockMode, timeout: Duration) -> Result<File, DATError> {
+    let start = Instant::now();
+    loop {
+        // Call through the trait, as newer versions of std have inherent locking methods with the same names.
+        let res = match mode {
+            LockMode::Exclusive => FileExt::try_lock_exclusive(&raw_file),
+            LockMode::Shared => FileExt::try_lock_shared(&raw_file),
+        };
+        match res {
+            Ok(()) => return Ok(raw_file),
+            Err(err) if err.raw_os_error() == fs2::lock_contended_error().raw_os_error() => {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Err(DATError::Locked("File is locked by another handle.".into()));
+                }
+                std::thread::sleep(LOCK_RETRY_INTERVAL.min(timeout - elapsed));
+            }
+            Err(err) => return Err(DATError::from(err)),
+        }
+    }
+}
+
 // --- Unit Tests
 
 #[cfg(test)]
@@ -795,6 +1260,21 @@ mod tests {
 
     // --- Module Functions
 
+    #[test]
+    fn test_read_content_error_path() -> Result<(), String> {
+        match read_content("./resources/DOES_NOT_EXIST.DAT") {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::FileIO(ref ctx) => {
+                    assert_eq!(ctx.path(), Some(Path::new("./resources/DOES_NOT_EXIST.DAT")));
+                    assert!(ctx.source().is_some());
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
     #[test]
     fn test_check_type() -> Result<(), String> {
         match check_type(TEST_XOR_PATH) {
@@ -803,6 +1283,35 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "default-dats")]
+    #[test]
+    fn test_create_default_plaintext() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let cases = [
+            (PlaintextDATType::Common, "COMMON.DAT"),
+            (PlaintextDATType::Control0, "CONTROL0.DAT"),
+            (PlaintextDATType::Control1, "CONTROL1.DAT"),
+        ];
+        for case in cases.iter() {
+            let tmp_path = tmp_dir.path().join(case.1);
+            match create_default_plaintext(&tmp_path, case.0) {
+                Ok(_) => (),
+                Err(err) => return Err(format!("Error creating file for case {}: {}", case.1, err)),
+            };
+            match (
+                std::fs::read(&tmp_path),
+                std::fs::read(Path::new("./resources/default_dats").join(case.1)),
+            ) {
+                (Ok(created), Ok(expected)) => assert_eq!(created, expected),
+                _ => return Err(format!("Error reading files for case {}.", case.1)),
+            };
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_get_header_contents() -> Result<(), String> {
         let header_bytes = [
@@ -863,6 +1372,73 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_read_content_mapped() -> Result<(), String> {
+        let cases: [(&str, &[u8]); 2] = [(TEST_PATH, TEST_CONTENTS), (TEST_XOR_PATH, TEST_XOR_CONTENTS)];
+        for (path, contents) in cases.iter() {
+            match read_content_mapped(path) {
+                Ok(content_bytes) => assert_eq!(&content_bytes[..], *contents),
+                Err(err) => return Err(format!("Read error: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_read_content_mapped_private() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST_XOR.DAT");
+        match copy(TEST_XOR_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        // Unmasking happens in the map, so the masked file on disk must be unchanged.
+        match read_content_mapped(&tmp_path) {
+            Ok(content_bytes) => assert_eq!(&content_bytes[..], TEST_XOR_CONTENTS),
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match (std::fs::read(&tmp_path), std::fs::read(TEST_XOR_PATH)) {
+            (Ok(mapped), Ok(original)) => Ok(assert_eq!(mapped, original)),
+            _ => Err("Error reading files.".to_owned()),
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_read_content_mapped_error_truncated() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        match OpenOptions::new().write(true).open(&tmp_path) {
+            Ok(file) => match file.set_len(u64::from(HEADER_SIZE) + 2) {
+                Ok(_) => (),
+                Err(err) => return Err(format!("Could not truncate temp file: {}", err)),
+            },
+            Err(err) => return Err(format!("Could not open temp file: {}", err)),
+        };
+        match read_content_mapped(&tmp_path) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Underflow(ref ctx) => {
+                    assert_eq!(ctx.path(), Some(tmp_path.as_path()));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
     #[test]
     fn test_write_content() -> Result<(), String> {
         // Make a tempfile
@@ -888,6 +1464,45 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "lock")]
+    #[test]
+    fn test_write_content_waits_for_lock() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let reader = match DATFile::open_locked(&tmp_path, Duration::from_secs(0)) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        // The write must not land while the reader holds its shared lock.
+        let reader_path = tmp_path.clone();
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            let file_bytes = std::fs::read(&reader_path);
+            drop(reader);
+            file_bytes
+        });
+        let res = write_content(&tmp_path, b"Hi!");
+        match (release.join(), std::fs::read(TEST_PATH)) {
+            (Ok(Ok(file_bytes)), Ok(original_bytes)) => assert_eq!(file_bytes, original_bytes),
+            _ => return Err("Could not read file while locked.".to_owned()),
+        };
+        match res {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing content: {}", err)),
+        };
+        match read_content(&tmp_path) {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, b"Hi!")),
+            Err(err) => Err(format!("Error reading file after write: {}", err)),
+        }
+    }
+
     // --- DATFile
 
     #[test]
@@ -934,6 +1549,84 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "lock")]
+    #[test]
+    fn test_datfile_open_locked_shared() -> Result<(), String> {
+        // Any number of shared locks may be held at once.
+        let _first = match DATFile::open_locked(TEST_PATH, Duration::from_secs(0)) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        match DATFile::open_locked(TEST_PATH, Duration::from_secs(0)) {
+            Ok(dat_file) => Ok(assert_eq!(dat_file.content_size(), TEST_CONTENTS.len() as u32 + 1)),
+            Err(err) => Err(format!("Open error: {}", err)),
+        }
+    }
+
+    #[cfg(feature = "lock")]
+    #[test]
+    fn test_datfile_open_options_locked_error_locked() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let _writer =
+            match DATFile::open_options_locked(&tmp_path, &mut opts, LockMode::Exclusive, Duration::from_secs(0)) {
+                Ok(dat_file) => dat_file,
+                Err(err) => return Err(format!("Open error: {}", err)),
+            };
+        // Both writers and readers are excluded while the exclusive lock is held.
+        match DATFile::open_options_locked(&tmp_path, &mut opts, LockMode::Exclusive, Duration::from_millis(30)) {
+            Ok(_) => return Err("No error returned for exclusive lock.".to_owned()),
+            Err(DATError::Locked(ctx)) => assert_eq!(ctx.path(), Some(tmp_path.as_path())),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+        };
+        match DATFile::open_locked(&tmp_path, Duration::from_millis(30)) {
+            Ok(_) => Err("No error returned for shared lock.".to_owned()),
+            Err(DATError::Locked(_)) => Ok(()),
+            Err(err) => Err(format!("Incorrect error: {}", err)),
+        }
+    }
+
+    #[cfg(feature = "lock")]
+    #[test]
+    fn test_datfile_open_options_locked_waits_for_release() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let writer =
+            match DATFile::open_options_locked(&tmp_path, &mut opts, LockMode::Exclusive, Duration::from_secs(0)) {
+                Ok(dat_file) => dat_file,
+                Err(err) => return Err(format!("Open error: {}", err)),
+            };
+        // The lock is released when the holder is dropped.
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            drop(writer);
+        });
+        let res = DATFile::open_options_locked(&tmp_path, &mut opts, LockMode::Exclusive, Duration::from_secs(10));
+        release.join().unwrap();
+        match res {
+            Ok(_) => Ok(()),
+            Err(err) => Err(format!("Open error: {}", err)),
+        }
+    }
+
     #[test]
     fn test_datfile_create() -> Result<(), String> {
         let tmp_dir = match tempdir() {
@@ -959,6 +1652,44 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "default-dats")]
+    #[test]
+    fn test_datfile_create_default() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("UISAVE.DAT");
+        match DATFile::create_default(&tmp_path, DATType::UISave) {
+            Ok(dat_file) => assert_eq!(dat_file.file_type(), DATType::UISave),
+            Err(err) => return Err(format!("{}", err)),
+        };
+        match (
+            std::fs::read(&tmp_path),
+            std::fs::read("./resources/default_dats/UISAVE.DAT"),
+        ) {
+            (Ok(created), Ok(expected)) => assert_eq!(created, expected),
+            _ => return Err("Error reading files.".to_owned()),
+        };
+        Ok(())
+    }
+
+    #[cfg(feature = "default-dats")]
+    #[test]
+    fn test_datfile_create_default_error_no_default() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("HOTBAR.DAT");
+        match DATFile::create_default(&tmp_path, DATType::Hotbar) {
+            Ok(_) => return Err("No error returned.".to_owned()),
+            Err(DATError::InvalidInput(ctx)) => assert_eq!(ctx.path(), Some(tmp_path.as_path())),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+        };
+        Ok(assert!(!tmp_path.exists()))
+    }
+
     #[test]
     fn test_datfile_create_with_content() -> Result<(), String> {
         let tmp_dir = match tempdir() {
@@ -1245,6 +1976,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_datfile_read_buffered_seek() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_XOR_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Open error: {}", err)),
+        };
+        let mut buf = [0u8; 2];
+        if let Err(err) = dat_file.read_exact(&mut buf) {
+            return Err(format!("Read error: {}", err));
+        }
+        assert_eq!(&buf, b"Ma");
+        // Seeks within the buffered region should not move the underlying file.
+        let raw_pos = dat_file.raw_file.stream_position().unwrap();
+        match dat_file.seek(SeekFrom::Current(-1)) {
+            Ok(pos) => assert_eq!(pos, 1),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        }
+        match dat_file.seek(SeekFrom::Start(3)) {
+            Ok(pos) => assert_eq!(pos, 3),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        }
+        assert_eq!(dat_file.raw_file.stream_position().unwrap(), raw_pos);
+        if let Err(err) = dat_file.read_exact(&mut buf) {
+            return Err(format!("Read error: {}", err));
+        }
+        assert_eq!(&buf, b"ro");
+        // Seeking from the end leaves the buffer.
+        match dat_file.seek(SeekFrom::End(-1)) {
+            Ok(pos) => assert_eq!(pos, TEST_XOR_CONTENTS.len() as u64 - 1),
+            Err(err) => return Err(format!("Seek error: {}", err)),
+        }
+        let mut end_buf = [0u8; 1];
+        match dat_file.read(&mut end_buf) {
+            Ok(count) => assert_eq!(count, 1),
+            Err(err) => return Err(format!("Read error: {}", err)),
+        }
+        Ok(assert_eq!(&end_buf, b"!"))
+    }
+
     #[test]
     fn test_datfile_seek_current() -> Result<(), String> {
         let mut dat_file = match DATFile::open(TEST_PATH) {
@@ -1345,6 +2115,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_datfile_write_after_buffered_read() -> Result<(), String> {
+        // Make a tempfile
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = tmp_dir.path().join("TEST_XOR.DAT");
+        match copy(TEST_XOR_PATH, &tmp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+        };
+        // Open tempfile
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true);
+        let mut dat_file = match DATFile::open_options(&tmp_path, &mut opts) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening temp file: {}", err)),
+        };
+        // Read part of the content, leaving the rest buffered
+        let mut buf = [0u8; 1];
+        if let Err(err) = dat_file.read_exact(&mut buf) {
+            return Err(format!("Error reading content: {}", err));
+        }
+        // Write should land directly after the bytes that were read
+        match dat_file.write(b"ee") {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing content: {}", err)),
+        };
+        match dat_file.stream_position() {
+            Ok(pos) => assert_eq!(pos, 3),
+            Err(err) => return Err(format!("Error seeking in file: {}", err)),
+        }
+        // Check content
+        match read_content(&tmp_path) {
+            Ok(content_bytes) => Ok(assert_eq!(&content_bytes, b"Meero!")),
+            Err(err) => Err(format!("Error reading file after write: {}", err)),
+        }
+    }
+
     #[test]
     fn test_datfile_write_extend_content_size() -> Result<(), String> {
         // Make a tempfile
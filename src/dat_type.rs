@@ -44,6 +44,23 @@ impl DATType {
     pub const UISAVE: DATType = DATType::UISave;
 }
 
+/// Enumeration of known FFXIV plaintext DAT file types.
+///
+/// These files are UTF-8 plaintext and have no binary header, so they cannot be
+/// identified by a [`DATType`] or opened as a [`DATFile`](crate::dat_file::DATFile).
+///
+/// Enabled by feature `default-dats`.
+#[cfg(feature = "default-dats")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaintextDATType {
+    /// COMMON.DAT
+    Common,
+    /// CONTROL0.DAT
+    Control0,
+    /// CONTROL1.DAT
+    Control1,
+}
+
 impl From<u32> for DATType {
     fn from(x: u32) -> DATType {
         match x {
@@ -91,6 +108,39 @@ pub fn get_mask_for_type(file_type: &DATType) -> Option<u8> {
     }
 }
 
+/// Applies an XOR mask in place to a byte slice, as used for the contents of binary DAT files.
+/// Masking is its own inverse, so the same call both masks and unmasks.
+///
+/// The slice is processed a `u64` word at a time, which the compiler can vectorize, with any
+/// remaining tail bytes handled individually. A mask of `0x00` is a no-op.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_type::{apply_mask, get_mask_for_type, DATType};
+///
+/// let mask = get_mask_for_type(&DATType::Macro).unwrap();
+/// let mut bytes = *b"Hello, world!";
+/// apply_mask(&mut bytes, mask);
+/// assert_eq!(bytes[0], b'H' ^ mask);
+/// apply_mask(&mut bytes, mask);
+/// assert_eq!(&bytes, b"Hello, world!");
+/// ```
+pub fn apply_mask(bytes: &mut [u8], mask: u8) {
+    if mask == 0 {
+        return;
+    }
+    let mask_word = u64::from_ne_bytes([mask; 8]);
+    let mut chunks = bytes.chunks_exact_mut(8);
+    for chunk in &mut chunks {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        chunk.copy_from_slice(&(u64::from_ne_bytes(word) ^ mask_word).to_ne_bytes());
+    }
+    for byte in chunks.into_remainder() {
+        *byte ^= mask;
+    }
+}
+
 /// Gets the default header ending byte for a given DAT type.
 /// The purpose of this value is unknown, but it is a fixed value based on file type.
 /// Returns `None` if the file is of unknown type.
@@ -140,6 +190,53 @@ pub fn get_default_max_size_for_type(file_type: &DATType) -> Option<u32> {
     }
 }
 
+/// Gets the default contents of a DAT file of a given type, as created by the game client.
+/// Returns `None` if the file is of unknown type or no default file is bundled for it.
+///
+/// Default files are bundled for every known type except [`DATType::Hotbar`]. The default
+/// `MACRO.DAT` is also used for `MACROSYS.DAT`.
+///
+/// Enabled by feature `default-dats`.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_type::{DATType, get_default_file_for_type};
+/// let default_bytes = get_default_file_for_type(&DATType::Gearset).unwrap();
+/// ```
+#[cfg(feature = "default-dats")]
+pub fn get_default_file_for_type(file_type: &DATType) -> Option<&'static [u8]> {
+    match file_type {
+        DATType::Gearset => Some(include_bytes!("../resources/default_dats/GEARSET.DAT")),
+        DATType::GoldSaucer => Some(include_bytes!("../resources/default_dats/GS.DAT")),
+        DATType::ItemFinder => Some(include_bytes!("../resources/default_dats/ITEMFDR.DAT")),
+        DATType::ItemOrder => Some(include_bytes!("../resources/default_dats/ITEMODR.DAT")),
+        DATType::Keybind => Some(include_bytes!("../resources/default_dats/KEYBIND.DAT")),
+        DATType::LogFilter => Some(include_bytes!("../resources/default_dats/LOGFLTR.DAT")),
+        DATType::Macro => Some(include_bytes!("../resources/default_dats/MACRO.DAT")),
+        DATType::RecentTells => Some(include_bytes!("../resources/default_dats/ACQ.DAT")),
+        DATType::UISave => Some(include_bytes!("../resources/default_dats/UISAVE.DAT")),
+        _ => None,
+    }
+}
+
+/// Gets the default contents of a plaintext DAT file of a given type, as created by the game client.
+///
+/// Enabled by feature `default-dats`.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_type::{PlaintextDATType, get_default_file_for_plaintext_type};
+/// let default_bytes = get_default_file_for_plaintext_type(&PlaintextDATType::Common);
+/// ```
+#[cfg(feature = "default-dats")]
+pub fn get_default_file_for_plaintext_type(file_type: &PlaintextDATType) -> &'static [u8] {
+    match file_type {
+        PlaintextDATType::Common => include_bytes!("../resources/default_dats/COMMON.DAT"),
+        PlaintextDATType::Control0 => include_bytes!("../resources/default_dats/CONTROL0.DAT"),
+        PlaintextDATType::Control1 => include_bytes!("../resources/default_dats/CONTROL1.DAT"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,6 +255,29 @@ mod tests {
         (DATType::UISAVE, "./resources/default_dats/UISAVE.DAT"),
     ];
 
+    #[test]
+    fn test_apply_mask() -> Result<(), String> {
+        // Cover empty input, tail-only input, whole words, and words with a tail.
+        for len in [0usize, 1, 7, 8, 9, 16, 61].iter() {
+            let original: Vec<u8> = (0..*len).map(|i| i as u8).collect();
+            let mut bytes = original.clone();
+            apply_mask(&mut bytes, 0x73);
+            let expected: Vec<u8> = original.iter().map(|byte| byte ^ 0x73).collect();
+            assert_eq!(bytes, expected);
+            apply_mask(&mut bytes, 0x73);
+            assert_eq!(bytes, original);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_mask_zero() -> Result<(), String> {
+        let mut bytes = [1u8, 2, 3, 4, 5, 6, 7, 8, 9];
+        apply_mask(&mut bytes, 0x00);
+        assert_eq!(bytes, [1u8, 2, 3, 4, 5, 6, 7, 8, 9]);
+        Ok(())
+    }
+
     #[test]
     fn test_from_header_bytes() -> Result<(), String> {
         for case in FILE_TYPE_MAP.iter() {
@@ -187,6 +307,22 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "default-dats")]
+    #[test]
+    fn test_get_default_file_for_type() -> Result<(), String> {
+        for case in FILE_TYPE_MAP.iter() {
+            let expected = match std::fs::read(case.1) {
+                Ok(bytes) => bytes,
+                Err(err) => return Err(format!("Error reading file for case {}: {}", case.1, err)),
+            };
+            match get_default_file_for_type(&case.0) {
+                Some(bytes) => assert_eq!(bytes, &expected[..]),
+                None => return Err(format!("No value returned for case {}.", case.1)),
+            };
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_get_default_max_size_for_type() -> Result<(), String> {
         for case in FILE_TYPE_MAP.iter() {
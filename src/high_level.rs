@@ -1,3 +1,5 @@
+use std::convert::TryInto;
+
 use crate::dat_error::DATError;
 
 /// Defines a high-level data struct that can be represented as a writeable byte vector.
@@ -39,11 +41,11 @@ pub trait Validate {
     ///
     /// ```rust
     /// use libxivdat::high_level::Validate;
+    /// use libxivdat::xiv_macro::icon::{MacroIcon, MacroIconRef};
     /// use libxivdat::xiv_macro::Macro;
     ///
     /// let a_macro = Macro {
-    ///     icon_id: "0000000".to_string(),
-    ///     icon_key: "000".to_string(),
+    ///     icon: MacroIconRef::from(MacroIcon::NoIcon),
     ///     lines: vec![String::new(); 15],
     ///     title: "Title".to_string()
     /// };
@@ -52,11 +54,11 @@ pub trait Validate {
     ///
     /// ```rust
     /// use libxivdat::high_level::Validate;
+    /// use libxivdat::xiv_macro::icon::MacroIconRef;
     /// use libxivdat::xiv_macro::Macro;
     ///
     /// let a_macro = Macro {
-    ///     icon_id: "123456".to_string(),
-    ///     icon_key: "XYZ".to_string(),
+    ///     icon: MacroIconRef::new(0xFFF, 0x123456).unwrap(),
     ///     lines: vec![String::new(); 1],
     ///     title: "Looooooooooooooooong Title".to_string()
     /// };
@@ -64,3 +66,165 @@ pub trait Validate {
     /// ```
     fn validate(&self) -> Option<DATError>;
 }
+
+/// Defines a fixed-size binary record, such as a single resource in a block-based DAT file.
+/// Records can be nested; a struct's fields are read and written in order with no implicit padding.
+///
+/// This is implemented for all integer and float primitives (as little-endian) and for arrays of records.
+/// With feature `derive`, it can be derived for structs along with [`AsBytes`], [`Validate`], and
+/// `TryFrom<&[u8]>`.
+///
+/// # Derive attributes
+///
+/// | Attribute                | Target | Description                                                             |
+/// |--------------------------|--------|-------------------------------------------------------------------------|
+/// | `#[dat(string = N)]`     | Field  | A `String` stored as `N` bytes of null-padded UTF-8.                    |
+/// | `#[dat(pad = N)]`        | Field  | Skips `N` bytes before the field. Written as `0x00`.                    |
+/// | `#[dat(size = N)]`       | Struct | Pads the record to a total of `N` bytes. Written as `0x00`.             |
+///
+/// Padding is not preserved when a record is written back. Unknown data that must survive a round trip
+/// should be kept in a byte array field instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use libxivdat::high_level::{AsBytes, DatRecord};
+/// use std::convert::TryFrom;
+///
+/// #[derive(DatRecord, Debug, PartialEq)]
+/// #[dat(size = 16)]
+/// struct Entry {
+///     #[dat(string = 8)]
+///     name: String,
+///     id: u16,
+///     #[dat(pad = 2)]
+///     values: [u8; 2],
+/// }
+///
+/// let entry = Entry { name: "Name".to_string(), id: 0x0102, values: [3, 4] };
+/// assert_eq!(Entry::SIZE, 16);
+///
+/// let bytes = entry.as_bytes().unwrap();
+/// assert_eq!(bytes, b"Name\0\0\0\0\x02\x01\0\0\x03\x04\0\0");
+/// assert_eq!(Entry::try_from(&bytes[..]).unwrap(), entry);
+/// # }
+/// ```
+pub trait DatRecord: Sized {
+    /// Size of the record in bytes.
+    const SIZE: usize;
+
+    /// Reads a record from the start of a byte slice. Any bytes beyond [`SIZE`](DatRecord::SIZE) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::Overflow`] if the slice is shorter than [`SIZE`](DatRecord::SIZE).
+    ///
+    /// Returns a [`DATError::BadEncoding`] if a string field contains invalid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::high_level::DatRecord;
+    ///
+    /// let value = <[u16; 2]>::read_record(&[0x01, 0x00, 0x02, 0x00, 0xFF]).unwrap();
+    /// assert_eq!(value, [1, 2]);
+    /// ```
+    fn read_record(bytes: &[u8]) -> Result<Self, DATError>;
+
+    /// Appends exactly [`SIZE`](DatRecord::SIZE) bytes representing the record to a buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::Overflow`] if a string field is too long for its fixed length.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::high_level::DatRecord;
+    ///
+    /// let mut buf = Vec::new();
+    /// 0x0102u16.write_record(&mut buf).unwrap();
+    /// assert_eq!(buf, [0x02, 0x01]);
+    /// ```
+    fn write_record(&self, buf: &mut Vec<u8>) -> Result<(), DATError>;
+}
+
+/// Derives [`DatRecord`], [`AsBytes`], [`Validate`], and `TryFrom<&[u8]>` for a fixed-size record struct.
+/// See [`DatRecord`] for supported attributes.
+///
+/// Enabled by feature `derive`.
+#[cfg(feature = "derive")]
+pub use libxivdat_derive::DatRecord;
+
+macro_rules! impl_dat_record_le {
+    ($($t:ty),*) => {
+        $(
+            impl DatRecord for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn read_record(bytes: &[u8]) -> Result<Self, DATError> {
+                    match bytes.get(..Self::SIZE) {
+                        Some(bytes) => Ok(<$t>::from_le_bytes(bytes.try_into()?)),
+                        None => Err(DATError::Overflow("Data buffer is too small for record size.".into())),
+                    }
+                }
+
+                fn write_record(&self, buf: &mut Vec<u8>) -> Result<(), DATError> {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_dat_record_le!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl<T: DatRecord, const N: usize> DatRecord for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn read_record(bytes: &[u8]) -> Result<Self, DATError> {
+        if bytes.len() < Self::SIZE {
+            return Err(DATError::Overflow("Data buffer is too small for record size.".into()));
+        }
+        let items = (0..N)
+            .map(|i| T::read_record(&bytes[i * T::SIZE..]))
+            .collect::<Result<Vec<T>, DATError>>()?;
+        match items.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => Err(DATError::Underflow(
+                "Record array is shorter than its declared length.".into(),
+            )),
+        }
+    }
+
+    fn write_record(&self, buf: &mut Vec<u8>) -> Result<(), DATError> {
+        for item in self.iter() {
+            item.write_record(buf)?;
+        }
+        Ok(())
+    }
+}
+
+/// Helpers used by code generated by the `DatRecord` derive. Not public API.
+#[doc(hidden)]
+pub mod __derive {
+    use crate::dat_error::DATError;
+
+    /// Reads a null-padded UTF-8 string, stopping at the first null byte.
+    pub fn read_padded_string(bytes: &[u8]) -> Result<String, DATError> {
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        Ok(String::from_utf8(bytes[..end].to_vec())?)
+    }
+
+    /// Writes a string null-padded to `len` bytes. At least one null byte is always written.
+    pub fn write_padded_string(value: &str, len: usize, buf: &mut Vec<u8>) -> Result<(), DATError> {
+        if value.len() >= len {
+            return Err(DATError::Overflow("String is too long for its fixed length.".into()));
+        }
+        buf.extend_from_slice(value.as_bytes());
+        buf.resize(buf.len() + len - value.len(), 0);
+        Ok(())
+    }
+}
@@ -1,15 +1,43 @@
+/// Contains [`MacroBook`](book::MacroBook), a full book of macro slots that records whether it is the
+/// individual (`MACRO.DAT`) or shared (`MACROSYS.DAT`) book.
+pub mod book;
+
 /// Contains [`MacroIcon`](icon::MacroIcon), an enumeration of all valid
-/// macro icons and the helper functions [`macro_icon_to_key_and_id()`](icon::macro_icon_to_key_and_id)
+/// macro icons with their names, numeric keys and ids, and texture paths, and the helper functions [`macro_icon_to_key_and_id()`](icon::macro_icon_to_key_and_id)
 /// and [`macro_icon_from_key_and_id()`](icon::macro_icon_from_key_and_id) for conversions between
 /// enum values and raw [`Section`](crate::section::Section) contents.
 pub mod icon;
 use icon::*;
 
+/// Contains [`lint_lines()`](lint::lint_lines), which checks macro lines against the behavior of the game
+/// client and reports severity-tagged [`Diagnostics`](lint::Diagnostic).
+pub mod lint;
+use lint::{lint_lines, Diagnostic};
+
+/// Contains [`parse()`](share::parse) and [`export()`](share::export), which read and write macros in the
+/// share format commonly pasted on forums and chat services.
+pub mod share;
+
+/// Contains [`Template`](template::Template), which renders macros from templates with variables,
+/// conditionals, and includes.
+pub mod template;
+
+/// Contains [`GameGlyph`](text::GameGlyph) and [`Payload`](text::Payload), which represent the game's
+/// private-use glyphs and embedded payloads (such as auto-translate phrases), and helpers for converting
+/// them to and from readable placeholders like `{HQ}`.
+pub mod text;
+
+/// Contains [`parse_line()`](script::parse_line), which parses a macro line into a whitespace-preserving
+/// [`MacroLine`](script::MacroLine) of typed [`Tokens`](script::Token), such as commands, quoted arguments,
+/// placeholders, waits, and sound effects.
+pub mod script;
+use script::{parse_line, MacroLine, Token};
+
 use crate::dat_error::DATError;
 use crate::dat_file::{check_type, DATFile};
 use crate::dat_type::DATType;
 use crate::high_level::{AsBytes, Validate};
-use crate::section::{as_section_vec, read_section, read_section_content, Section, SectionData};
+use crate::section::{as_section_vec, read_section, read_section_content, Section, SectionData, SectionIter};
 use std::path::Path;
 
 /// The number of [`Macro`] items expected in a valid macro file.
@@ -27,6 +55,31 @@ pub const SECTION_TAG_KEY: &str = "K";
 /// The [`Section`](crate::section::Section) tag for macro icon lines.
 pub const SECTION_TAG_LINE: &str = "L";
 
+/// Character limits enforced by the game client on a [`Macro`]. All lengths are measured with
+/// [`char_count()`], not in bytes.
+///
+/// The [`Default`] limits are those of the game client, as used by [`Validate`].
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::MacroLimits;
+///
+/// let limits = MacroLimits::default();
+/// assert_eq!(limits.remaining_title("Title"), 15);
+/// // Japanese text counts one character per glyph, not per byte.
+/// assert_eq!(limits.remaining_line("/p こんにちは"), 172);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MacroLimits {
+    /// The exact number of lines in a macro.
+    pub line_count: usize,
+    /// The maximum number of characters in a single line.
+    pub line_length: usize,
+    /// The maximum number of characters in a title.
+    pub title_length: usize,
+}
+
 /// Resource definition for a Final Fantasy XIV macro.
 /// [`Macro`] owns its constituent data and is returned from helper functions like [`read_macro()`].
 /// To build a section with refrences to a pre-allocated buffer, use [`MacroData`].
@@ -43,20 +96,17 @@ pub const SECTION_TAG_LINE: &str = "L";
 /// strictly enforce this pattern, and will read lines until the next title.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Macro {
-    /// The index of the icon in the GUI icon selection menu as 3 hexadecimal digits. This value must match
-    /// the [`icon_id`](Self::icon_id) to be considered valid. Use [`change_icon()`](Self::change_icon)
-    /// to update both icon-related values at once.
-    pub icon_key: String,
-    /// The index of the icon in the game data files as 7 hexadecimal digits. This value must match
-    /// the [`icon_key`](Self::icon_key) to be considered valid. Use [`change_icon()`](Self::change_icon)
-    /// to update both icon-related values at once.
-    pub icon_id: String,
+    /// The icon key (the index of the icon in the GUI icon selection menu) and icon id (the index of the icon
+    /// in the game data files). These must match a known [`MacroIcon`] to be considered valid. Use
+    /// [`change_icon()`](Self::change_icon) to set both from a [`MacroIcon`].
+    pub icon: MacroIconRef,
     /// A vector of macro lines. Macros created by the game client are always 15 lines long, even if those
     /// lines are blank. Lines must be shorter than 180 utf-8 characters. This is a character limit, not a byte limit.
     /// This library does not enforce these standards, but attempting to write a macro
     /// of a different size and use it in the game client may produce undefined behavior.
     /// Macro lines may contain an extended, FFXIV-specific character set (including
     /// game icons such as the HQ icon and item link icon). These are likely to render improperly on other platforms.
+    /// Use the [`text`] submodule to convert them to readable placeholders.
     pub lines: Vec<String>,
     /// The title of the macro. Titles have a maximum length of 20 utf-8 characters in the game client.
     /// This is a character limit, not a byte limit. Longer titles may produce undefined behavior.
@@ -79,20 +129,17 @@ pub struct Macro {
 /// strictly enforce this pattern, and will read lines until the next title.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MacroData<'a> {
-    /// The index of the icon in the GUI icon selection menu as 3 hexadecimal digits. This value must match
-    /// the [`icon_id`](Self::icon_id) to be considered valid. Use [`change_icon()`](Self::change_icon)
-    /// to update both icon-related values at once.
-    pub icon_key: &'a str,
-    /// The index of the icon in the game data files as 7 hexadecimal digits. This value must match
-    /// the [`icon_key`](Self::icon_key) to be considered valid. Use [`change_icon()`](Self::change_icon)
-    /// to update both icon-related values at once.
-    pub icon_id: &'a str,
+    /// The icon key (the index of the icon in the GUI icon selection menu) and icon id (the index of the icon
+    /// in the game data files). These must match a known [`MacroIcon`] to be considered valid. Use
+    /// [`change_icon()`](Self::change_icon) to set both from a [`MacroIcon`].
+    pub icon: MacroIconRef,
     /// A vector of macro lines. Macros created by the game client are always 15 lines long, even if those
     /// lines are blank. Lines must be shorter than 180 utf-8 characters. This is a character limit, not a byte limit.
     /// This library does not enforce these standards, but attempting to write a macro
     /// of a different size and use it in the game client may produce undefined behavior.
     /// Macro lines may contain an extended, FFXIV-specific character set (including
     /// game icons such as the HQ icon and item link icon). These are likely to render improperly on other platforms.
+    /// Use the [`text`] submodule to convert them to readable placeholders.
     pub lines: Vec<&'a str>,
     /// The title of the macro. Titles have a maximum length of 20 utf-8 characters in the game client.
     /// This is a character limit, not a byte limit. Longer titles may produce undefined behavior.
@@ -102,8 +149,7 @@ pub struct MacroData<'a> {
 impl From<&MacroData<'_>> for Macro {
     fn from(x: &MacroData) -> Self {
         Macro {
-            icon_key: x.icon_key.to_owned(),
-            icon_id: x.icon_id.to_owned(),
+            icon: x.icon,
             lines: x.lines.iter().map(|item| String::from(*item)).collect(),
             title: x.title.to_owned(),
         }
@@ -124,32 +170,19 @@ impl AsBytes for Macro {
 
 impl Validate for Macro {
     fn validate(&self) -> Option<DATError> {
-        if self.title.len() > 20 {
-            return Some(DATError::Overflow("Title is longer than 20 characters."));
-        }
-        if macro_icon_from_key_and_id(&self.icon_key, &self.icon_id).is_none() {
-            return Some(DATError::InvalidInput("Macro icon is invalid."));
-        }
-        if self.lines.len() < 15 {
-            return Some(DATError::Underflow("Macro has fewer than 15 lines."));
-        }
-        if self.lines.len() > 15 {
-            return Some(DATError::Overflow("Macro has more than 15 lines."));
-        }
-        for line in self.lines.iter() {
-            if line.len() > 180 {
-                return Some(DATError::Overflow("Line is longer than 180 characters."));
-            }
-        }
-        None
+        validate_macro(
+            &MacroLimits::default(),
+            &self.title,
+            self.get_icon(),
+            self.lines.iter().map(String::as_str),
+        )
     }
 }
 
 impl<'a> From<&'a Macro> for MacroData<'a> {
     fn from(x: &'a Macro) -> Self {
         MacroData {
-            icon_key: &x.icon_key,
-            icon_id: &x.icon_id,
+            icon: x.icon,
             lines: x.lines.iter().map(String::as_str).collect(),
             title: &x.title,
         }
@@ -170,24 +203,55 @@ impl AsBytes for MacroData<'_> {
 
 impl Validate for MacroData<'_> {
     fn validate(&self) -> Option<DATError> {
-        if self.title.len() > 20 {
-            return Some(DATError::Overflow("Title is longer than 20 characters."));
-        }
-        if macro_icon_from_key_and_id(&self.icon_key, &self.icon_id).is_none() {
-            return Some(DATError::InvalidInput("Macro icon is invalid."));
-        }
-        if self.lines.len() < 15 {
-            return Some(DATError::Underflow("Macro has fewer than 15 lines."));
-        }
-        if self.lines.len() > 15 {
-            return Some(DATError::Overflow("Macro has more than 15 lines."));
-        }
-        for line in self.lines.iter() {
-            if line.len() > 180 {
-                return Some(DATError::Overflow("Line is longer than 180 characters."));
-            }
+        validate_macro(
+            &MacroLimits::default(),
+            self.title,
+            self.get_icon(),
+            self.lines.iter().copied(),
+        )
+    }
+}
+
+impl Default for MacroLimits {
+    fn default() -> Self {
+        MacroLimits {
+            line_count: 15,
+            line_length: 180,
+            title_length: 20,
         }
-        None
+    }
+}
+
+impl MacroLimits {
+    /// Returns the number of characters that can still be added to a line before it reaches
+    /// [`line_length`](Self::line_length). Returns 0 if the line is already too long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroLimits;
+    ///
+    /// let limits = MacroLimits::default();
+    /// assert_eq!(limits.remaining_line("/ac Sprint"), 170);
+    /// assert_eq!(limits.remaining_line(&"X".repeat(200)), 0);
+    /// ```
+    pub fn remaining_line(&self, line: &str) -> usize {
+        self.line_length.saturating_sub(char_count(line))
+    }
+
+    /// Returns the number of characters that can still be added to a title before it reaches
+    /// [`title_length`](Self::title_length). Returns 0 if the title is already too long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroLimits;
+    ///
+    /// let limits = MacroLimits::default();
+    /// assert_eq!(limits.remaining_title("Opener"), 14);
+    /// ```
+    pub fn remaining_title(&self, title: &str) -> usize {
+        self.title_length.saturating_sub(char_count(title))
     }
 }
 
@@ -219,8 +283,8 @@ impl Macro {
     pub fn as_sections(&self) -> Result<Vec<Section>, DATError> {
         let mut sec_vec = vec![
             Section::new(SECTION_TAG_TITLE.to_owned(), String::from(&self.title))?,
-            Section::new(SECTION_TAG_ICON.to_owned(), String::from(&self.icon_id))?,
-            Section::new(SECTION_TAG_KEY.to_owned(), String::from(&self.icon_key))?,
+            Section::new(SECTION_TAG_ICON.to_owned(), self.icon.icon_id_hex().to_owned())?,
+            Section::new(SECTION_TAG_KEY.to_owned(), self.icon.key_hex().to_owned())?,
         ];
         for line in self.lines.iter() {
             sec_vec.push(Section::new(SECTION_TAG_LINE.to_owned(), String::from(line))?);
@@ -228,8 +292,7 @@ impl Macro {
         Ok(sec_vec)
     }
 
-    /// Changes the [`icon_key`](Self.icon_key) and [`icon_id`](Self.icon_id) to a valid pair based on an input
-    /// [`MacroIcon`].
+    /// Changes the [`icon`](Self::icon) key and id to the valid pair for a [`MacroIcon`].
     ///
     /// # Examples
     ///
@@ -243,17 +306,45 @@ impl Macro {
     ///     MacroIcon::NoIcon
     /// ).unwrap();
     ///
-    /// assert_eq!(a_macro.icon_id, "0000000");
-    /// assert_eq!(a_macro.icon_key, "000");
+    /// assert_eq!(a_macro.icon.icon_id(), 0x0000000);
+    /// assert_eq!(a_macro.icon.key(), 0x000);
     ///
     /// a_macro.change_icon(MacroIcon::SymbolArrowUp);
-    /// assert_eq!(a_macro.icon_id, "00102FF");
-    /// assert_eq!(a_macro.icon_key, "037");
+    /// assert_eq!(a_macro.icon.icon_id(), 0x00102FF);
+    /// assert_eq!(a_macro.icon.key(), 0x037);
     /// ```
     pub fn change_icon(&mut self, icon: MacroIcon) {
-        let (key, id) = macro_icon_to_key_and_id(&icon);
-        self.icon_key = key.to_owned();
-        self.icon_id = id.to_owned();
+        self.icon = MacroIconRef::from(icon);
+    }
+
+    /// Returns the icon the game client displays for the macro. If the first line is a `/micon` (or `/macroicon`)
+    /// command with a name, this is an [`EffectiveIcon::Micon`]. Otherwise, it is the [`MacroIcon`] chosen via GUI.
+    /// Returns [`None`] if there is no `/micon` line and the icon key and id are not a known valid icon.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::icon::{EffectiveIcon, MacroIcon, MiconKind};
+    ///
+    /// let mut a_macro = Macro::new(
+    ///     "Title".to_string(),
+    ///     vec!["/micon \"Potion\" item".to_string(), "/item Potion".to_string()],
+    ///     MacroIcon::DefaultIcon
+    /// ).unwrap();
+    /// assert_eq!(
+    ///     a_macro.effective_icon(),
+    ///     Some(EffectiveIcon::Micon { kind: MiconKind::Item, name: "Potion".to_string() })
+    /// );
+    ///
+    /// a_macro.lines[0] = String::new();
+    /// assert_eq!(a_macro.effective_icon(), Some(EffectiveIcon::Icon(MacroIcon::DefaultIcon)));
+    /// ```
+    pub fn effective_icon(&self) -> Option<EffectiveIcon> {
+        match self.lines.first().and_then(|line| micon_from_line(line)) {
+            Some(micon) => Some(micon),
+            None => self.get_icon().map(EffectiveIcon::Icon),
+        }
     }
 
     /// Builds a [`Macro`] from a [`Vec`] of [`Sections`](crate::section::Section).
@@ -316,8 +407,9 @@ impl Macro {
     ///
     /// # Errors
     ///
-    /// Returns [`DATError::InvalidInput`] if the sections are not provided in the order described above
-    /// or any sections are missing.
+    /// Returns [`DATError::InvalidInput`] if the sections are not provided in the order described above,
+    /// any sections are missing, or the icon key and id are not 3 and 7 hex digits. A well-formed key and id
+    /// that do not match a known [`MacroIcon`] are kept as-is.
     ///
     /// # Examples
     ///
@@ -338,36 +430,36 @@ impl Macro {
     /// ```
     pub fn from_sections_unsafe(sections: Vec<Section>) -> Result<Macro, DATError> {
         if sections.len() < 4 {
-            return Err(DATError::InvalidInput("Macros require a minimum of 4 sections."));
+            return Err(DATError::InvalidInput("Macros require a minimum of 4 sections.".into()));
         }
         if sections[0].tag != SECTION_TAG_TITLE {
-            return Err(DATError::InvalidInput("First section was not a Title (T) section."));
+            return Err(DATError::InvalidInput(
+                "First section was not a Title (T) section.".into(),
+            ));
         }
         let title = String::from(&sections[0].content);
         if sections[1].tag != SECTION_TAG_ICON {
-            return Err(DATError::InvalidInput("Second section was not a Icon (I) section."));
+            return Err(DATError::InvalidInput(
+                "Second section was not a Icon (I) section.".into(),
+            ));
         }
-        let icon_id = String::from(&sections[1].content);
         if sections[2].tag != SECTION_TAG_KEY {
-            return Err(DATError::InvalidInput("Third section was not a Key (K) section."));
+            return Err(DATError::InvalidInput(
+                "Third section was not a Key (K) section.".into(),
+            ));
         }
-        let icon_key = String::from(&sections[2].content);
+        let icon = MacroIconRef::from_hex(&sections[2].content, &sections[1].content)?;
         let mut lines = Vec::<String>::new();
         for line in sections[3..].iter() {
             if line.tag != SECTION_TAG_LINE {
-                return Err(DATError::InvalidInput("Non-line (L) section in lines block."));
+                return Err(DATError::InvalidInput("Non-line (L) section in lines block.".into()));
             }
             lines.push(line.content.to_owned());
         }
-        Ok(Macro {
-            icon_key,
-            icon_id,
-            lines,
-            title,
-        })
+        Ok(Macro { icon, lines, title })
     }
 
-    /// Gets the [`MacroIcon`] correpsonding to the current [`icon_key`](Self.icon_key) and [`icon_id`](Self.icon_id).
+    /// Gets the [`MacroIcon`] correpsonding to the current [`icon`](Self::icon) key and id.
     /// Returns [`None`] if the id and key do not correspond to a known valid icon.
     ///
     /// # Examples
@@ -384,7 +476,70 @@ impl Macro {
     /// assert_eq!(a_macro.get_icon().unwrap(), MacroIcon::SymbolArrowUp);
     /// ```
     pub fn get_icon(&self) -> Option<MacroIcon> {
-        macro_icon_from_key_and_id(&self.icon_key, &self.icon_id)
+        self.icon.icon()
+    }
+
+    /// Returns true if the title and all lines are blank, as in an unused macro slot. The icon is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let a_macro = Macro::new(String::new(), vec![], MacroIcon::DefaultIcon).unwrap();
+    /// assert!(a_macro.is_empty());
+    ///
+    /// let a_macro = Macro::new(String::new(), vec!["/echo".to_string()], MacroIcon::DefaultIcon).unwrap();
+    /// assert!(!a_macro.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.lines.iter().all(|line| line.is_empty())
+    }
+
+    /// Checks the macro's lines against the behavior of the game client. See [`lint_lines()`](lint::lint_lines).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    /// use libxivdat::xiv_macro::lint::LintKind;
+    ///
+    /// let a_macro = Macro::new(
+    ///     "Title".to_string(),
+    ///     vec!["/ac Sprint <wait.1> <wait.2>".to_string()],
+    ///     MacroIcon::DefaultIcon
+    /// ).unwrap();
+    ///
+    /// let diagnostics = a_macro.lint();
+    /// assert_eq!(diagnostics[0].line, 1);
+    /// assert_eq!(diagnostics[0].kind, LintKind::MultipleWaits(2));
+    /// ```
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        lint_lines(&self.lines)
+    }
+
+    /// Parses each line of the macro into a [`MacroLine`]. See [`parse_line()`](script::parse_line).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let a_macro = Macro::new(
+    ///     "Title".to_string(),
+    ///     vec!["/ac \"Fire IV\" <t> <wait.3>".to_string()],
+    ///     MacroIcon::DefaultIcon
+    /// ).unwrap();
+    ///
+    /// let script = a_macro.parse_lines();
+    /// assert_eq!(script[0].command(), Some("ac"));
+    /// assert_eq!(script[0].wait(), Some(3));
+    /// ```
+    pub fn parse_lines(&self) -> Vec<MacroLine> {
+        self.lines.iter().map(|line| parse_line(line)).collect()
     }
 
     /// Builds a new [`Macro`] with a given title, [`MacroIcon`], and content.
@@ -417,7 +572,6 @@ impl Macro {
     /// assert_eq!(a_macro.get_icon().unwrap(), MacroIcon::SymbolCircle);
     /// ```
     pub fn new(title: String, lines: Vec<String>, icon: MacroIcon) -> Result<Macro, DATError> {
-        let (icon_key, icon_id) = macro_icon_to_key_and_id(&icon);
         let mut padded_lines = lines.clone();
         if lines.len() < 15 {
             for line in std::iter::repeat(String::new()).take(15 - lines.len()) {
@@ -425,8 +579,7 @@ impl Macro {
             }
         }
         let res_macro = Macro {
-            icon_id: icon_id.to_owned(),
-            icon_key: icon_key.to_owned(),
+            icon: MacroIconRef::from(icon),
             lines: padded_lines,
             title,
         };
@@ -435,6 +588,76 @@ impl Macro {
             None => Ok(res_macro),
         }
     }
+
+    /// Sets the icon via a `/micon` command on the first line. If the first line is already a `/micon`
+    /// (or `/macroicon`) command, it is rewritten. Otherwise, a new line is inserted before all other lines,
+    /// and a trailing blank line is removed if needed to keep the line count. Other lines are not modified.
+    ///
+    /// The [`MiconKind`] argument is omitted for [`MiconKind::Action`], as it is the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the name contains a double quote.
+    ///
+    /// Returns [`DATError::Overflow`] if the `/micon` line would exceed the client's line length, or if a
+    /// line must be inserted into a full macro whose last line is not blank.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::icon::{MacroIcon, MiconKind};
+    ///
+    /// let mut a_macro = Macro::new(
+    ///     "Title".to_string(),
+    ///     vec!["/ac Sprint".to_string()],
+    ///     MacroIcon::DefaultIcon
+    /// ).unwrap();
+    ///
+    /// a_macro.set_micon(MiconKind::Action, "Sprint").unwrap();
+    /// assert_eq!(a_macro.lines[0], "/micon \"Sprint\"");
+    /// assert_eq!(a_macro.lines[1], "/ac Sprint");
+    /// assert_eq!(a_macro.lines.len(), 15);
+    ///
+    /// a_macro.set_micon(MiconKind::Item, "Potion").unwrap();
+    /// assert_eq!(a_macro.lines[0], "/micon \"Potion\" item");
+    /// assert_eq!(a_macro.lines[1], "/ac Sprint");
+    /// ```
+    pub fn set_micon(&mut self, kind: MiconKind, name: &str) -> Result<(), DATError> {
+        if name.contains('"') {
+            return Err(DATError::InvalidInput("Micon names cannot contain quotes.".into()));
+        }
+        let line = match kind {
+            MiconKind::Action => format!("/micon \"{}\"", name),
+            _ => format!("/micon \"{}\" {}", name, kind),
+        };
+        let limits = MacroLimits::default();
+        if char_count(&line) > limits.line_length {
+            return Err(DATError::Overflow(
+                format!("Line is longer than {} characters.", limits.line_length).into(),
+            ));
+        }
+        if let Some(first) = self.lines.first_mut() {
+            if is_micon_command(&parse_line(first)) {
+                *first = line;
+                return Ok(());
+            }
+        }
+        if self.lines.len() >= limits.line_count {
+            match self.lines.last() {
+                Some(last) if last.is_empty() => {
+                    self.lines.pop();
+                }
+                _ => {
+                    return Err(DATError::Overflow(
+                        "Macro has no blank line to make room for /micon.".into(),
+                    ))
+                }
+            }
+        }
+        self.lines.insert(0, line);
+        Ok(())
+    }
 }
 
 impl<'a> MacroData<'a> {
@@ -462,11 +685,11 @@ impl<'a> MacroData<'a> {
     ///
     /// assert_eq!(sections[0].content, "Title");
     /// ```
-    pub fn as_section_data(&self) -> Result<Vec<SectionData>, DATError> {
+    pub fn as_section_data(&self) -> Result<Vec<SectionData<'_>>, DATError> {
         let mut sec_vec = vec![
             SectionData::new(SECTION_TAG_TITLE, self.title)?,
-            SectionData::new(SECTION_TAG_ICON, self.icon_id)?,
-            SectionData::new(SECTION_TAG_KEY, self.icon_key)?,
+            SectionData::new(SECTION_TAG_ICON, self.icon.icon_id_hex())?,
+            SectionData::new(SECTION_TAG_KEY, self.icon.key_hex())?,
         ];
         for line in self.lines.iter() {
             sec_vec.push(SectionData::new(SECTION_TAG_LINE, line)?);
@@ -474,8 +697,7 @@ impl<'a> MacroData<'a> {
         Ok(sec_vec)
     }
 
-    /// Changes the [`icon_key`](Self.icon_key) and [`icon_id`](Self.icon_id) to a valid pair based on an input
-    /// [`MacroIcon`].
+    /// Changes the [`icon`](Self::icon) key and id to the valid pair for a [`MacroIcon`].
     ///
     /// # Examples
     ///
@@ -489,17 +711,40 @@ impl<'a> MacroData<'a> {
     ///     &MacroIcon::NoIcon
     /// ).unwrap();
     ///
-    /// assert_eq!(a_macro.icon_id, "0000000");
-    /// assert_eq!(a_macro.icon_key, "000");
+    /// assert_eq!(a_macro.icon.icon_id(), 0x0000000);
+    /// assert_eq!(a_macro.icon.key(), 0x000);
     ///
     /// a_macro.change_icon(&MacroIcon::SymbolArrowUp);
-    /// assert_eq!(a_macro.icon_id, "00102FF");
-    /// assert_eq!(a_macro.icon_key, "037");
+    /// assert_eq!(a_macro.icon.icon_id(), 0x00102FF);
+    /// assert_eq!(a_macro.icon.key(), 0x037);
     /// ```
-    pub fn change_icon(&mut self, icon: &'a MacroIcon) {
-        let (key, id) = macro_icon_to_key_and_id(&icon);
-        self.icon_key = key;
-        self.icon_id = id;
+    pub fn change_icon(&mut self, icon: &MacroIcon) {
+        self.icon = MacroIconRef::from(icon);
+    }
+
+    /// Returns the icon the game client displays for the macro. See [`Macro::effective_icon()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroData;
+    /// use libxivdat::xiv_macro::icon::{EffectiveIcon, MacroIcon, MiconKind};
+    ///
+    /// let a_macro = MacroData::new(
+    ///     "Title",
+    ///     vec!["/micon \"Fire IV\"", "/ac \"Fire IV\""],
+    ///     &MacroIcon::DefaultIcon
+    /// ).unwrap();
+    /// assert_eq!(
+    ///     a_macro.effective_icon(),
+    ///     Some(EffectiveIcon::Micon { kind: MiconKind::Action, name: "Fire IV".to_string() })
+    /// );
+    /// ```
+    pub fn effective_icon(&self) -> Option<EffectiveIcon> {
+        match self.lines.first().and_then(|line| micon_from_line(line)) {
+            Some(micon) => Some(micon),
+            None => self.get_icon().map(EffectiveIcon::Icon),
+        }
     }
 
     /// Builds a [`MacroData`] from a [`Vec`] of [`SectionData`](crate::section::SectionData).
@@ -562,7 +807,9 @@ impl<'a> MacroData<'a> {
     ///
     /// # Errors
     ///
-    /// Returns [`DATError::InvalidInput`] if the sections are not provided in the order described above.
+    /// Returns [`DATError::InvalidInput`] if the sections are not provided in the order described above,
+    /// or the icon key and id are not 3 and 7 hex digits. A well-formed key and id that do not match a
+    /// known [`MacroIcon`] are kept as-is.
     ///
     /// # Examples
     ///
@@ -583,36 +830,36 @@ impl<'a> MacroData<'a> {
     /// ```
     pub fn from_section_data_unsafe(sections: Vec<SectionData>) -> Result<MacroData, DATError> {
         if sections.len() < 4 {
-            return Err(DATError::InvalidInput("Macros require a minimum of 4 sections."));
+            return Err(DATError::InvalidInput("Macros require a minimum of 4 sections.".into()));
         }
         if sections[0].tag != SECTION_TAG_TITLE {
-            return Err(DATError::InvalidInput("First section was not a Title (T) section."));
+            return Err(DATError::InvalidInput(
+                "First section was not a Title (T) section.".into(),
+            ));
         }
         let title = sections[0].content;
         if sections[1].tag != SECTION_TAG_ICON {
-            return Err(DATError::InvalidInput("Second section was not a Icon (I) section."));
+            return Err(DATError::InvalidInput(
+                "Second section was not a Icon (I) section.".into(),
+            ));
         }
-        let icon_id = sections[1].content;
         if sections[2].tag != SECTION_TAG_KEY {
-            return Err(DATError::InvalidInput("Third section was not a Key (K) section."));
+            return Err(DATError::InvalidInput(
+                "Third section was not a Key (K) section.".into(),
+            ));
         }
-        let icon_key = sections[2].content;
+        let icon = MacroIconRef::from_hex(sections[2].content, sections[1].content)?;
         let mut lines = Vec::<&str>::new();
         for line in sections[3..].iter() {
             if line.tag != "L" {
-                return Err(DATError::InvalidInput("Non-line (L) section in lines block."));
+                return Err(DATError::InvalidInput("Non-line (L) section in lines block.".into()));
             }
             lines.push(line.content);
         }
-        Ok(MacroData {
-            icon_key,
-            icon_id,
-            lines,
-            title,
-        })
+        Ok(MacroData { icon, lines, title })
     }
 
-    /// Gets the [`MacroIcon`] correpsonding to the current [`icon_key`](Self.icon_key) and [`icon_id`](Self.icon_id).
+    /// Gets the [`MacroIcon`] correpsonding to the current [`icon`](Self::icon) key and id.
     /// Returns [`None`] if the id and key do not correspond to a known valid icon.
     ///
     /// # Examples
@@ -629,7 +876,70 @@ impl<'a> MacroData<'a> {
     /// assert_eq!(a_macro.get_icon().unwrap(), MacroIcon::SymbolArrowUp);
     /// ```
     pub fn get_icon(&self) -> Option<MacroIcon> {
-        macro_icon_from_key_and_id(&self.icon_key, &self.icon_id)
+        self.icon.icon()
+    }
+
+    /// Returns true if the title and all lines are blank, as in an unused macro slot. The icon is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroData;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let a_macro = MacroData::new("", vec![], &MacroIcon::DefaultIcon).unwrap();
+    /// assert!(a_macro.is_empty());
+    ///
+    /// let a_macro = MacroData::new("Title", vec![], &MacroIcon::DefaultIcon).unwrap();
+    /// assert!(!a_macro.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.lines.iter().all(|line| line.is_empty())
+    }
+
+    /// Checks the macro's lines against the behavior of the game client. See [`lint_lines()`](lint::lint_lines).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroData;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    /// use libxivdat::xiv_macro::lint::LintKind;
+    ///
+    /// let a_macro = MacroData::new(
+    ///     "Title",
+    ///     vec!["/ac Sprint", "/micon Sprint"],
+    ///     &MacroIcon::DefaultIcon
+    /// ).unwrap();
+    ///
+    /// let diagnostics = a_macro.lint();
+    /// assert_eq!(diagnostics[0].line, 2);
+    /// assert_eq!(diagnostics[0].kind, LintKind::MiconNotFirst);
+    /// ```
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        lint_lines(&self.lines)
+    }
+
+    /// Parses each line of the macro into a [`MacroLine`]. See [`parse_line()`](script::parse_line).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::MacroData;
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let a_macro = MacroData::new(
+    ///     "Title",
+    ///     vec!["/echo Ready! <se.1>"],
+    ///     &MacroIcon::DefaultIcon
+    /// ).unwrap();
+    ///
+    /// let script = a_macro.parse_lines();
+    /// assert_eq!(script[0].command(), Some("echo"));
+    /// assert_eq!(script[0].to_string(), "/echo Ready! <se.1>");
+    /// ```
+    pub fn parse_lines(&self) -> Vec<MacroLine> {
+        self.lines.iter().map(|line| parse_line(line)).collect()
     }
 
     /// Builds a new [`MacroData`] with a given title, [`MacroIcon`], and content.
@@ -668,7 +978,6 @@ impl<'a> MacroData<'a> {
     /// assert_eq!(a_macro.get_icon().unwrap(), MacroIcon::SymbolCircle);
     /// ```
     pub fn new(title: &'a str, lines: Vec<&'a str>, icon: &'a MacroIcon) -> Result<MacroData<'a>, DATError> {
-        let (icon_key, icon_id) = macro_icon_to_key_and_id(&icon);
         let mut padded_lines = lines.clone();
         if lines.len() < 15 {
             for line in std::iter::repeat("").take(15 - lines.len()) {
@@ -676,8 +985,7 @@ impl<'a> MacroData<'a> {
             }
         }
         let res_macro = MacroData {
-            icon_id,
-            icon_key,
+            icon: MacroIconRef::from(icon),
             lines: padded_lines,
             title,
         };
@@ -714,7 +1022,7 @@ impl<'a> MacroData<'a> {
 /// assert_eq!(macro_data.lines[0], "DefaultIcon");
 /// assert_eq!(macro_data.get_icon().unwrap(), MacroIcon::DefaultIcon);
 /// ```
-pub fn as_macro(bytes: &[u8]) -> Result<MacroData, DATError> {
+pub fn as_macro(bytes: &[u8]) -> Result<MacroData<'_>, DATError> {
     let sec_vec = as_section_vec(bytes)?;
     MacroData::from_section_data_unsafe(sec_vec)
 }
@@ -746,11 +1054,30 @@ pub fn as_macro(bytes: &[u8]) -> Result<MacroData, DATError> {
 /// assert_eq!(macro_data_vec[1].lines[0], "DPS1");
 /// assert_eq!(macro_data_vec[1].get_icon().unwrap(), MacroIcon::DPS1);
 /// ```
-pub fn as_macro_vec(bytes: &[u8]) -> Result<Vec<MacroData>, DATError> {
-    let sections = as_section_vec(bytes)?;
-    let mut macro_vec = Vec::<MacroData>::new();
+///
+/// With feature `mmap`, the content can be read through a memory map with
+/// [`read_content_mapped()`](crate::dat_file::read_content_mapped). The returned [`MacroData`]
+/// borrow from the mapped content; owned [`Macros`](Macro) only need to be built for the ones that are kept.
+///
+/// ```rust
+/// # #[cfg(feature = "mmap")]
+/// # {
+/// use libxivdat::dat_file::read_content_mapped;
+/// use libxivdat::xiv_macro::{as_macro_vec, Macro};
+///
+/// let content_bytes = read_content_mapped("./resources/TEST_MACRO.DAT").unwrap();
+/// let macro_data_vec = as_macro_vec(&content_bytes).unwrap();
+/// let kept: Vec<Macro> = macro_data_vec.iter().filter(|m| m.title == "1").map(Macro::from).collect();
+///
+/// assert_eq!(kept.len(), 1);
+/// assert_eq!(kept[0].lines[0], "DPS1");
+/// # }
+/// ```
+pub fn as_macro_vec(bytes: &[u8]) -> Result<Vec<MacroData<'_>>, DATError> {
+    let mut macro_vec = Vec::<MacroData>::with_capacity(EXPECTED_ITEM_COUNT);
     let mut sec_vec = Vec::<SectionData>::new();
-    for next_section in sections.into_iter() {
+    for next_section in SectionIter::new(bytes) {
+        let (_, next_section) = next_section?;
         // Push a new macro on every title
         if next_section.tag == SECTION_TAG_TITLE {
             if !sec_vec.is_empty() {
@@ -794,7 +1121,7 @@ pub fn as_writeable_bytes(macros: &[MacroData]) -> Result<Vec<u8>, DATError> {
     let macros_len = macros.len();
     if macros_len > EXPECTED_ITEM_COUNT {
         return Err(DATError::Overflow(
-            "A valid macro file cannot contain more than 100 macros.",
+            "A valid macro file cannot contain more than 100 macros.".into(),
         ));
     }
     if macros_len < EXPECTED_ITEM_COUNT {
@@ -836,6 +1163,46 @@ pub fn as_writeable_bytes_unsafe(macros: &[MacroData]) -> Result<Vec<u8>, DATErr
     Ok(res_vec)
 }
 
+/// Counts the characters in a title or line the way the game client does.
+///
+/// Each unicode character counts as one, including the game's private-use glyphs such as the HQ icon.
+/// An embedded payload, such as an auto-translate phrase, counts as a single character. Its extent is
+/// taken from its encoded length (see [`text`]), as its data may itself contain `0x03`. A malformed
+/// payload counts each of its characters.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::char_count;
+///
+/// assert_eq!(char_count("Fire IV"), 7);
+/// assert_eq!(char_count("ファイガ"), 4);
+/// // HQ icon glyph
+/// assert_eq!(char_count("Potion\u{E03C}"), 7);
+/// // Auto-translate payload
+/// assert_eq!(char_count("Hi \u{2}\u{2E}\u{1}\u{3}"), 4);
+/// assert_eq!(char_count("\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}"), 1);
+/// ```
+pub fn char_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut rest = text;
+    while let Some(len) = next_char_len(rest) {
+        count += 1;
+        rest = &rest[len..];
+    }
+    count
+}
+
+/// Returns the length in bytes of the first character of the text as counted by [`char_count()`],
+/// or `None` if the text is empty.
+fn next_char_len(text: &str) -> Option<usize> {
+    let next = text.chars().next()?;
+    Some(match next {
+        '\u{2}' => text::payload_len(text.as_bytes()).unwrap_or(1),
+        _ => next.len_utf8(),
+    })
+}
+
 /// Reads the next [`Macro`] from a [`DATFile`](crate::dat_file::DATFile).
 ///
 /// # Errors
@@ -865,7 +1232,7 @@ pub fn as_writeable_bytes_unsafe(macros: &[MacroData]) -> Result<Vec<u8>, DATErr
 pub fn read_macro(dat_file: &mut DATFile) -> Result<Macro, DATError> {
     if dat_file.file_type() != DATType::Macro {
         Err(DATError::IncorrectType(
-            "Attempted to read a macro from a non-macro file.",
+            "Attempted to read a macro from a non-macro file.".into(),
         ))
     } else {
         Ok(read_macro_unsafe(dat_file)?)
@@ -914,7 +1281,7 @@ pub fn read_macro(dat_file: &mut DATFile) -> Result<Macro, DATError> {
 pub fn read_macro_content<P: AsRef<Path>>(path: P) -> Result<Vec<Macro>, DATError> {
     if check_type(&path)? != DATType::Macro {
         Err(DATError::IncorrectType(
-            "Attempted to read a macro from a non-macro file.",
+            "Attempted to read a macro from a non-macro file.".into(),
         ))
     } else {
         Ok(read_macro_content_unsafe(path)?)
@@ -1002,14 +1369,14 @@ pub fn read_macro_unsafe(dat_file: &mut DATFile) -> Result<Macro, DATError> {
 /// assert_eq!(macro_contents[1].get_icon().unwrap(), MacroIcon::DPS1);
 /// ```
 pub fn read_macro_content_unsafe<P: AsRef<Path>>(path: P) -> Result<Vec<Macro>, DATError> {
-    let sections = read_section_content(path)?;
+    let sections = read_section_content(&path)?;
     let mut macro_vec = Vec::<Macro>::new();
     let mut sec_vec = Vec::<Section>::new();
     for next_section in sections.into_iter() {
         // Push a new macro on every title
         if next_section.tag == SECTION_TAG_TITLE {
             if !sec_vec.is_empty() {
-                macro_vec.push(Macro::from_sections_unsafe(sec_vec)?);
+                macro_vec.push(Macro::from_sections_unsafe(sec_vec).map_err(|err| err.with_path(&path))?);
             }
             sec_vec = Vec::<Section>::new();
         }
@@ -1017,7 +1384,7 @@ pub fn read_macro_content_unsafe<P: AsRef<Path>>(path: P) -> Result<Vec<Macro>,
     }
     // Push last macro
     if !sec_vec.is_empty() {
-        macro_vec.push(Macro::from_sections_unsafe(sec_vec)?);
+        macro_vec.push(Macro::from_sections_unsafe(sec_vec).map_err(|err| err.with_path(&path))?);
     }
     Ok(macro_vec)
 }
@@ -1049,7 +1416,7 @@ pub fn to_writeable_bytes(macros: &[Macro]) -> Result<Vec<u8>, DATError> {
     let macros_len = macros.len();
     if macros_len > EXPECTED_ITEM_COUNT {
         return Err(DATError::Overflow(
-            "A valid macro file cannot contain more than 100 macros.",
+            "A valid macro file cannot contain more than 100 macros.".into(),
         ));
     }
     if macros_len < EXPECTED_ITEM_COUNT {
@@ -1094,6 +1461,64 @@ pub fn to_writeable_bytes_unsafe(macros: &[Macro]) -> Result<Vec<u8>, DATError>
     Ok(res_vec)
 }
 
+/// Returns true if a parsed line is a `/micon` or `/macroicon` command.
+fn is_micon_command(line: &MacroLine) -> bool {
+    line.command().map_or(false, |name| {
+        name.eq_ignore_ascii_case("micon") || name.eq_ignore_ascii_case("macroicon")
+    })
+}
+
+/// Parses a `/micon "Name" [kind]` line into an [`EffectiveIcon::Micon`]. Returns [`None`] if the line is not
+/// a `/micon` command or has no name.
+fn micon_from_line(line: &str) -> Option<EffectiveIcon> {
+    let parsed = parse_line(line);
+    if !is_micon_command(&parsed) {
+        return None;
+    }
+    let mut args = parsed.arguments();
+    let name = match args.next()? {
+        Token::Quoted(name) | Token::Text(name) => name.to_owned(),
+        _ => return None,
+    };
+    let kind = match args.next() {
+        Some(Token::Text(kind)) => MiconKind::from_name(kind),
+        _ => MiconKind::Action,
+    };
+    Some(EffectiveIcon::Micon { kind, name })
+}
+
+/// Checks a macro's fields against a set of [`MacroLimits`] and its icon against the known [`MacroIcons`](MacroIcon).
+fn validate_macro<'a>(
+    limits: &MacroLimits, title: &str, icon: Option<MacroIcon>, lines: impl ExactSizeIterator<Item = &'a str>,
+) -> Option<DATError> {
+    if char_count(title) > limits.title_length {
+        return Some(DATError::Overflow(
+            format!("Title is longer than {} characters.", limits.title_length).into(),
+        ));
+    }
+    if icon.is_none() {
+        return Some(DATError::InvalidInput("Macro icon is invalid.".into()));
+    }
+    if lines.len() < limits.line_count {
+        return Some(DATError::Underflow(
+            format!("Macro has fewer than {} lines.", limits.line_count).into(),
+        ));
+    }
+    if lines.len() > limits.line_count {
+        return Some(DATError::Overflow(
+            format!("Macro has more than {} lines.", limits.line_count).into(),
+        ));
+    }
+    for line in lines {
+        if char_count(line) > limits.line_length {
+            return Some(DATError::Overflow(
+                format!("Line is longer than {} characters.", limits.line_length).into(),
+            ));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1280,6 +1705,140 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_char_count() -> Result<(), String> {
+        assert_eq!(char_count(""), 0);
+        assert_eq!(char_count("abc"), 3);
+        assert_eq!(char_count("日本語"), 3);
+        assert_eq!(char_count("\u{E03C}\u{E0BB}"), 2);
+        assert_eq!(char_count("a\u{2}\u{2E}\u{4}\u{3}\u{3}\u{3}\u{3}b"), 3);
+        // {AT:4:12}, whose length byte is 0x03
+        assert_eq!(char_count("a\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}b"), 3);
+        // Unterminated payloads count every character.
+        assert_eq!(char_count("a\u{2}\u{2E}"), 3);
+        Ok(())
+    }
+
+    // --- MacroLimits
+    #[test]
+    fn test_macro_limits_remaining() -> Result<(), String> {
+        let limits = MacroLimits::default();
+        assert_eq!(limits.remaining_line(""), 180);
+        assert_eq!(limits.remaining_line(&"あ".repeat(179)), 1);
+        assert_eq!(limits.remaining_line(&"あ".repeat(181)), 0);
+        assert_eq!(limits.remaining_title(&"\u{E03C}".repeat(20)), 0);
+        assert_eq!(limits.remaining_title(&"\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}".repeat(20)), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_validate_multibyte() -> Result<(), String> {
+        let title = "タイトル".repeat(5);
+        let line = "\u{E03C}".repeat(180);
+        let a_macro = MacroData {
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
+            lines: vec![&line; 15],
+            title: &title,
+        };
+        match a_macro.validate() {
+            Some(err) => Err(format!("Error: {}", err)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn test_macro_validate_auto_translate() -> Result<(), String> {
+        // {AT:4:12}
+        let payload = "\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}";
+        let title = payload.repeat(20);
+        let line = payload.repeat(180);
+        let a_macro = MacroData {
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
+            lines: vec![&line; 15],
+            title: &title,
+        };
+        match a_macro.validate() {
+            Some(err) => Err(format!("Error: {}", err)),
+            None => Ok(()),
+        }
+    }
+
+    // --- Macro
+    #[test]
+    fn test_macro_effective_icon() -> Result<(), String> {
+        let mut a_macro = Macro::new("".to_owned(), vec!["/MacroIcon Sprint".to_owned()], MacroIcon::Tank2).unwrap();
+        assert_eq!(
+            a_macro.effective_icon(),
+            Some(EffectiveIcon::Micon {
+                kind: MiconKind::Action,
+                name: "Sprint".to_owned()
+            })
+        );
+        // A /micon without a name falls back to the GUI icon.
+        a_macro.lines[0] = "/micon".to_owned();
+        assert_eq!(a_macro.effective_icon(), Some(EffectiveIcon::Icon(MacroIcon::Tank2)));
+        // An unterminated bracket before a full-width space is not a placeholder.
+        a_macro.lines[0] = "/micon <\u{3000}".to_owned();
+        assert_eq!(
+            a_macro.effective_icon(),
+            Some(EffectiveIcon::Micon {
+                kind: MiconKind::Action,
+                name: "<".to_owned()
+            })
+        );
+        // A /micon after the first line is ignored.
+        a_macro.lines[0] = String::new();
+        a_macro.lines[1] = "/micon Sprint".to_owned();
+        assert_eq!(a_macro.effective_icon(), Some(EffectiveIcon::Icon(MacroIcon::Tank2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_set_micon_rewrite() -> Result<(), String> {
+        let mut a_macro = Macro::new(
+            "".to_owned(),
+            vec!["/micon".to_owned(), "/ac Sprint".to_owned()],
+            MacroIcon::DefaultIcon,
+        )
+        .unwrap();
+        match a_macro.set_micon(MiconKind::Emote, "Dance") {
+            Ok(_) => {
+                assert_eq!(a_macro.lines[0], "/micon \"Dance\" emote");
+                assert_eq!(a_macro.lines[1], "/ac Sprint");
+                assert_eq!(a_macro.lines.len(), 15);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_set_micon_error_full() -> Result<(), String> {
+        let mut a_macro = Macro::new("".to_owned(), vec!["/ac Sprint".to_owned(); 15], MacroIcon::DefaultIcon).unwrap();
+        match a_macro.set_micon(MiconKind::Action, "Sprint") {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(_) => {
+                    assert_eq!(a_macro.lines, vec!["/ac Sprint".to_owned(); 15]);
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_set_micon_error_quote() -> Result<(), String> {
+        let mut a_macro = Macro::new("".to_owned(), vec![], MacroIcon::DefaultIcon).unwrap();
+        match a_macro.set_micon(MiconKind::Action, "Bad\"Name") {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
     // --- MacroData
     #[test]
     fn test_macrodata_change_icon() -> Result<(), String> {
@@ -1361,6 +1920,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_macrodata_from_sectiondata_error_icon_hex() -> Result<(), String> {
+        let mut sec_vec = vec![
+            SectionData::new("T", "Title").unwrap(),
+            SectionData::new("I", "123456").unwrap(),
+            SectionData::new("K", "XYZ").unwrap(),
+        ];
+        let mut line_vec = vec![SectionData::new("L", "Line").unwrap(); 15];
+        sec_vec.append(&mut line_vec);
+        match MacroData::from_section_data_unsafe(sec_vec) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_from_sections_unknown_icon() -> Result<(), String> {
+        let mut sec_vec = vec![
+            Section::new("T".to_owned(), "Title".to_owned()).unwrap(),
+            Section::new("I".to_owned(), "0ABCDEF".to_owned()).unwrap(),
+            Section::new("K".to_owned(), "0FF".to_owned()).unwrap(),
+        ];
+        sec_vec.append(&mut vec![Section::new("L".to_owned(), "Line".to_owned()).unwrap(); 15]);
+        match Macro::from_sections_unsafe(sec_vec.clone()) {
+            Ok(a_macro) => {
+                assert_eq!(a_macro.icon.key(), 0x0FF);
+                assert_eq!(a_macro.icon.icon_id(), 0xABCDEF);
+                assert_eq!(a_macro.get_icon(), None);
+                match a_macro.as_sections() {
+                    Ok(sections) => assert_eq!(sections, sec_vec),
+                    Err(err) => return Err(format!("Error: {}", err)),
+                }
+            }
+            Err(err) => return Err(format!("Error: {}", err)),
+        }
+        match Macro::from_sections(sec_vec) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
     #[test]
     fn test_macrodata_from_sectiondata_error_no_lines() -> Result<(), String> {
         let sec_vec = vec![
@@ -1406,8 +2012,7 @@ mod tests {
     #[test]
     fn test_macrodata_get_icon_none() -> Result<(), String> {
         let a_macro = MacroData {
-            icon_id: "1231234",
-            icon_key: "XYZ",
+            icon: MacroIconRef::new(0xFFF, 0x1231234).unwrap(),
             lines: vec![""; 15],
             title: "Title",
         };
@@ -1421,8 +2026,8 @@ mod tests {
                 assert_eq!(a_macro.title, "Title");
                 assert_eq!(a_macro.lines[0], "Line");
                 let (icon_key, icon_id) = macro_icon_to_key_and_id(&MacroIcon::SymbolCheck);
-                assert_eq!(a_macro.icon_id, icon_id);
-                assert_eq!(a_macro.icon_key, icon_key);
+                assert_eq!(a_macro.icon.icon_id_hex(), icon_id);
+                assert_eq!(a_macro.icon.key_hex(), icon_key);
                 Ok(())
             }
             Err(err) => Err(format!("Error building macro: {}", err)),
@@ -1438,10 +2043,9 @@ mod tests {
     #[test]
     fn test_macrodata_validate_error_title() -> Result<(), String> {
         let a_macro = MacroData {
-            icon_id: "0000000",
-            icon_key: "000",
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
             lines: vec![""; 15],
-            title: &std::iter::repeat("X").take(21).collect::<String>(),
+            title: &"X".repeat(21),
         };
         match a_macro.validate() {
             Some(err) => match err {
@@ -1455,8 +2059,7 @@ mod tests {
     #[test]
     fn test_macrodata_validate_error_icon() -> Result<(), String> {
         let a_macro = MacroData {
-            icon_id: "0000000",
-            icon_key: "XYZ",
+            icon: MacroIconRef::new(0x001, 0x0000000).unwrap(),
             lines: vec![""; 15],
             title: "",
         };
@@ -1471,10 +2074,9 @@ mod tests {
 
     #[test]
     fn test_macro_validate_error_line_len() -> Result<(), String> {
-        let long_line = std::iter::repeat("X").take(181).collect::<String>();
+        let long_line = "X".repeat(181);
         let a_macro = MacroData {
-            icon_id: "0000000",
-            icon_key: "000",
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
             lines: vec![&long_line; 15],
             title: "",
         };
@@ -1490,8 +2092,7 @@ mod tests {
     #[test]
     fn test_macrodata_validate_error_line_count_high() -> Result<(), String> {
         let a_macro = MacroData {
-            icon_id: "0000000",
-            icon_key: "000",
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
             lines: vec![""; 16],
             title: "",
         };
@@ -1507,8 +2108,7 @@ mod tests {
     #[test]
     fn test_macrodata_validate_error_line_count_low() -> Result<(), String> {
         let a_macro = MacroData {
-            icon_id: "0000000",
-            icon_key: "000",
+            icon: MacroIconRef::from(MacroIcon::NoIcon),
             lines: vec![""; 14],
             title: "",
         };
@@ -0,0 +1,808 @@
+use super::icon::{MacroIcon, MacroIconRef};
+use super::{read_macro_content, to_writeable_bytes, Macro, EXPECTED_ITEM_COUNT};
+use crate::dat_error::DATError;
+use crate::dat_file::write_content;
+use std::path::{Path, PathBuf};
+
+/// The file name of an individual (character-specific) macro book.
+pub const INDIVIDUAL_FILE_NAME: &str = "MACRO.DAT";
+
+/// The file name of the shared macro book.
+pub const SHARED_FILE_NAME: &str = "MACROSYS.DAT";
+
+/// The prefix of the character directories within the game's config directory. Individual macro books are
+/// stored in these directories, while the shared book is stored in the config directory itself.
+pub use crate::dat_file::CHARACTER_DIR_PREFIX;
+
+/// The two macro books shown in the game client's User Macros menu.
+///
+/// Both books share the same file format and [`DATType`](crate::dat_type::DATType), so the book
+/// can only be determined from the location of the file. See [`from_path()`](Self::from_path).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BookKind {
+    /// The character-specific book, stored in `MACRO.DAT` within a character directory.
+    Individual,
+    /// The book shared by all characters, stored in `MACROSYS.DAT` within the config directory.
+    Shared,
+}
+
+/// A full book of [`EXPECTED_ITEM_COUNT`] macro slots, addressed by slot number (0-99) as in the
+/// game client's User Macros menu.
+///
+/// A [`MacroBook`] always contains exactly [`EXPECTED_ITEM_COUNT`] macros. Empty slots hold a blank macro.
+/// Slots modified since the book was loaded or last saved are tracked, see [`changed_slots()`](Self::changed_slots).
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::Macro;
+/// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+/// use libxivdat::xiv_macro::icon::MacroIcon;
+///
+/// let mut book = MacroBook::new(BookKind::Shared);
+/// let a_macro = Macro::new("Title".to_string(), vec![], MacroIcon::DefaultIcon).unwrap();
+/// book.set(42, a_macro).unwrap();
+///
+/// assert_eq!(book.kind(), BookKind::Shared);
+/// assert_eq!(book.get(42).unwrap().title, "Title");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MacroBook {
+    changed: Vec<bool>,
+    kind: BookKind,
+    macros: Vec<Macro>,
+    path: Option<PathBuf>,
+}
+
+impl BookKind {
+    /// Returns the file name used by the game client for this book.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::BookKind;
+    ///
+    /// assert_eq!(BookKind::Individual.file_name(), "MACRO.DAT");
+    /// assert_eq!(BookKind::Shared.file_name(), "MACROSYS.DAT");
+    /// ```
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            BookKind::Individual => INDIVIDUAL_FILE_NAME,
+            BookKind::Shared => SHARED_FILE_NAME,
+        }
+    }
+
+    /// Determines which book a macro file holds from its path.
+    ///
+    /// The file name is checked first. If it is neither `MACRO.DAT` nor `MACROSYS.DAT`, a file within a
+    /// character directory (ie, `FFXIV_CHR0040000000000000`) is considered [`Individual`](Self::Individual).
+    /// Returns [`None`] if the book cannot be determined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::BookKind;
+    ///
+    /// assert_eq!(BookKind::from_path("config/MACROSYS.DAT"), Some(BookKind::Shared));
+    /// assert_eq!(BookKind::from_path("config/FFXIV_CHR0040000000000000/MACRO.DAT"), Some(BookKind::Individual));
+    /// assert_eq!(BookKind::from_path("config/FFXIV_CHR0040000000000000/MACRO.bak"), Some(BookKind::Individual));
+    /// assert_eq!(BookKind::from_path("backup/MACRO.bak"), None);
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<BookKind> {
+        let path = path.as_ref();
+        let file_name = path.file_name()?.to_string_lossy().to_uppercase();
+        if file_name == INDIVIDUAL_FILE_NAME {
+            return Some(BookKind::Individual);
+        }
+        if file_name == SHARED_FILE_NAME {
+            return Some(BookKind::Shared);
+        }
+        let dir_name = path.parent()?.file_name()?.to_string_lossy().to_uppercase();
+        if dir_name.starts_with(CHARACTER_DIR_PREFIX) {
+            Some(BookKind::Individual)
+        } else {
+            None
+        }
+    }
+}
+
+impl MacroBook {
+    /// Returns an iterator over the slots modified since the book was loaded or last saved, in slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let mut book = MacroBook::open_as("./resources/TEST_MACRO.DAT", BookKind::Individual).unwrap();
+    /// assert_eq!(book.changed_slots().count(), 0);
+    ///
+    /// book.swap(0, 1).unwrap();
+    /// assert_eq!(book.changed_slots().collect::<Vec<_>>(), vec![0, 1]);
+    /// ```
+    pub fn changed_slots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changed
+            .iter()
+            .enumerate()
+            .filter(|(_, changed)| **changed)
+            .map(|(slot, _)| slot)
+    }
+
+    /// Empties a slot, returning the previous [`Macro`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the slot is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let mut book = MacroBook::open_as("./resources/TEST_MACRO.DAT", BookKind::Individual).unwrap();
+    /// let old_macro = book.clear(1).unwrap();
+    ///
+    /// assert_eq!(old_macro.title, "1");
+    /// assert!(book.get(1).unwrap().is_empty());
+    /// ```
+    pub fn clear(&mut self, slot: usize) -> Result<Macro, DATError> {
+        self.set(slot, empty_macro())
+    }
+
+    /// Builds a [`MacroBook`] from a [`Vec`] of exactly [`EXPECTED_ITEM_COUNT`] [`Macros`](Macro), such as the
+    /// result of [`read_macro_content()`](super::read_macro_content).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::Underflow`] or [`DATError::Overflow`] if the number of macros is not
+    /// [`EXPECTED_ITEM_COUNT`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::read_macro_content;
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let macros = read_macro_content("./resources/TEST_MACRO.DAT").unwrap();
+    /// let book = MacroBook::from_macros(BookKind::Individual, macros).unwrap();
+    ///
+    /// assert_eq!(book.get(1).unwrap().title, "1");
+    /// ```
+    pub fn from_macros(kind: BookKind, macros: Vec<Macro>) -> Result<MacroBook, DATError> {
+        if macros.len() < EXPECTED_ITEM_COUNT {
+            return Err(DATError::Underflow(
+                format!("A macro book must contain exactly {} macros.", EXPECTED_ITEM_COUNT).into(),
+            ));
+        }
+        if macros.len() > EXPECTED_ITEM_COUNT {
+            return Err(DATError::Overflow(
+                format!("A macro book must contain exactly {} macros.", EXPECTED_ITEM_COUNT).into(),
+            ));
+        }
+        Ok(MacroBook {
+            changed: vec![false; EXPECTED_ITEM_COUNT],
+            kind,
+            macros,
+            path: None,
+        })
+    }
+
+    /// Returns the [`Macro`] in a slot, or [`None`] if the slot is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let book = MacroBook::new(BookKind::Individual);
+    /// assert_eq!(book.get(99).unwrap().title, "");
+    /// assert!(book.get(100).is_none());
+    /// ```
+    pub fn get(&self, slot: usize) -> Option<&Macro> {
+        self.macros.get(slot)
+    }
+
+    /// Returns true if a slot was modified since the book was loaded or last saved. Out of range
+    /// slots are never changed.
+    pub fn is_changed(&self, slot: usize) -> bool {
+        self.changed.get(slot).copied().unwrap_or(false)
+    }
+
+    /// Returns an iterator over all non-empty slots and their [`Macros`](Macro), in slot order.
+    /// See [`Macro::is_empty()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let mut book = MacroBook::new(BookKind::Shared);
+    /// let a_macro = Macro::new("Title".to_string(), vec![], MacroIcon::DefaultIcon).unwrap();
+    /// book.set(7, a_macro).unwrap();
+    ///
+    /// let slots = book.iter().map(|(slot, _)| slot).collect::<Vec<_>>();
+    /// assert_eq!(slots, vec![7]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Macro)> {
+        self.macros
+            .iter()
+            .enumerate()
+            .filter(|(_, a_macro)| !a_macro.is_empty())
+    }
+
+    /// Returns which [`BookKind`] this book holds.
+    pub fn kind(&self) -> BookKind {
+        self.kind
+    }
+
+    /// Returns all [`Macros`](Macro) in the book, in slot order.
+    pub fn macros(&self) -> &[Macro] {
+        &self.macros
+    }
+
+    /// Moves the [`Macro`] in one slot to another, leaving the original slot empty. Returns the [`Macro`]
+    /// previously in the target slot. Moving a slot onto itself does nothing and returns an empty [`Macro`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if either slot is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let mut book = MacroBook::open_as("./resources/TEST_MACRO.DAT", BookKind::Individual).unwrap();
+    /// let old_macro = book.move_slot(1, 2).unwrap();
+    ///
+    /// assert_eq!(old_macro.title, "2");
+    /// assert_eq!(book.get(2).unwrap().title, "1");
+    /// assert!(book.get(1).unwrap().is_empty());
+    /// ```
+    pub fn move_slot(&mut self, from: usize, to: usize) -> Result<Macro, DATError> {
+        self.check_slot(from)?;
+        self.check_slot(to)?;
+        if from == to {
+            return Ok(empty_macro());
+        }
+        let moved = self.clear(from)?;
+        self.set(to, moved)
+    }
+
+    /// Opens a macro file as a [`MacroBook`], determining the [`BookKind`] from its path. See
+    /// [`BookKind::from_path()`]. The book remembers its path for [`save()`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the [`BookKind`] cannot be determined from the path. Use
+    /// [`open_as()`](Self::open_as) for files without a standard name or location.
+    ///
+    /// Returns a [`DATError::IncorrectType`] if the file is not a macro file, or [`DATError::Underflow`] or
+    /// [`DATError::Overflow`] if the file does not contain exactly [`EXPECTED_ITEM_COUNT`] macros.
+    ///
+    /// Otherwise, returns the same errors as [`read_macro_content()`](super::read_macro_content).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("MACROSYS.DAT");
+    /// # std::fs::copy("./resources/TEST_MACRO.DAT", &path).unwrap();
+    ///
+    /// let book = MacroBook::open(&path).unwrap();
+    /// assert_eq!(book.kind(), BookKind::Shared);
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MacroBook, DATError> {
+        match BookKind::from_path(&path) {
+            Some(kind) => MacroBook::open_as(path, kind),
+            None => Err(
+                DATError::InvalidInput("Could not determine the macro book from the file path.".into())
+                    .with_path(path.as_ref()),
+            ),
+        }
+    }
+
+    /// Opens a macro file as a [`MacroBook`] of the given [`BookKind`]. The book remembers its path
+    /// for [`save()`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`open()`](Self::open), except that the path is not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let book = MacroBook::open_as("./resources/TEST_MACRO.DAT", BookKind::Individual).unwrap();
+    /// assert_eq!(book.get(0).unwrap().title, "0");
+    /// ```
+    pub fn open_as<P: AsRef<Path>>(path: P, kind: BookKind) -> Result<MacroBook, DATError> {
+        let macros = read_macro_content(&path)?;
+        let mut book = MacroBook::from_macros(kind, macros).map_err(|err| err.with_path(path.as_ref()))?;
+        book.path = Some(path.as_ref().to_path_buf());
+        Ok(book)
+    }
+
+    /// Returns the path the book was opened from or last saved to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Builds a [`MacroBook`] with every slot empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    /// use libxivdat::xiv_macro::EXPECTED_ITEM_COUNT;
+    ///
+    /// let book = MacroBook::new(BookKind::Shared);
+    /// assert_eq!(book.macros().len(), EXPECTED_ITEM_COUNT);
+    /// ```
+    pub fn new(kind: BookKind) -> MacroBook {
+        MacroBook {
+            changed: vec![false; EXPECTED_ITEM_COUNT],
+            kind,
+            macros: vec![empty_macro(); EXPECTED_ITEM_COUNT],
+            path: None,
+        }
+    }
+
+    /// Writes the book back to the file it was opened from, then resets the changed slots.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the book was not opened from a file. Use [`save_as()`](Self::save_as)
+    /// for books built in memory.
+    ///
+    /// Otherwise, returns the same errors as [`to_writeable_bytes()`](super::to_writeable_bytes) and
+    /// [`write_content()`](crate::dat_file::write_content). If an error is returned, the changed slots are not reset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("MACRO.DAT");
+    /// # std::fs::copy("./resources/TEST_MACRO.DAT", &path).unwrap();
+    ///
+    /// let mut book = MacroBook::open(&path).unwrap();
+    /// book.swap(0, 1).unwrap();
+    /// book.save().unwrap();
+    ///
+    /// assert_eq!(book.changed_slots().count(), 0);
+    /// assert_eq!(MacroBook::open(&path).unwrap().get(0).unwrap().title, "1");
+    /// ```
+    pub fn save(&mut self) -> Result<(), DATError> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => {
+                return Err(DATError::InvalidInput(
+                    "The macro book was not opened from a file.".into(),
+                ))
+            }
+        };
+        self.save_as(path)
+    }
+
+    /// Writes the book to an existing macro file, then resets the changed slots. The book remembers this
+    /// path for future calls to [`save()`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`to_writeable_bytes()`](super::to_writeable_bytes) and
+    /// [`write_content()`](crate::dat_file::write_content). If an error is returned, the changed slots are not reset.
+    pub fn save_as<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DATError> {
+        let bytes = to_writeable_bytes(&self.macros)?;
+        write_content(&path, &bytes)?;
+        self.changed = vec![false; EXPECTED_ITEM_COUNT];
+        self.path = Some(path.as_ref().to_path_buf());
+        Ok(())
+    }
+
+    /// Replaces the [`Macro`] in a slot, returning the previous [`Macro`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the slot is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::Macro;
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// let mut book = MacroBook::new(BookKind::Individual);
+    /// let a_macro = Macro::new("Title".to_string(), vec![], MacroIcon::DefaultIcon).unwrap();
+    ///
+    /// let old_macro = book.set(0, a_macro).unwrap();
+    /// assert_eq!(old_macro.title, "");
+    /// assert_eq!(book.get(0).unwrap().title, "Title");
+    /// assert!(book.set(100, old_macro).is_err());
+    /// ```
+    pub fn set(&mut self, slot: usize, a_macro: Macro) -> Result<Macro, DATError> {
+        self.check_slot(slot)?;
+        if self.macros[slot] != a_macro {
+            self.changed[slot] = true;
+        }
+        Ok(std::mem::replace(&mut self.macros[slot], a_macro))
+    }
+
+    /// Changes which [`BookKind`] this book holds, ie to copy an individual book into the shared book.
+    pub fn set_kind(&mut self, kind: BookKind) {
+        self.kind = kind;
+    }
+
+    /// Swaps the [`Macros`](Macro) in two slots.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if either slot is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::book::{BookKind, MacroBook};
+    ///
+    /// let mut book = MacroBook::open_as("./resources/TEST_MACRO.DAT", BookKind::Individual).unwrap();
+    /// book.swap(0, 1).unwrap();
+    ///
+    /// assert_eq!(book.get(0).unwrap().title, "1");
+    /// assert_eq!(book.get(1).unwrap().title, "0");
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), DATError> {
+        self.check_slot(a)?;
+        self.check_slot(b)?;
+        if self.macros[a] != self.macros[b] {
+            self.changed[a] = true;
+            self.changed[b] = true;
+        }
+        self.macros.swap(a, b);
+        Ok(())
+    }
+
+    /// Returns an error if a slot is out of range.
+    fn check_slot(&self, slot: usize) -> Result<(), DATError> {
+        if slot < self.macros.len() {
+            Ok(())
+        } else {
+            Err(DATError::InvalidInput(
+                format!("Macro slot {} is out of range (0-{}).", slot, EXPECTED_ITEM_COUNT - 1).into(),
+            ))
+        }
+    }
+}
+
+/// Returns a blank [`Macro`], matching an unused slot in the game client.
+fn empty_macro() -> Macro {
+    Macro {
+        icon: MacroIconRef::from(MacroIcon::NoIcon),
+        lines: vec![String::new(); 15],
+        title: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xiv_macro::read_macro_content;
+    use std::fs::copy;
+    use tempfile::{tempdir, TempDir};
+
+    const TEST_FILE_PATH: &str = "./resources/TEST_MACRO.DAT";
+
+    fn copy_test_file(tmp_dir: &TempDir, file_name: &str) -> Result<PathBuf, String> {
+        let tmp_path = tmp_dir.path().join(file_name);
+        match copy(TEST_FILE_PATH, &tmp_path) {
+            Ok(_) => Ok(tmp_path),
+            Err(err) => Err(format!("Could not create temp file for testing: {}", err)),
+        }
+    }
+
+    fn open_test_book() -> Result<MacroBook, String> {
+        match MacroBook::open_as(TEST_FILE_PATH, BookKind::Individual) {
+            Ok(book) => Ok(book),
+            Err(err) => Err(format!("Error opening book: {}", err)),
+        }
+    }
+
+    // --- BookKind
+
+    #[test]
+    fn test_book_kind_from_path() -> Result<(), String> {
+        assert_eq!(BookKind::from_path("MACROSYS.DAT"), Some(BookKind::Shared));
+        assert_eq!(BookKind::from_path("cfg/macrosys.dat"), Some(BookKind::Shared));
+        assert_eq!(
+            BookKind::from_path("cfg/ffxiv_chr0040000000000000/macro.dat"),
+            Some(BookKind::Individual)
+        );
+        assert_eq!(
+            BookKind::from_path("cfg/FFXIV_CHR0040000000000000/TEST.DAT"),
+            Some(BookKind::Individual)
+        );
+        assert_eq!(BookKind::from_path(TEST_FILE_PATH), None);
+        assert_eq!(BookKind::from_path(""), None);
+        Ok(())
+    }
+
+    // --- MacroBook
+
+    #[test]
+    fn test_macro_book_new() -> Result<(), String> {
+        let book = MacroBook::new(BookKind::Individual);
+        assert_eq!(book.macros().len(), EXPECTED_ITEM_COUNT);
+        assert_eq!(book.get(0).unwrap().get_icon(), Some(MacroIcon::NoIcon));
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_book_from_macros() -> Result<(), String> {
+        let macros = match read_macro_content(TEST_FILE_PATH) {
+            Ok(macros) => macros,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        match MacroBook::from_macros(BookKind::Shared, macros) {
+            Ok(book) => {
+                assert_eq!(book.kind(), BookKind::Shared);
+                assert_eq!(book.get(0).unwrap().title, "0");
+                assert_eq!(book.get(1).unwrap().title, "1");
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_from_macros_error_underflow() -> Result<(), String> {
+        match MacroBook::from_macros(BookKind::Shared, vec![empty_macro(); EXPECTED_ITEM_COUNT - 1]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Underflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_from_macros_error_overflow() -> Result<(), String> {
+        match MacroBook::from_macros(BookKind::Shared, vec![empty_macro(); EXPECTED_ITEM_COUNT + 1]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_set() -> Result<(), String> {
+        let mut book = MacroBook::new(BookKind::Individual);
+        let a_macro = Macro::new("Title".to_owned(), vec![], MacroIcon::DefaultIcon).unwrap();
+        match book.set(99, a_macro.clone()) {
+            Ok(old_macro) => {
+                assert_eq!(old_macro, empty_macro());
+                assert_eq!(book.get(99), Some(&a_macro));
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_set_error_range() -> Result<(), String> {
+        let mut book = MacroBook::new(BookKind::Individual);
+        match book.set(EXPECTED_ITEM_COUNT, empty_macro()) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_set_unchanged() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        let same = book.get(3).unwrap().clone();
+        match book.set(3, same) {
+            Ok(_) => {
+                assert!(!book.is_changed(3));
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_clear() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.clear(5) {
+            Ok(old_macro) => {
+                assert_eq!(old_macro.title, "5");
+                assert!(book.get(5).unwrap().is_empty());
+                assert_eq!(book.changed_slots().collect::<Vec<_>>(), vec![5]);
+                assert_eq!(book.macros().len(), EXPECTED_ITEM_COUNT);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_swap() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.swap(2, 4) {
+            Ok(_) => {
+                assert_eq!(book.get(2).unwrap().title, "4");
+                assert_eq!(book.get(4).unwrap().title, "2");
+                assert_eq!(book.changed_slots().collect::<Vec<_>>(), vec![2, 4]);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_swap_error_range() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.swap(0, EXPECTED_ITEM_COUNT) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => {
+                    assert_eq!(book.changed_slots().count(), 0);
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_move_slot() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.move_slot(1, 3) {
+            Ok(old_macro) => {
+                assert_eq!(old_macro.title, "3");
+                assert_eq!(book.get(3).unwrap().title, "1");
+                assert!(book.get(1).unwrap().is_empty());
+                assert_eq!(book.changed_slots().collect::<Vec<_>>(), vec![1, 3]);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_move_slot_same() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.move_slot(1, 1) {
+            Ok(_) => {
+                assert_eq!(book.get(1).unwrap().title, "1");
+                assert_eq!(book.changed_slots().count(), 0);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_move_slot_error_range() -> Result<(), String> {
+        let mut book = open_test_book()?;
+        match book.move_slot(1, EXPECTED_ITEM_COUNT) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => {
+                    // The source slot must not be cleared.
+                    assert_eq!(book.get(1).unwrap().title, "1");
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_iter() -> Result<(), String> {
+        let mut book = MacroBook::new(BookKind::Shared);
+        let a_macro = Macro::new("Title".to_owned(), vec![], MacroIcon::DefaultIcon).unwrap();
+        book.set(10, a_macro.clone()).unwrap();
+        book.set(20, a_macro.clone()).unwrap();
+        assert_eq!(book.iter().collect::<Vec<_>>(), vec![(10, &a_macro), (20, &a_macro)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_book_open() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir, "MACROSYS.DAT")?;
+        match MacroBook::open(&tmp_path) {
+            Ok(book) => {
+                assert_eq!(book.kind(), BookKind::Shared);
+                assert_eq!(book.path(), Some(tmp_path.as_path()));
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_open_error_kind() -> Result<(), String> {
+        match MacroBook::open(TEST_FILE_PATH) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(ref ctx) => {
+                    assert_eq!(ctx.path(), Some(Path::new(TEST_FILE_PATH)));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_open_error_type() -> Result<(), String> {
+        match MacroBook::open_as("./resources/TEST.DAT", BookKind::Shared) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_macro_book_save() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir, "MACRO.DAT")?;
+        let mut book = match MacroBook::open(&tmp_path) {
+            Ok(book) => book,
+            Err(err) => return Err(format!("Error opening book: {}", err)),
+        };
+        let a_macro = Macro::new(
+            "Saved".to_owned(),
+            vec!["/echo saved".to_owned()],
+            MacroIcon::DefaultIcon,
+        )
+        .unwrap();
+        book.set(50, a_macro.clone()).unwrap();
+        if let Err(err) = book.save() {
+            return Err(format!("Error saving book: {}", err));
+        }
+        assert_eq!(book.changed_slots().count(), 0);
+        match MacroBook::open(&tmp_path) {
+            Ok(reopened) => {
+                assert_eq!(reopened.get(50), Some(&a_macro));
+                assert_eq!(reopened.get(0).unwrap().title, "0");
+                Ok(())
+            }
+            Err(err) => Err(format!("Error reopening book: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_macro_book_save_error_no_path() -> Result<(), String> {
+        let mut book = MacroBook::new(BookKind::Shared);
+        match book.save() {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+}
@@ -1,178 +1,567 @@
-/// Enum of all possible macro icons. This includes only the default macro icons, not icons
-/// configured with the `/micon <action>` command. Internally, the macro data preserves the
-/// icon chosen via GUI, ignoring the /micon command.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum MacroIcon {
-    /// Default macro icon
-    DefaultIcon,
-    /// DPS symbol with number 1
-    DPS1,
-    /// DPS symbol with number 2
-    DPS2,
-    /// DPS symbol with number 3
-    DPS3,
-    /// Tank symbol with number 1
-    Tank1,
-    /// Tank symbol with number 2
-    Tank2,
-    /// Tank symbol with number 3
-    Tank3,
-    /// Healer symbol with number 1
-    Healer1,
-    /// Healer symbol with number 2
-    Healer2,
-    /// Healer symbol with number 3
-    Healer3,
-    /// Crafter symbol with number 1 in purple
-    CrafterPurple1,
-    /// Crafter symbol with number 2 in purple
-    CrafterPurple2,
-    /// Crafter symbol with number 3 in purple
-    CrafterPurple3,
-    /// Crafter symbol with number 1 in yellow
-    CrafterYellow1,
-    /// Crafter symbol with number 2 in yellow
-    CrafterYellow2,
-    /// Crafter symbol with number 3 in yellow
-    CrafterYellow3,
-    /// Crafter symbol with number 1 in green
-    CrafterGreen1,
-    /// Crafter symbol with number 2 in green
-    CrafterGreen2,
-    /// Crafter symbol with number 3 in green
-    CrafterGreen3,
-    /// Hammer with gold border
-    ItemHammer,
-    /// Sword with gold border
-    ItemSword,
-    /// Shield with gold border
-    ItemShield,
-    /// Ring with gold border
-    ItemRing,
-    /// Shoes with gold border
-    ItemShoes,
-    /// Hat with gold border
-    ItemHat,
-    /// Bottle with gold border
-    ItemBottle,
-    /// Bread with gold border
-    ItemBread,
-    /// Gather symbol with number 1
-    Gatherer1,
-    /// Gather symbol with number 2
-    Gatherer2,
-    /// Gather symbol with number 3
-    Gatherer3,
-    /// The number 0 in gold on a blue background
-    Number0,
-    /// The number 1 in gold on a blue background
-    Number1,
-    /// The number 2 in gold on a blue background
-    Number2,
-    /// The number 3 in gold on a blue background
-    Number3,
-    /// The number 4 in gold on a blue background
-    Number4,
-    /// The number 5 in gold on a blue background
-    Number5,
-    /// The number 6 in gold on a blue background
-    Number6,
-    /// The number 7 in gold on a blue background
-    Number7,
-    /// The number 8 in gold on a blue background
-    Number8,
-    /// The number 9 in gold on a blue background
-    Number9,
-    /// The number 10 in gold on a blue background
-    Number10,
-    /// The number 0 in blue on a gold background
-    InverseNumber0,
-    /// The number 1 in blue on a gold background
-    InverseNumber1,
-    /// The number 2 in blue on a gold background
-    InverseNumber2,
-    /// The number 3 in blue on a gold background
-    InverseNumber3,
-    /// The number 4 in blue on a gold background
-    InverseNumber4,
-    /// The number 5 in blue on a gold background
-    InverseNumber5,
-    /// The number 6 in blue on a gold background
-    InverseNumber6,
-    /// The number 7 in blue on a gold background
-    InverseNumber7,
-    /// The number 8 in blue on a gold background
-    InverseNumber8,
-    /// The number 9 in blue on a gold background
-    InverseNumber9,
-    /// The number 10 in blue on a gold background
-    InverseNumber10,
-    /// A gray left arrow symbol
-    SymbolArrowLeft,
-    /// A gray right arrow symbol
-    SymbolArrowRight,
-    /// A gray up arrow symbol
-    SymbolArrowUp,
-    /// A gray down arrow symbol
-    SymbolArrowDown,
-    /// A gray circle symbol
-    SymbolCircle,
-    /// A gray triangle symbol
-    SymbolTriangle,
-    /// A gray square symbol
-    SymbolSquare,
-    /// A gray x symbol
-    SymbolX,
-    /// A gray no symbol
-    SymbolNo,
-    /// A gray warning symbol
-    SymbolWarning,
-    /// A gray check mark symbol
-    SymbolCheck,
-    /// A gray star symbol
-    SymbolStar,
-    /// A gray question mark symbol
-    SymbolQuestion,
-    /// A gray exclamation mark symbol
-    SymbolExclamation,
-    /// A gray plus symbol
-    SymbolPlus,
-    /// A gray minus symbol
-    SymbolMinus,
-    /// A gray clock symbol
-    SymbolClock,
-    /// A gray light bulb symbol
-    SymbolBulb,
-    /// A gray cog wheel / settings symbol
-    SymbolCog,
-    /// A gray magnifying glass / search symbol
-    SymbolSearch,
-    /// A gray speech bubble symbol
-    SymbolSpeech,
-    /// A gray heart symbol
-    SymbolHeart,
-    /// A gray spade symbol
-    SymbolSpade,
-    /// A gray club symbol
-    SymbolClub,
-    /// A gray diamond symbol
-    SymbolDiamond,
-    /// A gray dice symbol
-    SymbolDice,
-    /// A fire crystal item icon
-    CrystalFire,
-    /// An ice crystal item icon
-    CrystalIce,
-    /// A wind crystal item icon
-    CrystalWind,
-    /// An earth crystal item icon
-    CrystalEarth,
-    /// A lightning crystal item icon
-    CrystalLightning,
-    /// A water crystal item icon
-    CrystalWater,
-    /// A valid macro with no icon; this is technically possible, although the gui doesn't allow it
-    NoIcon,
+use crate::dat_error::DATError;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// The number of hex digits in the icon id [`Section`](crate::section::Section) of a macro.
+const ICON_ID_DIGITS: usize = 7;
+
+/// The number of hex digits in the key [`Section`](crate::section::Section) of a macro.
+const KEY_DIGITS: usize = 3;
+
+/// Generates [`MacroIcon`] and its lookup table from a single list of variants. Each variant is given as
+/// `Name = (key, key_str, id, id_str)`, where `key` and `id` are the numeric values and `key_str` and
+/// `id_str` are the hex strings stored in the key and icon [`Sections`](crate::section::Section) of a macro.
+macro_rules! macro_icon_table {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = ($key:literal, $key_str:literal, $id:literal, $id_str:literal),
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $name {
+            /// All macro icons, in declaration order.
+            pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            /// Returns the variant name, key, id, and section strings of the icon.
+            fn entry(&self) -> IconEntry {
+                match self {
+                    $($name::$variant => IconEntry {
+                        id: $id,
+                        id_str: $id_str,
+                        key: $key,
+                        key_str: $key_str,
+                        name: stringify!($variant),
+                    },)*
+                }
+            }
+        }
+    };
+}
+
+macro_icon_table! {
+    /// Enum of all possible macro icons. This includes only the default macro icons, not icons
+    /// configured with the `/micon <action>` command. Internally, the macro data preserves the
+    /// icon chosen via GUI, ignoring the /micon command. Use [`Macro::effective_icon()`](super::Macro::effective_icon)
+    /// to account for a `/micon` line.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum MacroIcon {
+        /// Default macro icon
+        DefaultIcon = (0x001, "001", 0x00101D1, "00101D1"),
+        /// DPS symbol with number 1
+        DPS1 = (0x002, "002", 0x0010235, "0010235"),
+        /// DPS symbol with number 2
+        DPS2 = (0x003, "003", 0x0010236, "0010236"),
+        /// DPS symbol with number 3
+        DPS3 = (0x004, "004", 0x0010237, "0010237"),
+        /// Tank symbol with number 1
+        Tank1 = (0x005, "005", 0x0010249, "0010249"),
+        /// Tank symbol with number 2
+        Tank2 = (0x006, "006", 0x001024A, "001024A"),
+        /// Tank symbol with number 3
+        Tank3 = (0x007, "007", 0x001024B, "001024B"),
+        /// Healer symbol with number 1
+        Healer1 = (0x008, "008", 0x001025D, "001025D"),
+        /// Healer symbol with number 2
+        Healer2 = (0x009, "009", 0x001025E, "001025E"),
+        /// Healer symbol with number 3
+        Healer3 = (0x00A, "00A", 0x001025F, "001025F"),
+        /// Crafter symbol with number 1 in purple
+        CrafterPurple1 = (0x00B, "00B", 0x00101E5, "00101E5"),
+        /// Crafter symbol with number 2 in purple
+        CrafterPurple2 = (0x00C, "00C", 0x00101E6, "00101E6"),
+        /// Crafter symbol with number 3 in purple
+        CrafterPurple3 = (0x00D, "00D", 0x00101E7, "00101E7"),
+        /// Crafter symbol with number 1 in yellow
+        CrafterYellow1 = (0x00E, "00E", 0x00101F9, "00101F9"),
+        /// Crafter symbol with number 2 in yellow
+        CrafterYellow2 = (0x00F, "00F", 0x00101FA, "00101FA"),
+        /// Crafter symbol with number 3 in yellow
+        CrafterYellow3 = (0x010, "010", 0x00101FB, "00101FB"),
+        /// Crafter symbol with number 1 in green
+        CrafterGreen1 = (0x011, "011", 0x001020D, "001020D"),
+        /// Crafter symbol with number 2 in green
+        CrafterGreen2 = (0x012, "012", 0x001020E, "001020E"),
+        /// Crafter symbol with number 3 in green
+        CrafterGreen3 = (0x013, "013", 0x001020F, "001020F"),
+        /// Hammer with gold border
+        ItemHammer = (0x014, "014", 0x00005E9, "00005E9"),
+        /// Sword with gold border
+        ItemSword = (0x015, "015", 0x000061B, "000061B"),
+        /// Shield with gold border
+        ItemShield = (0x016, "016", 0x000064D, "000064D"),
+        /// Ring with gold border
+        ItemRing = (0x017, "017", 0x000067E, "000067E"),
+        /// Shoes with gold border
+        ItemShoes = (0x018, "018", 0x00006B1, "00006B1"),
+        /// Hat with gold border
+        ItemHat = (0x019, "019", 0x00006E2, "00006E2"),
+        /// Bottle with gold border
+        ItemBottle = (0x01A, "01A", 0x0000715, "0000715"),
+        /// Bread with gold border
+        ItemBread = (0x01B, "01B", 0x0000746, "0000746"),
+        /// Gather symbol with number 1
+        Gatherer1 = (0x01C, "01C", 0x0010221, "0010221"),
+        /// Gather symbol with number 2
+        Gatherer2 = (0x01D, "01D", 0x0010222, "0010222"),
+        /// Gather symbol with number 3
+        Gatherer3 = (0x01E, "01E", 0x0010223, "0010223"),
+        /// The number 0 in gold on a blue background
+        Number0 = (0x01F, "01F", 0x0010271, "0010271"),
+        /// The number 1 in gold on a blue background
+        Number1 = (0x020, "020", 0x0010272, "0010272"),
+        /// The number 2 in gold on a blue background
+        Number2 = (0x021, "021", 0x0010273, "0010273"),
+        /// The number 3 in gold on a blue background
+        Number3 = (0x022, "022", 0x0010274, "0010274"),
+        /// The number 4 in gold on a blue background
+        Number4 = (0x023, "023", 0x0010275, "0010275"),
+        /// The number 5 in gold on a blue background
+        Number5 = (0x024, "024", 0x0010276, "0010276"),
+        /// The number 6 in gold on a blue background
+        Number6 = (0x025, "025", 0x0010277, "0010277"),
+        /// The number 7 in gold on a blue background
+        Number7 = (0x026, "026", 0x0010278, "0010278"),
+        /// The number 8 in gold on a blue background
+        Number8 = (0x027, "027", 0x0010279, "0010279"),
+        /// The number 9 in gold on a blue background
+        Number9 = (0x028, "028", 0x001027A, "001027A"),
+        /// The number 10 in gold on a blue background
+        Number10 = (0x029, "029", 0x001027B, "001027B"),
+        /// The number 0 in blue on a gold background
+        InverseNumber0 = (0x02A, "02A", 0x0010285, "0010285"),
+        /// The number 1 in blue on a gold background
+        InverseNumber1 = (0x02B, "02B", 0x0010286, "0010286"),
+        /// The number 2 in blue on a gold background
+        InverseNumber2 = (0x02C, "02C", 0x0010287, "0010287"),
+        /// The number 3 in blue on a gold background
+        InverseNumber3 = (0x02D, "02D", 0x0010288, "0010288"),
+        /// The number 4 in blue on a gold background
+        InverseNumber4 = (0x02E, "02E", 0x0010289, "0010289"),
+        /// The number 5 in blue on a gold background
+        InverseNumber5 = (0x02F, "02F", 0x001028A, "001028A"),
+        /// The number 6 in blue on a gold background
+        InverseNumber6 = (0x030, "030", 0x001028B, "001028B"),
+        /// The number 7 in blue on a gold background
+        InverseNumber7 = (0x031, "031", 0x001028C, "001028C"),
+        /// The number 8 in blue on a gold background
+        InverseNumber8 = (0x032, "032", 0x001028D, "001028D"),
+        /// The number 9 in blue on a gold background
+        InverseNumber9 = (0x033, "033", 0x001028E, "001028E"),
+        /// The number 10 in blue on a gold background
+        InverseNumber10 = (0x034, "034", 0x001028F, "001028F"),
+        /// A gray left arrow symbol
+        SymbolArrowLeft = (0x035, "035", 0x00102FD, "00102FD"),
+        /// A gray right arrow symbol
+        SymbolArrowRight = (0x036, "036", 0x00102FE, "00102FE"),
+        /// A gray up arrow symbol
+        SymbolArrowUp = (0x037, "037", 0x00102FF, "00102FF"),
+        /// A gray down arrow symbol
+        SymbolArrowDown = (0x038, "038", 0x0010300, "0010300"),
+        /// A gray circle symbol
+        SymbolCircle = (0x039, "039", 0x0010301, "0010301"),
+        /// A gray triangle symbol
+        SymbolTriangle = (0x03A, "03A", 0x0010302, "0010302"),
+        /// A gray square symbol
+        SymbolSquare = (0x03B, "03B", 0x0010303, "0010303"),
+        /// A gray x symbol
+        SymbolX = (0x03C, "03C", 0x0010304, "0010304"),
+        /// A gray no symbol
+        SymbolNo = (0x03D, "03D", 0x0010305, "0010305"),
+        /// A gray warning symbol
+        SymbolWarning = (0x03E, "03E", 0x0010306, "0010306"),
+        /// A gray check mark symbol
+        SymbolCheck = (0x03F, "03F", 0x0010307, "0010307"),
+        /// A gray star symbol
+        SymbolStar = (0x040, "040", 0x0010308, "0010308"),
+        /// A gray question mark symbol
+        SymbolQuestion = (0x041, "041", 0x0010309, "0010309"),
+        /// A gray exclamation mark symbol
+        SymbolExclamation = (0x042, "042", 0x001030A, "001030A"),
+        /// A gray plus symbol
+        SymbolPlus = (0x043, "043", 0x001030B, "001030B"),
+        /// A gray minus symbol
+        SymbolMinus = (0x044, "044", 0x001030C, "001030C"),
+        /// A gray clock symbol
+        SymbolClock = (0x045, "045", 0x001030D, "001030D"),
+        /// A gray light bulb symbol
+        SymbolBulb = (0x046, "046", 0x001030E, "001030E"),
+        /// A gray cog wheel / settings symbol
+        SymbolCog = (0x047, "047", 0x001030F, "001030F"),
+        /// A gray magnifying glass / search symbol
+        SymbolSearch = (0x048, "048", 0x0010310, "0010310"),
+        /// A gray speech bubble symbol
+        SymbolSpeech = (0x049, "049", 0x0010311, "0010311"),
+        /// A gray heart symbol
+        SymbolHeart = (0x04A, "04A", 0x0010312, "0010312"),
+        /// A gray spade symbol
+        SymbolSpade = (0x04B, "04B", 0x0010313, "0010313"),
+        /// A gray club symbol
+        SymbolClub = (0x04C, "04C", 0x0010314, "0010314"),
+        /// A gray diamond symbol
+        SymbolDiamond = (0x04D, "04D", 0x0010315, "0010315"),
+        /// A gray dice symbol
+        SymbolDice = (0x04E, "04E", 0x0010316, "0010316"),
+        /// A fire crystal item icon
+        CrystalFire = (0x04F, "04F", 0x0004E27, "0004E27"),
+        /// An ice crystal item icon
+        CrystalIce = (0x050, "050", 0x0004E29, "0004E29"),
+        /// A wind crystal item icon
+        CrystalWind = (0x051, "051", 0x0004E2A, "0004E2A"),
+        /// An earth crystal item icon
+        CrystalEarth = (0x052, "052", 0x0004E2C, "0004E2C"),
+        /// A lightning crystal item icon
+        CrystalLightning = (0x053, "053", 0x0004E2B, "0004E2B"),
+        /// A water crystal item icon
+        CrystalWater = (0x054, "054", 0x0004E28, "0004E28"),
+        /// A valid macro with no icon; this is technically possible, although the gui doesn't allow it
+        NoIcon = (0x000, "000", 0x0000000, "0000000"),
+    }
+}
+
+/// The icon displayed for a macro in the game client, accounting for a `/micon` command on the first line.
+/// See [`Macro::effective_icon()`](super::Macro::effective_icon).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EffectiveIcon {
+    /// A [`MacroIcon`] chosen via GUI.
+    Icon(MacroIcon),
+    /// An icon set by `/micon`, referencing an action, item, emote, or other entity by name.
+    Micon {
+        /// The kind of entity referenced.
+        kind: MiconKind,
+        /// The name of the entity, without quotes.
+        name: String,
+    },
+}
+
+/// The icon key and id of a macro, as stored in its key and icon [`Sections`](crate::section::Section).
+///
+/// Unlike [`MacroIcon`], this may hold a well-formed pair that does not match a known icon, such as
+/// an icon added in a later game version. Use [`icon()`](Self::icon) to look up the [`MacroIcon`].
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::icon::{MacroIcon, MacroIconRef};
+///
+/// let icon_ref = MacroIconRef::from(MacroIcon::ItemHammer);
+/// assert_eq!(icon_ref.key(), 0x014);
+/// assert_eq!(icon_ref.key_hex(), "014");
+/// assert_eq!(icon_ref.icon_id_hex(), "00005E9");
+///
+/// let unknown = MacroIconRef::from_hex("FFF", "0123456").unwrap();
+/// assert_eq!(unknown.icon(), None);
+/// ```
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct MacroIconRef {
+    icon_id: u32,
+    icon_id_hex: [u8; ICON_ID_DIGITS],
+    key: u16,
+    key_hex: [u8; KEY_DIGITS],
+}
+
+/// The kind of entity referenced by a `/micon` command, set by its optional second argument.
+/// If no kind is given, the game client assumes [`Action`](Self::Action).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MiconKind {
+    /// An action, such as `Fire IV`. `/micon "Fire IV"` or `/micon "Fire IV" action`
+    Action,
+    /// An emote. `/micon "Dance" emote`
+    Emote,
+    /// A gear set. `/micon "1" gearset`
+    Gearset,
+    /// An item. `/micon "Potion" item`
+    Item,
+    /// A minion. `/micon "Wind-up Cursor" minion`
+    Minion,
+    /// A mount. `/micon "Company Chocobo" mount`
+    Mount,
+    /// Any other kind, stored as written.
+    Other(String),
+}
+
+impl fmt::Display for MiconKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MiconKind::Action => write!(f, "action"),
+            MiconKind::Emote => write!(f, "emote"),
+            MiconKind::Gearset => write!(f, "gearset"),
+            MiconKind::Item => write!(f, "item"),
+            MiconKind::Minion => write!(f, "minion"),
+            MiconKind::Mount => write!(f, "mount"),
+            MiconKind::Other(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl MiconKind {
+    /// Returns the [`MiconKind`] for the second argument of a `/micon` command. Matching is case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MiconKind;
+    ///
+    /// assert_eq!(MiconKind::from_name("Item"), MiconKind::Item);
+    /// assert_eq!(MiconKind::from_name("fashion"), MiconKind::Other("fashion".to_string()));
+    /// ```
+    pub fn from_name(name: &str) -> MiconKind {
+        match name.to_lowercase().as_str() {
+            "action" => MiconKind::Action,
+            "emote" => MiconKind::Emote,
+            "gearset" => MiconKind::Gearset,
+            "item" => MiconKind::Item,
+            "minion" => MiconKind::Minion,
+            "mount" => MiconKind::Mount,
+            _ => MiconKind::Other(name.to_owned()),
+        }
+    }
+}
+
+/// A row of the [`MacroIcon`] table.
+struct IconEntry {
+    id: u32,
+    id_str: &'static str,
+    key: u16,
+    key_str: &'static str,
+    name: &'static str,
+}
+
+impl fmt::Display for MacroIcon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for MacroIcon {
+    type Err = DATError;
+
+    /// Parses a [`MacroIcon`] by name. See [`macro_icon_from_name()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the name does not match any icon.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match macro_icon_from_name(name) {
+            Some(icon) => Ok(icon),
+            None => Err(DATError::InvalidInput(
+                format!("Unknown macro icon name: {}.", name).into(),
+            )),
+        }
+    }
+}
+
+impl MacroIcon {
+    /// Returns the [`MacroIcon`] with the given numeric key and icon id, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// assert_eq!(MacroIcon::from_key_and_id(0x014, 0x5E9), Some(MacroIcon::ItemHammer));
+    /// assert_eq!(MacroIcon::from_key_and_id(0x014, 0x5EA), None);
+    /// ```
+    pub fn from_key_and_id(key: u16, icon_id: u32) -> Option<MacroIcon> {
+        MacroIcon::ALL
+            .iter()
+            .copied()
+            .find(|icon| icon.key() == key && icon.icon_id() == icon_id)
+    }
+
+    /// Returns the path of the icon's texture within the game data, or `None` for [`MacroIcon::NoIcon`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// assert_eq!(MacroIcon::DefaultIcon.icon_path().unwrap(), "ui/icon/066000/066001.tex");
+    /// assert_eq!(MacroIcon::NoIcon.icon_path(), None);
+    /// ```
+    pub fn icon_path(&self) -> Option<String> {
+        match self.icon_id() {
+            0 => None,
+            id => Some(format!("ui/icon/{:06}/{:06}.tex", id / 1000 * 1000, id)),
+        }
+    }
+
+    /// Returns the icon id, as stored in hex in the icon [`Section`](crate::section::Section) of a macro.
+    /// This is also the id of the icon's texture in the game data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// assert_eq!(MacroIcon::ItemHammer.icon_id(), 0x5E9);
+    /// ```
+    pub fn icon_id(&self) -> u32 {
+        self.entry().id
+    }
+
+    /// Returns the icon key, as stored in hex in the key [`Section`](crate::section::Section) of a macro.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// assert_eq!(MacroIcon::ItemHammer.key(), 0x014);
+    /// ```
+    pub fn key(&self) -> u16 {
+        self.entry().key
+    }
+
+    /// Returns the name of the icon, which is the name of its enum variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    ///
+    /// assert_eq!(MacroIcon::ItemHammer.name(), "ItemHammer");
+    /// assert_eq!("item hammer".parse::<MacroIcon>().unwrap(), MacroIcon::ItemHammer);
+    /// ```
+    pub fn name(&self) -> &'static str {
+        self.entry().name
+    }
+}
+
+impl fmt::Debug for MacroIconRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MacroIconRef")
+            .field("icon_id", &self.icon_id_hex())
+            .field("key", &self.key_hex())
+            .finish()
+    }
+}
+
+impl From<MacroIcon> for MacroIconRef {
+    fn from(icon: MacroIcon) -> Self {
+        MacroIconRef::from(&icon)
+    }
+}
+
+impl From<&MacroIcon> for MacroIconRef {
+    fn from(icon: &MacroIcon) -> Self {
+        MacroIconRef {
+            icon_id: icon.icon_id(),
+            icon_id_hex: to_hex_digits(icon.icon_id()),
+            key: icon.key(),
+            key_hex: to_hex_digits(u32::from(icon.key())),
+        }
+    }
+}
+
+impl TryFrom<MacroIconRef> for MacroIcon {
+    type Error = DATError;
+
+    fn try_from(icon_ref: MacroIconRef) -> Result<Self, Self::Error> {
+        match icon_ref.icon() {
+            Some(icon) => Ok(icon),
+            None => Err(DATError::InvalidInput("Macro icon is invalid.".into())),
+        }
+    }
+}
+
+impl MacroIconRef {
+    /// Parses a key and icon id from the hex strings stored in the key and icon
+    /// [`Sections`](crate::section::Section) of a macro. The key must be 3 hex digits and the id must
+    /// be 7 hex digits. Either case is accepted; digits are stored in uppercase.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the key or id is not the expected number of hex digits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::{MacroIcon, MacroIconRef};
+    ///
+    /// let icon_ref = MacroIconRef::from_hex("014", "00005e9").unwrap();
+    /// assert_eq!(icon_ref.icon(), Some(MacroIcon::ItemHammer));
+    /// assert_eq!(icon_ref.icon_id_hex(), "00005E9");
+    ///
+    /// assert!(MacroIconRef::from_hex("XYZ", "00005E9").is_err());
+    /// ```
+    pub fn from_hex(key: &str, icon_id: &str) -> Result<MacroIconRef, DATError> {
+        let key = parse_hex_digits(key, KEY_DIGITS, "key")?;
+        let icon_id = parse_hex_digits(icon_id, ICON_ID_DIGITS, "id")?;
+        MacroIconRef::new(key as u16, icon_id)
+    }
+
+    /// Returns the [`MacroIcon`] matching the key and icon id, or [`None`] if the pair is not a known icon.
+    pub fn icon(&self) -> Option<MacroIcon> {
+        MacroIcon::from_key_and_id(self.key, self.icon_id)
+    }
+
+    /// Returns the icon id.
+    pub fn icon_id(&self) -> u32 {
+        self.icon_id
+    }
+
+    /// Returns the icon id as 7 uppercase hex digits, as stored in the icon
+    /// [`Section`](crate::section::Section) of a macro.
+    pub fn icon_id_hex(&self) -> &str {
+        std::str::from_utf8(&self.icon_id_hex).unwrap_or_default()
+    }
+
+    /// Returns the icon key.
+    pub fn key(&self) -> u16 {
+        self.key
+    }
+
+    /// Returns the icon key as 3 uppercase hex digits, as stored in the key
+    /// [`Section`](crate::section::Section) of a macro.
+    pub fn key_hex(&self) -> &str {
+        std::str::from_utf8(&self.key_hex).unwrap_or_default()
+    }
+
+    /// Builds a [`MacroIconRef`] from a numeric key and icon id. The pair does not need to match a
+    /// known [`MacroIcon`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::Overflow`] if the key does not fit in 3 hex digits or the id does not fit
+    /// in 7 hex digits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::{MacroIcon, MacroIconRef};
+    ///
+    /// let icon_ref = MacroIconRef::new(0x037, 0x102FF).unwrap();
+    /// assert_eq!(icon_ref.icon(), Some(MacroIcon::SymbolArrowUp));
+    ///
+    /// assert!(MacroIconRef::new(0x1000, 0).is_err());
+    /// ```
+    pub fn new(key: u16, icon_id: u32) -> Result<MacroIconRef, DATError> {
+        if u32::from(key) >> (4 * KEY_DIGITS) != 0 {
+            return Err(DATError::Overflow(
+                format!("Macro icon key is longer than {} hex digits.", KEY_DIGITS).into(),
+            ));
+        }
+        if icon_id >> (4 * ICON_ID_DIGITS) != 0 {
+            return Err(DATError::Overflow(
+                format!("Macro icon id is longer than {} hex digits.", ICON_ID_DIGITS).into(),
+            ));
+        }
+        Ok(MacroIconRef {
+            icon_id,
+            icon_id_hex: to_hex_digits(icon_id),
+            key,
+            key_hex: to_hex_digits(u32::from(key)),
+        })
+    }
 }
 
 /// Returns the [`MacroIcon`] corresponding to the raw values of the key and icon
@@ -191,93 +580,37 @@ pub enum MacroIcon {
 /// assert_eq!(macro_icon_from_key_and_id(key_val, id_val).unwrap(), MacroIcon::ItemHammer);
 /// ```
 pub fn macro_icon_from_key_and_id(key: &str, id: &str) -> Option<MacroIcon> {
-    match (key, id) {
-        ("000", "0000000") => Some(MacroIcon::NoIcon),
-        ("001", "00101D1") => Some(MacroIcon::DefaultIcon),
-        ("002", "0010235") => Some(MacroIcon::DPS1),
-        ("003", "0010236") => Some(MacroIcon::DPS1),
-        ("004", "0010237") => Some(MacroIcon::DPS1),
-        ("005", "0010249") => Some(MacroIcon::Tank1),
-        ("006", "001024A") => Some(MacroIcon::Tank2),
-        ("007", "001024B") => Some(MacroIcon::Tank3),
-        ("008", "001025D") => Some(MacroIcon::Healer1),
-        ("009", "001025E") => Some(MacroIcon::Healer2),
-        ("00A", "001025F") => Some(MacroIcon::Healer3),
-        ("00B", "00101E5") => Some(MacroIcon::CrafterPurple1),
-        ("00C", "00101E6") => Some(MacroIcon::CrafterPurple2),
-        ("00D", "00101E7") => Some(MacroIcon::CrafterPurple3),
-        ("00E", "00101F9") => Some(MacroIcon::CrafterYellow1),
-        ("00F", "00101FA") => Some(MacroIcon::CrafterYellow2),
-        ("010", "00101FB") => Some(MacroIcon::CrafterYellow3),
-        ("011", "001020D") => Some(MacroIcon::CrafterGreen1),
-        ("012", "001020E") => Some(MacroIcon::CrafterGreen2),
-        ("013", "001020F") => Some(MacroIcon::CrafterGreen3),
-        ("014", "00005E9") => Some(MacroIcon::ItemHammer),
-        ("015", "000061B") => Some(MacroIcon::ItemSword),
-        ("016", "000064D") => Some(MacroIcon::ItemShield),
-        ("017", "000067E") => Some(MacroIcon::ItemRing),
-        ("018", "00006B1") => Some(MacroIcon::ItemShoes),
-        ("019", "00006E2") => Some(MacroIcon::ItemHat),
-        ("01A", "0000715") => Some(MacroIcon::ItemBottle),
-        ("01B", "0000746") => Some(MacroIcon::ItemBread),
-        ("01C", "0010221") => Some(MacroIcon::Gatherer1),
-        ("01D", "0010222") => Some(MacroIcon::Gatherer2),
-        ("01E", "0010223") => Some(MacroIcon::Gatherer3),
-        ("01F", "0010271") => Some(MacroIcon::Number0),
-        ("020", "0010272") => Some(MacroIcon::Number1),
-        ("021", "0010273") => Some(MacroIcon::Number2),
-        ("022", "0010274") => Some(MacroIcon::Number3),
-        ("023", "0010275") => Some(MacroIcon::Number4),
-        ("024", "0010276") => Some(MacroIcon::Number5),
-        ("025", "0010277") => Some(MacroIcon::Number6),
-        ("026", "0010278") => Some(MacroIcon::Number7),
-        ("027", "0010279") => Some(MacroIcon::Number8),
-        ("028", "001027A") => Some(MacroIcon::Number9),
-        ("029", "001027B") => Some(MacroIcon::Number10),
-        ("02A", "0010285") => Some(MacroIcon::InverseNumber0),
-        ("02B", "0010286") => Some(MacroIcon::InverseNumber1),
-        ("02C", "0010287") => Some(MacroIcon::InverseNumber2),
-        ("02D", "0010288") => Some(MacroIcon::InverseNumber3),
-        ("02E", "0010289") => Some(MacroIcon::InverseNumber4),
-        ("02F", "001028A") => Some(MacroIcon::InverseNumber5),
-        ("030", "001028B") => Some(MacroIcon::InverseNumber6),
-        ("031", "001028C") => Some(MacroIcon::InverseNumber7),
-        ("032", "001028D") => Some(MacroIcon::InverseNumber8),
-        ("033", "001028E") => Some(MacroIcon::InverseNumber9),
-        ("034", "001028F") => Some(MacroIcon::InverseNumber10),
-        ("035", "00102FD") => Some(MacroIcon::SymbolArrowLeft),
-        ("036", "00102FE") => Some(MacroIcon::SymbolArrowRight),
-        ("037", "00102FF") => Some(MacroIcon::SymbolArrowUp),
-        ("038", "0010300") => Some(MacroIcon::SymbolArrowDown),
-        ("039", "0010301") => Some(MacroIcon::SymbolCircle),
-        ("03A", "0010302") => Some(MacroIcon::SymbolTriangle),
-        ("03B", "0010303") => Some(MacroIcon::SymbolSquare),
-        ("03C", "0010304") => Some(MacroIcon::SymbolX),
-        ("03D", "0010305") => Some(MacroIcon::SymbolNo),
-        ("03E", "0010306") => Some(MacroIcon::SymbolWarning),
-        ("03F", "0010307") => Some(MacroIcon::SymbolCheck),
-        ("040", "0010308") => Some(MacroIcon::SymbolStar),
-        ("041", "0010309") => Some(MacroIcon::SymbolQuestion),
-        ("042", "001030A") => Some(MacroIcon::SymbolExclamation),
-        ("043", "001030B") => Some(MacroIcon::SymbolPlus),
-        ("044", "001030C") => Some(MacroIcon::SymbolMinus),
-        ("045", "001030D") => Some(MacroIcon::SymbolClock),
-        ("046", "001030E") => Some(MacroIcon::SymbolBulb),
-        ("047", "001030F") => Some(MacroIcon::SymbolCog),
-        ("048", "0010310") => Some(MacroIcon::SymbolSearch),
-        ("049", "0010311") => Some(MacroIcon::SymbolSpeech),
-        ("04A", "0010312") => Some(MacroIcon::SymbolHeart),
-        ("04B", "0010313") => Some(MacroIcon::SymbolSpade),
-        ("04C", "0010314") => Some(MacroIcon::SymbolClub),
-        ("04D", "0010315") => Some(MacroIcon::SymbolDiamond),
-        ("04E", "0010316") => Some(MacroIcon::SymbolDice),
-        ("04F", "0004E27") => Some(MacroIcon::CrystalFire),
-        ("050", "0004E29") => Some(MacroIcon::CrystalIce),
-        ("051", "0004E2A") => Some(MacroIcon::CrystalWind),
-        ("052", "0004E2C") => Some(MacroIcon::CrystalEarth),
-        ("053", "0004E2B") => Some(MacroIcon::CrystalLightning),
-        ("054", "0004E28") => Some(MacroIcon::CrystalWater),
-        _ => None,
+    MacroIcon::ALL.iter().copied().find(|icon| {
+        let entry = icon.entry();
+        entry.key_str == key && entry.id_str == id
+    })
+}
+
+/// Returns the [`MacroIcon`] with a given name, as written in shared macros. Names are the enum variant names;
+/// matching is case-insensitive and ignores spaces, hyphens, and underscores. `"Default"` and `"None"`
+/// are also accepted for [`MacroIcon::DefaultIcon`] and [`MacroIcon::NoIcon`].
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::xiv_macro::icon::{MacroIcon, macro_icon_from_name};
+///
+/// assert_eq!(macro_icon_from_name("Item Hammer"), Some(MacroIcon::ItemHammer));
+/// assert_eq!(macro_icon_from_name("tank_1"), Some(MacroIcon::Tank1));
+/// assert_eq!(macro_icon_from_name("Hammer"), None);
+/// ```
+pub fn macro_icon_from_name(name: &str) -> Option<MacroIcon> {
+    let normalized: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    match normalized.as_str() {
+        "default" => Some(MacroIcon::DefaultIcon),
+        "none" => Some(MacroIcon::NoIcon),
+        _ => MacroIcon::ALL
+            .iter()
+            .copied()
+            .find(|icon| icon.name().eq_ignore_ascii_case(&normalized)),
     }
 }
 
@@ -292,92 +625,82 @@ pub fn macro_icon_from_key_and_id(key: &str, id: &str) -> Option<MacroIcon> {
 /// assert_eq!(key_val, "014");
 /// assert_eq!(id_val, "00005E9");
 /// ```
-pub fn macro_icon_to_key_and_id(macro_icon: &MacroIcon) -> (&str, &str) {
-    match macro_icon {
-        MacroIcon::NoIcon => ("000", "0000000"),
-        MacroIcon::DefaultIcon => ("001", "00101D1"),
-        MacroIcon::DPS1 => ("002", "0010235"),
-        MacroIcon::DPS2 => ("003", "0010236"),
-        MacroIcon::DPS3 => ("004", "0010237"),
-        MacroIcon::Tank1 => ("005", "0010249"),
-        MacroIcon::Tank2 => ("006", "001024A"),
-        MacroIcon::Tank3 => ("007", "001024B"),
-        MacroIcon::Healer1 => ("008", "001025D"),
-        MacroIcon::Healer2 => ("009", "001025E"),
-        MacroIcon::Healer3 => ("00A", "001025F"),
-        MacroIcon::CrafterPurple1 => ("00B", "00101E5"),
-        MacroIcon::CrafterPurple2 => ("00C", "00101E6"),
-        MacroIcon::CrafterPurple3 => ("00D", "00101E7"),
-        MacroIcon::CrafterYellow1 => ("00E", "00101F9"),
-        MacroIcon::CrafterYellow2 => ("00F", "00101FA"),
-        MacroIcon::CrafterYellow3 => ("010", "00101FB"),
-        MacroIcon::CrafterGreen1 => ("011", "001020D"),
-        MacroIcon::CrafterGreen2 => ("012", "001020E"),
-        MacroIcon::CrafterGreen3 => ("013", "001020F"),
-        MacroIcon::ItemHammer => ("014", "00005E9"),
-        MacroIcon::ItemSword => ("015", "000061B"),
-        MacroIcon::ItemShield => ("016", "000064D"),
-        MacroIcon::ItemRing => ("017", "000067E"),
-        MacroIcon::ItemShoes => ("018", "00006B1"),
-        MacroIcon::ItemHat => ("019", "00006E2"),
-        MacroIcon::ItemBottle => ("01A", "0000715"),
-        MacroIcon::ItemBread => ("01B", "0000746"),
-        MacroIcon::Gatherer1 => ("01C", "0010221"),
-        MacroIcon::Gatherer2 => ("01D", "0010222"),
-        MacroIcon::Gatherer3 => ("01E", "0010223"),
-        MacroIcon::Number0 => ("01F", "0010271"),
-        MacroIcon::Number1 => ("020", "0010272"),
-        MacroIcon::Number2 => ("021", "0010273"),
-        MacroIcon::Number3 => ("022", "0010274"),
-        MacroIcon::Number4 => ("023", "0010275"),
-        MacroIcon::Number5 => ("024", "0010276"),
-        MacroIcon::Number6 => ("025", "0010277"),
-        MacroIcon::Number7 => ("026", "0010278"),
-        MacroIcon::Number8 => ("027", "0010279"),
-        MacroIcon::Number9 => ("028", "001027A"),
-        MacroIcon::Number10 => ("029", "001027B"),
-        MacroIcon::InverseNumber0 => ("02A", "0010285"),
-        MacroIcon::InverseNumber1 => ("02B", "0010286"),
-        MacroIcon::InverseNumber2 => ("02C", "0010287"),
-        MacroIcon::InverseNumber3 => ("02D", "0010288"),
-        MacroIcon::InverseNumber4 => ("02E", "0010289"),
-        MacroIcon::InverseNumber5 => ("02F", "001028A"),
-        MacroIcon::InverseNumber6 => ("030", "001028B"),
-        MacroIcon::InverseNumber7 => ("031", "001028C"),
-        MacroIcon::InverseNumber8 => ("032", "001028D"),
-        MacroIcon::InverseNumber9 => ("033", "001028E"),
-        MacroIcon::InverseNumber10 => ("034", "001028F"),
-        MacroIcon::SymbolArrowLeft => ("035", "00102FD"),
-        MacroIcon::SymbolArrowRight => ("036", "00102FE"),
-        MacroIcon::SymbolArrowUp => ("037", "00102FF"),
-        MacroIcon::SymbolArrowDown => ("038", "0010300"),
-        MacroIcon::SymbolCircle => ("039", "0010301"),
-        MacroIcon::SymbolTriangle => ("03A", "0010302"),
-        MacroIcon::SymbolSquare => ("03B", "0010303"),
-        MacroIcon::SymbolX => ("03C", "0010304"),
-        MacroIcon::SymbolNo => ("03D", "0010305"),
-        MacroIcon::SymbolWarning => ("03E", "0010306"),
-        MacroIcon::SymbolCheck => ("03F", "0010307"),
-        MacroIcon::SymbolStar => ("040", "0010308"),
-        MacroIcon::SymbolQuestion => ("041", "0010309"),
-        MacroIcon::SymbolExclamation => ("042", "001030A"),
-        MacroIcon::SymbolPlus => ("043", "001030B"),
-        MacroIcon::SymbolMinus => ("044", "001030C"),
-        MacroIcon::SymbolClock => ("045", "001030D"),
-        MacroIcon::SymbolBulb => ("046", "001030E"),
-        MacroIcon::SymbolCog => ("047", "001030F"),
-        MacroIcon::SymbolSearch => ("048", "0010310"),
-        MacroIcon::SymbolSpeech => ("049", "0010311"),
-        MacroIcon::SymbolHeart => ("04A", "0010312"),
-        MacroIcon::SymbolSpade => ("04B", "0010313"),
-        MacroIcon::SymbolClub => ("04C", "0010314"),
-        MacroIcon::SymbolDiamond => ("04D", "0010315"),
-        MacroIcon::SymbolDice => ("04E", "0010316"),
-        MacroIcon::CrystalFire => ("04F", "0004E27"),
-        MacroIcon::CrystalIce => ("050", "0004E29"),
-        MacroIcon::CrystalWind => ("051", "0004E2A"),
-        MacroIcon::CrystalEarth => ("052", "0004E2C"),
-        MacroIcon::CrystalLightning => ("053", "0004E2B"),
-        MacroIcon::CrystalWater => ("054", "0004E28"),
+pub fn macro_icon_to_key_and_id(macro_icon: &MacroIcon) -> (&'static str, &'static str) {
+    let entry = macro_icon.entry();
+    (entry.key_str, entry.id_str)
+}
+
+/// Parses a string of exactly `digits` hex digits.
+fn parse_hex_digits(value: &str, digits: usize, name: &str) -> Result<u32, DATError> {
+    if value.len() != digits || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(DATError::InvalidInput(
+            format!("Macro icon {} is not {} hex digits: {:?}.", name, digits, value).into(),
+        ));
+    }
+    u32::from_str_radix(value, 16).map_err(|_| DATError::InvalidInput("Macro icon is invalid.".into()))
+}
+
+/// Formats the low digits of a value as uppercase hex, zero-padded to `N` digits.
+fn to_hex_digits<const N: usize>(value: u32) -> [u8; N] {
+    let mut digits = [b'0'; N];
+    for (i, digit) in digits.iter_mut().rev().enumerate() {
+        *digit = b"0123456789ABCDEF"[(value >> (4 * i) & 0xF) as usize];
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- MacroIcon
+
+    #[test]
+    fn test_macro_icon_round_trip() -> Result<(), String> {
+        for icon in MacroIcon::ALL.iter() {
+            let (key, id) = macro_icon_to_key_and_id(icon);
+            assert_eq!(macro_icon_from_key_and_id(key, id), Some(*icon), "{}", icon);
+            assert_eq!(MacroIcon::from_key_and_id(icon.key(), icon.icon_id()), Some(*icon));
+            assert_eq!(u16::from_str_radix(key, 16), Ok(icon.key()));
+            assert_eq!(u32::from_str_radix(id, 16), Ok(icon.icon_id()));
+            assert_eq!(key.len(), 3);
+            assert_eq!(id.len(), 7);
+            match icon.to_string().parse::<MacroIcon>() {
+                Ok(parsed) => assert_eq!(parsed, *icon),
+                Err(err) => return Err(format!("Error: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_icon_all_unique() -> Result<(), String> {
+        for (i, icon) in MacroIcon::ALL.iter().enumerate() {
+            for other in MacroIcon::ALL[i + 1..].iter() {
+                assert_ne!(icon.key(), other.key(), "{} {}", icon, other);
+                assert_ne!(icon.icon_id(), other.icon_id(), "{} {}", icon, other);
+                assert_ne!(icon.name(), other.name());
+            }
+        }
+        assert_eq!(MacroIcon::ALL.len(), 85);
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_icon_dps() -> Result<(), String> {
+        assert_eq!(macro_icon_from_key_and_id("003", "0010236"), Some(MacroIcon::DPS2));
+        assert_eq!(macro_icon_from_key_and_id("004", "0010237"), Some(MacroIcon::DPS3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_icon_from_str_error() -> Result<(), String> {
+        match "Sparkles".parse::<MacroIcon>() {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
     }
 }
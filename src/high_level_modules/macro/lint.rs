@@ -0,0 +1,394 @@
+use super::script::{parse_line, Placeholder, Token};
+use std::fmt;
+
+/// The maximum number of seconds the game client accepts for `/wait` and `<wait.N>`.
+pub const MAX_WAIT_SECONDS: u8 = 60;
+
+/// Text commands recognized by [`lint_lines()`]. Commands not in this list are reported as
+/// [`LintKind::UnknownCommand`]. This covers common commands and emotes, but is not exhaustive.
+pub const KNOWN_COMMANDS: &[&str] = &[
+    "a",
+    "ac",
+    "action",
+    "alliance",
+    "as",
+    "assist",
+    "battlemode",
+    "bm",
+    "bow",
+    "cheer",
+    "clap",
+    "cwl1",
+    "cwl2",
+    "cwl3",
+    "cwl4",
+    "cwl5",
+    "cwl6",
+    "cwl7",
+    "cwl8",
+    "cwlinkshell1",
+    "cwlinkshell2",
+    "cwlinkshell3",
+    "cwlinkshell4",
+    "cwlinkshell5",
+    "cwlinkshell6",
+    "cwlinkshell7",
+    "cwlinkshell8",
+    "dance",
+    "e",
+    "echo",
+    "em",
+    "emote",
+    "enemysign",
+    "equip",
+    "facetarget",
+    "fc",
+    "focustarget",
+    "follow",
+    "freecompany",
+    "gearset",
+    "gs",
+    "hotbar",
+    "item",
+    "l1",
+    "l2",
+    "l3",
+    "l4",
+    "l5",
+    "l6",
+    "l7",
+    "l8",
+    "linkshell1",
+    "linkshell2",
+    "linkshell3",
+    "linkshell4",
+    "linkshell5",
+    "linkshell6",
+    "linkshell7",
+    "linkshell8",
+    "lockon",
+    "macrocancel",
+    "macroerror",
+    "macroicon",
+    "macrolock",
+    "marking",
+    "mcancel",
+    "merror",
+    "micon",
+    "minion",
+    "mk",
+    "mlock",
+    "mount",
+    "n",
+    "no",
+    "novice",
+    "p",
+    "party",
+    "petbar",
+    "r",
+    "reply",
+    "s",
+    "say",
+    "sh",
+    "shout",
+    "sit",
+    "statusoff",
+    "t",
+    "ta",
+    "target",
+    "targetenemy",
+    "targetnpc",
+    "targetpc",
+    "tell",
+    "tenemy",
+    "thumbsup",
+    "wait",
+    "wave",
+    "waymark",
+    "wm",
+    "y",
+    "yell",
+    "yes",
+];
+
+/// A single problem found in a macro by [`lint_lines()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The problem that was found.
+    pub kind: LintKind,
+    /// The line number of the offending line, starting from 1.
+    pub line: usize,
+    /// How severe the problem is.
+    pub severity: Severity,
+}
+
+/// The kinds of problems reported by [`lint_lines()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LintKind {
+    /// A `/micon` or `/macroicon` command appears on a line other than the first.
+    MiconNotFirst,
+    /// A line contains more than one `<wait.N>` suffix. Only the last is used by the game client.
+    MultipleWaits(usize),
+    /// A line ends in whitespace.
+    TrailingWhitespace,
+    /// A line contains an odd number of double quotes.
+    UnbalancedQuotes,
+    /// A line starts with a command not in [`KNOWN_COMMANDS`]. Contains the command name without the slash.
+    UnknownCommand(String),
+    /// A `/wait` or `<wait.N>` exceeds [`MAX_WAIT_SECONDS`]. Contains the wait time as written.
+    WaitTooLong(String),
+}
+
+/// The severity of a [`Diagnostic`]. Severities are ordered from least to most severe.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// A stylistic issue that does not affect how the macro runs.
+    Info,
+    /// The macro will run, but likely not as intended.
+    Warning,
+    /// The game client will reject or fail to run the line.
+    Error,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.severity, self.kind)
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintKind::MiconNotFirst => write!(f, "/micon should be the first line of the macro."),
+            LintKind::MultipleWaits(count) => write!(f, "Line has {} waits; only the last is used.", count),
+            LintKind::TrailingWhitespace => write!(f, "Line has trailing whitespace."),
+            LintKind::UnbalancedQuotes => write!(f, "Line has unbalanced quotes."),
+            LintKind::UnknownCommand(name) => write!(f, "Unknown command /{}.", name),
+            LintKind::WaitTooLong(secs) => write!(
+                f,
+                "Wait of {} seconds exceeds the maximum of {} seconds.",
+                secs, MAX_WAIT_SECONDS
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl LintKind {
+    /// Returns the [`Severity`] reported for this kind of problem.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::lint::{LintKind, Severity};
+    ///
+    /// assert_eq!(LintKind::UnbalancedQuotes.severity(), Severity::Error);
+    /// assert_eq!(LintKind::TrailingWhitespace.severity(), Severity::Info);
+    /// ```
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintKind::UnbalancedQuotes | LintKind::WaitTooLong(_) => Severity::Error,
+            LintKind::MiconNotFirst | LintKind::MultipleWaits(_) | LintKind::UnknownCommand(_) => Severity::Warning,
+            LintKind::TrailingWhitespace => Severity::Info,
+        }
+    }
+}
+
+/// Checks macro lines against the behavior of the game client and returns a [`Diagnostic`] for each
+/// problem found, in line order. Unlike [`Validate`](crate::high_level::Validate), lints do not prevent a
+/// macro from being written.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::lint::{lint_lines, LintKind, Severity};
+///
+/// let diagnostics = lint_lines(&["/ac \"Fire IV\" <t> <wait.3>", "/micon \"Fire IV\"", "/wait 90"]);
+///
+/// assert_eq!(diagnostics.len(), 2);
+/// assert_eq!(diagnostics[0].line, 2);
+/// assert_eq!(diagnostics[0].kind, LintKind::MiconNotFirst);
+/// assert_eq!(diagnostics[1].line, 3);
+/// assert_eq!(diagnostics[1].severity, Severity::Error);
+/// ```
+pub fn lint_lines<S: AsRef<str>>(lines: &[S]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::<Diagnostic>::new();
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        let mut push = |kind: LintKind| {
+            diagnostics.push(Diagnostic {
+                severity: kind.severity(),
+                kind,
+                line: index + 1,
+            })
+        };
+        let parsed = parse_line(line);
+
+        if let Some(name) = parsed.command() {
+            let lower = name.to_lowercase();
+            if !KNOWN_COMMANDS.contains(&lower.as_str()) {
+                push(LintKind::UnknownCommand(name.to_owned()));
+            }
+            if index > 0 && (lower == "micon" || lower == "macroicon") {
+                push(LintKind::MiconNotFirst);
+            }
+            if lower == "wait" {
+                if let Some(Token::Text(secs)) = parsed.arguments().next() {
+                    if exceeds_max_wait(secs) {
+                        push(LintKind::WaitTooLong(secs.to_owned()));
+                    }
+                }
+            }
+        }
+
+        let mut wait_count = 0;
+        for token in parsed.tokens.iter() {
+            let secs = match token {
+                Token::Wait(secs) => secs.to_string(),
+                Token::Placeholder(Placeholder::Other(name)) => match name.strip_prefix("wait.") {
+                    Some(secs) => secs.to_owned(),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            wait_count += 1;
+            if exceeds_max_wait(&secs) {
+                push(LintKind::WaitTooLong(secs));
+            }
+        }
+        if wait_count > 1 {
+            push(LintKind::MultipleWaits(wait_count));
+        }
+
+        if line.matches('"').count() % 2 != 0 {
+            push(LintKind::UnbalancedQuotes);
+        }
+        if line.ends_with(char::is_whitespace) {
+            push(LintKind::TrailingWhitespace);
+        }
+    }
+    diagnostics
+}
+
+/// Returns true if a wait time parses as a number of seconds greater than [`MAX_WAIT_SECONDS`].
+fn exceeds_max_wait(secs: &str) -> bool {
+    secs.parse::<f64>()
+        .map_or(false, |secs| secs > f64::from(MAX_WAIT_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(lines: &[&str]) -> Vec<(usize, LintKind)> {
+        lint_lines(lines)
+            .into_iter()
+            .map(|diag| (diag.line, diag.kind))
+            .collect()
+    }
+
+    // --- Module Functions
+
+    #[test]
+    fn test_lint_lines_clean() -> Result<(), String> {
+        let lines = [
+            "/micon \"Fire IV\"",
+            "/ac \"Fire IV\" <t> <wait.3>",
+            "/wait 60",
+            "/echo Done! <se.1>",
+            "Hello <t>!",
+            "/p <\u{3000}> 「こんにちは」",
+            "",
+        ];
+        assert_eq!(kinds(&lines), vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_wait_too_long() -> Result<(), String> {
+        assert_eq!(
+            kinds(&["/wait 61", "/ac Sprint <wait.90>", "/p <wait.300>", "/wait 60.5"]),
+            vec![
+                (1, LintKind::WaitTooLong("61".to_owned())),
+                (2, LintKind::WaitTooLong("90".to_owned())),
+                (3, LintKind::WaitTooLong("300".to_owned())),
+                (4, LintKind::WaitTooLong("60.5".to_owned())),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_multiple_waits() -> Result<(), String> {
+        assert_eq!(
+            kinds(&["/p a <wait.1> b <wait.2>"]),
+            vec![(1, LintKind::MultipleWaits(2))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_unknown_command() -> Result<(), String> {
+        assert_eq!(
+            kinds(&["/notacommand <t>", "/AC Sprint"]),
+            vec![(1, LintKind::UnknownCommand("notacommand".to_owned()))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_unbalanced_quotes() -> Result<(), String> {
+        let diagnostics = lint_lines(&["/ac \"Fire IV <t>"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, LintKind::UnbalancedQuotes);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_micon_not_first() -> Result<(), String> {
+        assert_eq!(
+            kinds(&["/ac Sprint", "/macroicon Sprint"]),
+            vec![(2, LintKind::MiconNotFirst)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_lines_trailing_whitespace() -> Result<(), String> {
+        let diagnostics = lint_lines(&["/ac Sprint ", "   ", "/p <\u{3000}"]);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].kind, LintKind::TrailingWhitespace);
+        assert_eq!(diagnostics[1].line, 2);
+        assert_eq!(diagnostics[1].severity, Severity::Info);
+        assert_eq!(diagnostics[2].kind, LintKind::TrailingWhitespace);
+        Ok(())
+    }
+
+    // --- Diagnostic
+
+    #[test]
+    fn test_diagnostic_display() -> Result<(), String> {
+        let diagnostics = lint_lines(&["", "/foo"]);
+        assert_eq!(diagnostics[0].to_string(), "line 2: warning: Unknown command /foo.");
+        Ok(())
+    }
+
+    // --- Severity
+
+    #[test]
+    fn test_severity_ordering() -> Result<(), String> {
+        assert!(Severity::Error > Severity::Warning);
+        assert!(Severity::Warning > Severity::Info);
+        Ok(())
+    }
+}
@@ -0,0 +1,427 @@
+use std::fmt;
+
+/// A parsed macro line. A [`MacroLine`] preserves all whitespace and renders back to
+/// the exact text it was parsed from via its [`Display`](std::fmt::Display) implementation.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::script::{parse_line, Placeholder, Token};
+///
+/// let line = parse_line("/ac \"Fire IV\" <t> <wait.3>");
+///
+/// assert_eq!(line.command(), Some("ac"));
+/// assert_eq!(line.wait(), Some(3));
+/// assert_eq!(
+///     line.arguments().collect::<Vec<_>>(),
+///     vec![
+///         &Token::Quoted("Fire IV".to_string()),
+///         &Token::Placeholder(Placeholder::Target),
+///         &Token::Wait(3)
+///     ]
+/// );
+/// assert_eq!(line.to_string(), "/ac \"Fire IV\" <t> <wait.3>");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MacroLine {
+    /// The tokens comprising the line, in order, including whitespace.
+    pub tokens: Vec<Token>,
+}
+
+/// A target placeholder, such as `<t>`, which the game client replaces with the name of an entity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Placeholder {
+    /// `<f>`, the current focus target.
+    Focus,
+    /// `<lt>`, the last target.
+    LastTarget,
+    /// `<me>`, the player character.
+    Me,
+    /// `<mo>`, the entity under the mouse cursor.
+    MouseOver,
+    /// Any other placeholder, such as `<pos>` or `<attack1>`, stored without the brackets.
+    Other(String),
+    /// `<1>` through `<8>`, a party member by list position.
+    PartyMember(u8),
+    /// `<t>`, the current target.
+    Target,
+    /// `<tt>`, the target of the current target.
+    TargetOfTarget,
+}
+
+/// A single token of a [`MacroLine`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Token {
+    /// A text command, such as `/ac`, stored without the leading slash. Only the first word of
+    /// a line is parsed as a command.
+    Command(String),
+    /// A target placeholder, such as `<t>`.
+    Placeholder(Placeholder),
+    /// A double-quoted argument, such as `"Fire IV"`, stored without the quotes.
+    Quoted(String),
+    /// A sound effect, such as `<se.1>`, stored as the sound effect number.
+    SoundEffect(u8),
+    /// Unquoted text, such as the `3` in `/wait 3` or the contents of a chat message.
+    Text(String),
+    /// A wait suffix, such as `<wait.3>`, stored as a number of seconds.
+    Wait(u8),
+    /// A run of whitespace between other tokens.
+    Whitespace(String),
+}
+
+impl fmt::Display for MacroLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in self.tokens.iter() {
+            write!(f, "{}", token)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for MacroLine {
+    fn from(x: &str) -> Self {
+        parse_line(x)
+    }
+}
+
+impl fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Placeholder::Focus => write!(f, "<f>"),
+            Placeholder::LastTarget => write!(f, "<lt>"),
+            Placeholder::Me => write!(f, "<me>"),
+            Placeholder::MouseOver => write!(f, "<mo>"),
+            Placeholder::Other(name) => write!(f, "<{}>", name),
+            Placeholder::PartyMember(num) => write!(f, "<{}>", num),
+            Placeholder::Target => write!(f, "<t>"),
+            Placeholder::TargetOfTarget => write!(f, "<tt>"),
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Command(name) => write!(f, "/{}", name),
+            Token::Placeholder(placeholder) => write!(f, "{}", placeholder),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::SoundEffect(num) => write!(f, "<se.{}>", num),
+            Token::Text(text) | Token::Whitespace(text) => write!(f, "{}", text),
+            Token::Wait(secs) => write!(f, "<wait.{}>", secs),
+        }
+    }
+}
+
+impl MacroLine {
+    /// Returns an iterator over all non-whitespace tokens following the command. If the line
+    /// has no command, this includes every non-whitespace token.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::script::{parse_line, Token};
+    ///
+    /// let line = parse_line("/wait 3");
+    /// assert_eq!(line.arguments().collect::<Vec<_>>(), vec![&Token::Text("3".to_string())]);
+    /// ```
+    pub fn arguments(&self) -> impl Iterator<Item = &Token> {
+        self.tokens
+            .iter()
+            .filter(|token| !matches!(token, Token::Command(_) | Token::Whitespace(_)))
+    }
+
+    /// Returns the name of the line's command without the leading slash, or [`None`]
+    /// if the line is plain chat text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::script::parse_line;
+    ///
+    /// assert_eq!(parse_line("/micon \"Fire IV\"").command(), Some("micon"));
+    /// assert_eq!(parse_line("Hello <t>!").command(), None);
+    /// ```
+    pub fn command(&self) -> Option<&str> {
+        self.tokens.iter().find_map(|token| match token {
+            Token::Command(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the number of seconds the game client will wait after executing the line, as
+    /// set by a `<wait.N>` suffix. If a line contains multiple waits, the last one is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::script::parse_line;
+    ///
+    /// assert_eq!(parse_line("/ac Sprint <wait.2>").wait(), Some(2));
+    /// assert_eq!(parse_line("/ac Sprint").wait(), None);
+    /// ```
+    pub fn wait(&self) -> Option<u8> {
+        self.tokens.iter().rev().find_map(|token| match token {
+            Token::Wait(secs) => Some(*secs),
+            _ => None,
+        })
+    }
+}
+
+impl Placeholder {
+    /// Returns the [`Placeholder`] for the contents of a bracketed placeholder, ie `t` for `<t>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::script::Placeholder;
+    ///
+    /// assert_eq!(Placeholder::from_name("mo"), Placeholder::MouseOver);
+    /// assert_eq!(Placeholder::from_name("2"), Placeholder::PartyMember(2));
+    /// assert_eq!(Placeholder::from_name("pos"), Placeholder::Other("pos".to_string()));
+    /// ```
+    pub fn from_name(name: &str) -> Placeholder {
+        match name {
+            "f" => Placeholder::Focus,
+            "lt" => Placeholder::LastTarget,
+            "me" => Placeholder::Me,
+            "mo" => Placeholder::MouseOver,
+            "t" => Placeholder::Target,
+            "tt" => Placeholder::TargetOfTarget,
+            _ => match parse_number(name) {
+                Some(num) if (1..=8).contains(&num) => Placeholder::PartyMember(num),
+                _ => Placeholder::Other(name.to_owned()),
+            },
+        }
+    }
+}
+
+/// Parses a single macro line into a [`MacroLine`].
+///
+/// Parsing never fails. Text that does not match a more specific token is preserved as
+/// [`Token::Text`], so that the resulting line always renders back to the exact input.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::script::{parse_line, Token};
+///
+/// let line = parse_line("/echo Done! <se.1>");
+///
+/// assert_eq!(line.command(), Some("echo"));
+/// assert_eq!(line.tokens.last(), Some(&Token::SoundEffect(1)));
+/// assert_eq!(line.to_string(), "/echo Done! <se.1>");
+/// ```
+pub fn parse_line(line: &str) -> MacroLine {
+    let mut tokens = Vec::<Token>::new();
+    let mut rest = line;
+    let mut word_start = true;
+    while let Some(next) = rest.chars().next() {
+        let len = if next.is_whitespace() {
+            let len = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+            tokens.push(Token::Whitespace(rest[..len].to_owned()));
+            word_start = true;
+            len
+        } else if let Some(len) = bracket_len(rest) {
+            tokens.push(parse_bracket(&rest[1..len - 1]));
+            word_start = false;
+            len
+        } else if let Some(len) = quoted_len(rest).filter(|_| word_start) {
+            tokens.push(Token::Quoted(rest[1..len - 1].to_owned()));
+            word_start = false;
+            len
+        } else {
+            let len = text_len(rest);
+            let is_command = next == '/' && tokens.iter().all(|token| matches!(token, Token::Whitespace(_)));
+            tokens.push(if is_command {
+                Token::Command(rest[1..len].to_owned())
+            } else {
+                Token::Text(rest[..len].to_owned())
+            });
+            word_start = false;
+            len
+        };
+        rest = &rest[len..];
+    }
+    MacroLine { tokens }
+}
+
+/// Returns the byte length of a bracketed token, such as `<t>`, at the start of the input if one exists.
+/// Brackets must be non-empty and may not contain whitespace or another opening bracket.
+fn bracket_len(text: &str) -> Option<usize> {
+    if !text.starts_with('<') {
+        return None;
+    }
+    let close = text[1..].find(|c: char| c == '>' || c == '<' || c.is_whitespace())? + 1;
+    // `close` may be the start of a multi-byte whitespace char, so check the char rather than slicing one byte.
+    if text[close..].starts_with('>') && close > 1 {
+        Some(close + 1)
+    } else {
+        None
+    }
+}
+
+/// Parses the contents of a bracketed token, ie `wait.3` for `<wait.3>`.
+fn parse_bracket(name: &str) -> Token {
+    if let Some(secs) = name.strip_prefix("wait.").and_then(parse_number) {
+        return Token::Wait(secs);
+    }
+    if let Some(num) = name.strip_prefix("se.").and_then(parse_number) {
+        return Token::SoundEffect(num);
+    }
+    Token::Placeholder(Placeholder::from_name(name))
+}
+
+/// Parses a [`u8`], but only if it renders back to the exact input. This rejects forms such as
+/// `03` or `+3` that would not round-trip.
+fn parse_number(text: &str) -> Option<u8> {
+    text.parse::<u8>().ok().filter(|num| num.to_string() == text)
+}
+
+/// Returns the byte length of a double-quoted token at the start of the input if it has a closing quote.
+fn quoted_len(text: &str) -> Option<usize> {
+    if !text.starts_with('"') {
+        return None;
+    }
+    text[1..].find('"').map(|close| close + 2)
+}
+
+/// Returns the byte length of the run of plain text at the start of the input, ending at
+/// whitespace or the start of a bracketed token.
+fn text_len(text: &str) -> usize {
+    text.char_indices()
+        .skip(1)
+        .find(|(i, c)| c.is_whitespace() || bracket_len(&text[*i..]).is_some())
+        .map_or(text.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUND_TRIP_LINES: [&str; 15] = [
+        "",
+        "   ",
+        "/ac \"Fire IV\" <t>",
+        "/ac   \"Fire IV\"\t<t> <wait.3>",
+        "/micon \"Fire IV\"",
+        "/echo Ready! <se.1>",
+        "  /wait 3",
+        "Hello <t>, I'm <me>!",
+        "/ac \"Unterminated <t>",
+        "/p <wait.03> <se.x> <> < t> <wait.300>",
+        "/ac<t>",
+        "/echo «Ω» <mo>",
+        "/p <\u{3000}>",
+        "/p\u{3000}<t>\u{3000}<wait.3>",
+        "/micon <\u{3000}",
+    ];
+
+    // --- Module Functions
+
+    #[test]
+    fn test_parse_line_round_trip() -> Result<(), String> {
+        for line in ROUND_TRIP_LINES.iter() {
+            assert_eq!(parse_line(line).to_string(), *line);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_tokens() -> Result<(), String> {
+        let line = parse_line("/ac \"Fire IV\" <t> <wait.3>");
+        assert_eq!(
+            line.tokens,
+            vec![
+                Token::Command("ac".to_owned()),
+                Token::Whitespace(" ".to_owned()),
+                Token::Quoted("Fire IV".to_owned()),
+                Token::Whitespace(" ".to_owned()),
+                Token::Placeholder(Placeholder::Target),
+                Token::Whitespace(" ".to_owned()),
+                Token::Wait(3),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_chat_text() -> Result<(), String> {
+        let line = parse_line("Hello <t>! /ac");
+        assert_eq!(line.command(), None);
+        assert_eq!(
+            line.tokens,
+            vec![
+                Token::Text("Hello".to_owned()),
+                Token::Whitespace(" ".to_owned()),
+                Token::Placeholder(Placeholder::Target),
+                Token::Text("!".to_owned()),
+                Token::Whitespace(" ".to_owned()),
+                Token::Text("/ac".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_leading_whitespace() -> Result<(), String> {
+        let line = parse_line("  /wait 3");
+        assert_eq!(line.command(), Some("wait"));
+        assert_eq!(line.arguments().collect::<Vec<_>>(), vec![&Token::Text("3".to_owned())]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_unterminated_quote() -> Result<(), String> {
+        let line = parse_line("/ac \"Fire <t>");
+        assert_eq!(
+            line.arguments().collect::<Vec<_>>(),
+            vec![
+                &Token::Text("\"Fire".to_owned()),
+                &Token::Placeholder(Placeholder::Target)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_noncanonical_brackets() -> Result<(), String> {
+        let line = parse_line("<wait.03><se.x><>");
+        assert_eq!(
+            line.tokens,
+            vec![
+                Token::Placeholder(Placeholder::Other("wait.03".to_owned())),
+                Token::Placeholder(Placeholder::Other("se.x".to_owned())),
+                Token::Text("<>".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_placeholders() -> Result<(), String> {
+        let line = parse_line("<f><lt><me><mo><t><tt><1><8><9>");
+        assert_eq!(
+            line.tokens,
+            vec![
+                Token::Placeholder(Placeholder::Focus),
+                Token::Placeholder(Placeholder::LastTarget),
+                Token::Placeholder(Placeholder::Me),
+                Token::Placeholder(Placeholder::MouseOver),
+                Token::Placeholder(Placeholder::Target),
+                Token::Placeholder(Placeholder::TargetOfTarget),
+                Token::Placeholder(Placeholder::PartyMember(1)),
+                Token::Placeholder(Placeholder::PartyMember(8)),
+                Token::Placeholder(Placeholder::Other("9".to_owned())),
+            ]
+        );
+        Ok(())
+    }
+
+    // --- MacroLine
+
+    #[test]
+    fn test_macro_line_wait_last() -> Result<(), String> {
+        assert_eq!(parse_line("/p <wait.1> text <wait.5>").wait(), Some(5));
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+use super::icon::{macro_icon_from_name, MacroIcon, MacroIconRef};
+use super::text::{from_readable, to_readable};
+use super::{char_count, next_char_len, Macro, MacroLimits};
+use crate::dat_error::DATError;
+
+/// The shortest fence that opens and closes the code block containing a macro's lines.
+pub const CODE_FENCE: &str = "```";
+
+/// A macro imported from shared text by [`parse()`], with a report of any changes needed to make it valid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharedMacro {
+    /// The imported macro, padded to 15 lines.
+    pub content: Macro,
+    /// Content that was cut to fit the game client's limits, in the order it was found.
+    pub truncations: Vec<Truncation>,
+    /// The icon name given in the shared text, if it did not match a [`MacroIcon`] or a raw `key:id` pair.
+    /// The macro uses [`MacroIcon::DefaultIcon`] instead.
+    pub unknown_icon: Option<String>,
+}
+
+/// Content cut from a shared macro to fit within [`MacroLimits`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Truncation {
+    /// Lines were dropped from the end of the macro. Contains the number of lines dropped, not counting
+    /// trailing blank lines.
+    ExtraLines(usize),
+    /// A line was shortened. Contains the index of the line.
+    Line(usize),
+    /// The title was shortened.
+    Title,
+}
+
+impl SharedMacro {
+    /// Returns true if any content was cut to fit the game client's limits.
+    pub fn is_truncated(&self) -> bool {
+        !self.truncations.is_empty()
+    }
+}
+
+/// A macro in the process of being parsed.
+#[derive(Default)]
+struct Draft {
+    icon: Option<String>,
+    lines: Vec<String>,
+    title: Option<String>,
+}
+
+/// Formats a [`Macro`] in the share format used on forums and chat services: a `Title:` line, an `Icon:`
+/// line, and the macro's lines in a code block. Trailing blank lines are omitted.
+///
+/// The title and lines are written with [`to_readable()`], so game glyphs and payloads appear as
+/// placeholders such as `{HQ}`. If the macro's icon is not a known [`MacroIcon`], its raw key and id are
+/// written as `Icon: key:id` in hex. If any line starts with three or more backticks, the code fence is
+/// lengthened so that the line does not close the block.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::icon::MacroIcon;
+/// use libxivdat::xiv_macro::share::export;
+/// use libxivdat::xiv_macro::Macro;
+///
+/// let a_macro = Macro::new(
+///     "Sprint".to_string(),
+///     vec!["/ac Sprint".to_string(), "/p Go!".to_string()],
+///     MacroIcon::SymbolArrowRight,
+/// ).unwrap();
+///
+/// assert_eq!(export(&a_macro), "Title: Sprint\nIcon: SymbolArrowRight\n```\n/ac Sprint\n/p Go!\n```\n");
+/// ```
+pub fn export(a_macro: &Macro) -> String {
+    let mut out = format!("Title: {}\n", to_readable(a_macro.title.as_bytes()));
+    match a_macro.get_icon() {
+        Some(icon) => out.push_str(&format!("Icon: {}\n", icon)),
+        None => out.push_str(&format!(
+            "Icon: {}:{}\n",
+            a_macro.icon.key_hex(),
+            a_macro.icon.icon_id_hex()
+        )),
+    }
+    let line_count = a_macro.lines.len() - a_macro.lines.iter().rev().take_while(|line| line.is_empty()).count();
+    let lines: Vec<String> = a_macro.lines[..line_count]
+        .iter()
+        .map(|line| to_readable(line.as_bytes()))
+        .collect();
+    let longest_fence = lines.iter().map(|line| fence_len(line)).max().unwrap_or(0);
+    let fence = "`".repeat(CODE_FENCE.len().max(longest_fence + 1));
+    out.push_str(&fence);
+    out.push('\n');
+    for line in lines.iter() {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&fence);
+    out.push('\n');
+    out
+}
+
+/// Formats a slice of [`Macros`](Macro) in the share format, separated by blank lines. See [`export()`].
+/// Empty macros are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::share::{export_all, parse};
+/// use libxivdat::xiv_macro::read_macro_content;
+///
+/// let macros = read_macro_content("./resources/TEST_MACRO.DAT").unwrap();
+/// let shared = export_all(&macros[..3]);
+///
+/// assert_eq!(parse(&shared).unwrap().len(), 3);
+/// ```
+pub fn export_all(macros: &[Macro]) -> String {
+    macros
+        .iter()
+        .filter(|a_macro| !a_macro.is_empty())
+        .map(export)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Parses a block of pasted text into [`SharedMacros`](SharedMacro).
+///
+/// Each macro is an optional `Title:` (or `Name:`) line and an optional `Icon:` line followed by a code
+/// block of macro lines fenced with three or more backticks. A block is closed by a fence at least as long
+/// as the one that opened it. Header names are case-insensitive and may be wrapped
+/// in markdown emphasis, such as `**Title:**`. Any other text outside of code blocks is ignored, so a
+/// whole forum post or chat log may be pasted at once. If the text contains no code blocks, all lines that
+/// are not headers are read as a single macro.
+///
+/// Icon names are matched with [`macro_icon_from_name()`](super::icon::macro_icon_from_name). An icon given
+/// as a raw `key:id` pair in hex, as written by [`export()`], is used as is. Titles and lines are read with
+/// [`from_readable()`], so placeholders such as `{HQ}` become game glyphs. Titles and
+/// lines that exceed [`MacroLimits`] are truncated, lines beyond the 15th are dropped, and each such change
+/// is reported in [`SharedMacro::truncations`]. Macros are padded to 15 lines.
+///
+/// # Errors
+///
+/// Returns [`DATError::BadEncoding`] if a title or line contains a payload placeholder that does not
+/// produce valid UTF-8.
+///
+/// Returns [`DATError::Overflow`] if a macro cannot be built after truncation. This should not occur in
+/// practice.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::icon::MacroIcon;
+/// use libxivdat::xiv_macro::share::parse;
+///
+/// let pasted = "Here's my opener!\n\
+///               **Title:** Opener\n\
+///               **Icon:** Tank 1\n\
+///               ```\n\
+///               /ac \"Fast Blade\" <t>\n\
+///               /p Pulling! <se.1>\n\
+///               ```";
+/// let shared = parse(pasted).unwrap();
+///
+/// assert_eq!(shared.len(), 1);
+/// assert_eq!(shared[0].content.title, "Opener");
+/// assert_eq!(shared[0].content.get_icon(), Some(MacroIcon::Tank1));
+/// assert_eq!(shared[0].content.lines[1], "/p Pulling! <se.1>");
+/// assert_eq!(shared[0].content.lines.len(), 15);
+/// assert!(!shared[0].is_truncated());
+/// ```
+pub fn parse(text: &str) -> Result<Vec<SharedMacro>, DATError> {
+    let mut res_vec = Vec::<SharedMacro>::new();
+    let mut draft = Draft::default();
+    let mut loose_lines = Vec::<String>::new();
+    // The length of the fence that opened the current code block, if any.
+    let mut open_fence = None::<usize>;
+
+    for raw in text.lines() {
+        let raw_fence = fence_len(raw);
+        match open_fence {
+            Some(open) if raw_fence >= open => {
+                res_vec.push(finish(std::mem::take(&mut draft))?);
+                open_fence = None;
+            }
+            Some(_) => draft.lines.push(raw.to_owned()),
+            None if raw_fence >= CODE_FENCE.len() => open_fence = Some(raw_fence),
+            None => match header(raw) {
+                Some((name, value)) => match name.as_str() {
+                    "title" | "name" => draft.title = Some(value),
+                    _ => draft.icon = Some(value),
+                },
+                None => loose_lines.push(raw.to_owned()),
+            },
+        }
+    }
+
+    if open_fence.is_some() {
+        res_vec.push(finish(draft)?);
+    } else if res_vec.is_empty() {
+        let start = loose_lines.iter().take_while(|line| line.trim().is_empty()).count();
+        draft.lines = loose_lines.split_off(start);
+        if draft.title.is_some() || draft.lines.iter().any(|line| !line.trim().is_empty()) {
+            res_vec.push(finish(draft)?);
+        }
+    }
+    Ok(res_vec)
+}
+
+/// Builds a [`SharedMacro`] from a draft, truncating content to fit [`MacroLimits`].
+fn finish(draft: Draft) -> Result<SharedMacro, DATError> {
+    let limits = MacroLimits::default();
+    let mut truncations = Vec::<Truncation>::new();
+
+    let mut title = String::from_utf8(from_readable(&draft.title.unwrap_or_default()))?;
+    if let Some(truncated) = truncate(&title, limits.title_length) {
+        title = truncated;
+        truncations.push(Truncation::Title);
+    }
+
+    let mut lines = draft
+        .lines
+        .iter()
+        .map(|line| String::from_utf8(from_readable(line)))
+        .collect::<Result<Vec<String>, _>>()?;
+    while lines.last().map_or(false, |line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let extra = lines.len().saturating_sub(limits.line_count);
+    if extra > 0 {
+        lines.truncate(limits.line_count);
+        truncations.push(Truncation::ExtraLines(extra));
+    }
+    for (index, line) in lines.iter_mut().enumerate() {
+        if let Some(truncated) = truncate(line, limits.line_length) {
+            *line = truncated;
+            truncations.push(Truncation::Line(index));
+        }
+    }
+
+    let (icon, unknown_icon) = match draft.icon {
+        Some(name) => match macro_icon_from_name(&name) {
+            Some(icon) => (MacroIconRef::from(icon), None),
+            None => match icon_ref_from_hex(&name) {
+                Some(icon_ref) => (icon_ref, None),
+                None => (MacroIconRef::from(MacroIcon::DefaultIcon), Some(name)),
+            },
+        },
+        None => (MacroIconRef::from(MacroIcon::DefaultIcon), None),
+    };
+
+    let mut content = Macro::new(title, lines, MacroIcon::DefaultIcon)?;
+    content.icon = icon;
+    Ok(SharedMacro {
+        content,
+        truncations,
+        unknown_icon,
+    })
+}
+
+/// Returns the number of backticks at the start of a line, ignoring leading whitespace.
+fn fence_len(raw: &str) -> usize {
+    raw.trim_start().chars().take_while(|c| *c == '`').count()
+}
+
+/// Parses a `Name: value` header line, returning the lowercase name and trimmed value. Only the
+/// `title`, `name`, and `icon` headers are recognized.
+fn header(raw: &str) -> Option<(String, String)> {
+    let (name, value) = raw.split_at(raw.find(':')?);
+    let name = name.trim().trim_matches(|c| c == '*' || c == '_').to_lowercase();
+    if !matches!(name.as_str(), "title" | "name" | "icon") {
+        return None;
+    }
+    let value = value[1..].trim().trim_matches(|c| c == '*' || c == '_').trim();
+    Some((name, value.to_owned()))
+}
+
+/// Parses a raw `key:id` icon pair in hex, as written by [`export()`] for icons that are not a [`MacroIcon`].
+fn icon_ref_from_hex(value: &str) -> Option<MacroIconRef> {
+    let (key, icon_id) = value.split_at(value.find(':')?);
+    MacroIconRef::from_hex(key, &icon_id[1..]).ok()
+}
+
+/// Returns the text cut to a maximum [`char_count()`], or `None` if it already fits.
+fn truncate(text: &str, max: usize) -> Option<String> {
+    if char_count(text) <= max {
+        return None;
+    }
+    let mut end = 0;
+    for _ in 0..max {
+        match next_char_len(&text[end..]) {
+            Some(len) => end += len,
+            None => break,
+        }
+    }
+    Some(text[..end].to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- Module Functions
+
+    #[test]
+    fn test_export_round_trip() -> Result<(), String> {
+        let a_macro = match Macro::new(
+            "Round Trip".to_owned(),
+            vec!["/ac \"Fire IV\" <t>".to_owned(), String::new(), "/p Done!".to_owned()],
+            MacroIcon::CrystalFire,
+        ) {
+            Ok(a_macro) => a_macro,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match parse(&export(&a_macro)) {
+            Ok(shared) => {
+                assert_eq!(shared.len(), 1);
+                assert_eq!(shared[0].content, a_macro);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_export_round_trip_unknown_icon() -> Result<(), String> {
+        let mut a_macro = match Macro::new("Custom".to_owned(), vec!["/p hi".to_owned()], MacroIcon::DefaultIcon) {
+            Ok(a_macro) => a_macro,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        a_macro.icon = match MacroIconRef::new(0x0AB, 0x12345) {
+            Ok(icon_ref) => icon_ref,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        let shared_text = export(&a_macro);
+        assert!(shared_text.contains("Icon: 0AB:0012345\n"));
+        match parse(&shared_text) {
+            Ok(shared) => {
+                assert_eq!(shared[0].content, a_macro);
+                assert_eq!(shared[0].unknown_icon, None);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_export_round_trip_readable() -> Result<(), String> {
+        let a_macro = match Macro::new(
+            "Potion\u{E03C}".to_owned(),
+            vec![
+                "/p \u{2}\u{2E}\u{3}\u{5}\u{D}\u{3} {literal}".to_owned(),
+                "```".to_owned(),
+                "  ````not a fence".to_owned(),
+            ],
+            MacroIcon::DefaultIcon,
+        ) {
+            Ok(a_macro) => a_macro,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        let shared_text = export(&a_macro);
+        assert_eq!(
+            shared_text,
+            "Title: Potion{HQ}\nIcon: DefaultIcon\n`````\n/p {AT:4:12} {{literal}}\n```\n  ````not a fence\n`````\n"
+        );
+        match parse(&shared_text) {
+            Ok(shared) => {
+                assert_eq!(shared.len(), 1);
+                assert_eq!(shared[0].content, a_macro);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_parse_multiple() -> Result<(), String> {
+        let text =
+            "Title: One\n```\n/p one\n```\nSome chatter in between.\nName: Two\nIcon: Number2\n```text\n/p two\n```";
+        match parse(text) {
+            Ok(shared) => {
+                assert_eq!(shared.len(), 2);
+                assert_eq!(shared[0].content.title, "One");
+                assert_eq!(shared[0].content.get_icon(), Some(MacroIcon::DefaultIcon));
+                assert_eq!(shared[1].content.title, "Two");
+                assert_eq!(shared[1].content.get_icon(), Some(MacroIcon::Number2));
+                assert_eq!(shared[1].content.lines[0], "/p two");
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_parse_no_code_block() -> Result<(), String> {
+        match parse("\n/ac Sprint\n\n/p Go!\n") {
+            Ok(shared) => {
+                assert_eq!(shared.len(), 1);
+                assert_eq!(&shared[0].content.lines[..3], &["/ac Sprint", "", "/p Go!"]);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_parse_empty() -> Result<(), String> {
+        match parse("Nothing to see here.") {
+            Ok(shared) => {
+                assert_eq!(shared.len(), 1);
+                match parse("\n  \n") {
+                    Ok(shared) => Ok(assert!(shared.is_empty())),
+                    Err(err) => Err(format!("Error: {}", err)),
+                }
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_parse_truncation() -> Result<(), String> {
+        let mut text = "Title: A Title That Is Far Too Long\n```\n".to_owned();
+        text.push_str(&format!("/p {}\n", "X".repeat(200)));
+        for i in 1..18 {
+            text.push_str(&format!("/p {}\n", i));
+        }
+        text.push_str("\n\n```");
+        match parse(&text) {
+            Ok(shared) => {
+                let content = &shared[0].content;
+                assert_eq!(content.title, "A Title That Is Far ");
+                assert_eq!(char_count(&content.lines[0]), 180);
+                assert_eq!(content.lines[14], "/p 14");
+                assert_eq!(
+                    shared[0].truncations,
+                    vec![Truncation::Title, Truncation::ExtraLines(3), Truncation::Line(0)]
+                );
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_icon() -> Result<(), String> {
+        match parse("Icon: Sparkles\n```\n/p hi\n```") {
+            Ok(shared) => {
+                assert_eq!(shared[0].content.get_icon(), Some(MacroIcon::DefaultIcon));
+                assert_eq!(shared[0].unknown_icon, Some("Sparkles".to_owned()));
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_truncate_payload() -> Result<(), String> {
+        assert_eq!(
+            truncate("ab\u{2}\u{2E}\u{1}\u{3}cd", 3),
+            Some("ab\u{2}\u{2E}\u{1}\u{3}".to_owned())
+        );
+        // {AT:4:12}, whose length byte is 0x03, is not split.
+        assert_eq!(
+            truncate("a\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}cd", 2),
+            Some("a\u{2}\u{2E}\u{3}\u{5}\u{D}\u{3}".to_owned())
+        );
+        assert_eq!(truncate("abc", 3), None);
+        Ok(())
+    }
+}
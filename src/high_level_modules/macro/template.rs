@@ -0,0 +1,464 @@
+use super::icon::MacroIcon;
+use super::{char_count, Macro, MacroLimits};
+use crate::dat_error::{DATError, ErrorContext};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A macro template, rendered into a [`Macro`] with a set of variables.
+///
+/// # Syntax
+///
+/// | Syntax              | Description                                                                      |
+/// |---------------------|----------------------------------------------------------------------------------|
+/// | `{{name}}`          | Replaced with the value of the variable `name`. Undefined variables are errors.  |
+/// | `{{#if name}}`      | Renders the following lines only if `name` is defined and not empty.            |
+/// | `{{else}}`          | Renders the following lines only if the preceding `{{#if}}` did not.             |
+/// | `{{/if}}`           | Ends an `{{#if}}` block. Blocks may be nested.                                   |
+/// | `{{> name}}`        | Renders the partial template `name` in place. See [`add_partial()`](Self::add_partial). |
+///
+/// Block and include tags must be on their own line, and do not produce a macro line. Variables may be
+/// used anywhere in the title or a line.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::icon::MacroIcon;
+/// use libxivdat::xiv_macro::template::Template;
+/// use std::collections::HashMap;
+///
+/// let mut template = Template::new(
+///     "{{role}} Opener",
+///     "{{#if marker}}\n/mk {{marker}} <t>\n{{/if}}\n{{> pull}}",
+///     MacroIcon::DefaultIcon,
+/// );
+/// template.add_partial("pull", "/p Pulling in 5! <se.1>");
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("role", "Tank");
+/// vars.insert("marker", "attack1");
+///
+/// let a_macro = template.render(&vars).unwrap();
+/// assert_eq!(a_macro.title, "Tank Opener");
+/// assert_eq!(a_macro.lines[0], "/mk attack1 <t>");
+/// assert_eq!(a_macro.lines[1], "/p Pulling in 5! <se.1>");
+/// assert_eq!(a_macro.lines.len(), 15);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template {
+    icon: MacroIcon,
+    partials: HashMap<String, String>,
+    source: String,
+    title: String,
+}
+
+/// An open `{{#if}}` block.
+struct Block {
+    /// Whether the enclosing block is rendered.
+    parent_active: bool,
+    /// Whether the block's variable is truthy.
+    condition: bool,
+    /// Whether an `{{else}}` has been seen.
+    in_else: bool,
+    /// The line of the `{{#if}}` tag.
+    line: usize,
+}
+
+/// A line of rendered output and where it came from.
+struct RenderedLine {
+    /// The line number within its source.
+    line: usize,
+    /// The partial the line came from, if not the template itself.
+    partial: Option<String>,
+    /// The rendered text.
+    text: String,
+}
+
+/// A tag that occupies a whole line.
+enum Directive<'a> {
+    Else,
+    EndIf,
+    If(&'a str),
+    Include(&'a str),
+}
+
+impl Block {
+    fn is_active(&self) -> bool {
+        self.parent_active && (self.condition != self.in_else)
+    }
+}
+
+impl Template {
+    /// Registers a partial template that can be included with `{{> name}}`. Partials may include other
+    /// partials, and use the same variables as the template. Adding a partial with an existing name
+    /// replaces it.
+    pub fn add_partial<N: Into<String>, S: Into<String>>(&mut self, name: N, source: S) {
+        self.partials.insert(name.into(), source.into());
+    }
+
+    /// Builds a new [`Template`] from a title, a source with one macro line per line of text, and an icon.
+    pub fn new<T: Into<String>, S: Into<String>>(title: T, source: S, icon: MacroIcon) -> Template {
+        Template {
+            icon,
+            partials: HashMap::new(),
+            source: source.into(),
+            title: title.into(),
+        }
+    }
+
+    /// Renders the template into a [`Macro`] using a set of variables. The rendered macro is built
+    /// with [`Macro::new()`], so it is validated and padded to 15 lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::InvalidInput`] if the template uses an undefined variable or partial, includes
+    /// a partial recursively, or has unbalanced `{{#if}}` blocks.
+    ///
+    /// Returns [`DATError::Overflow`] if the rendered title or a rendered line is too long, or if the
+    /// template renders too many lines. See [`MacroLimits`].
+    ///
+    /// Where possible, the error's [`line()`](crate::dat_error::ErrorContext::line) is the line of the template
+    /// (or partial, as noted in the message) that caused it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::icon::MacroIcon;
+    /// use libxivdat::xiv_macro::template::Template;
+    /// use std::collections::HashMap;
+    ///
+    /// let template = Template::new("Title", "/p One\n/p {{two}}", MacroIcon::DefaultIcon);
+    /// let err = template.render(&HashMap::<&str, &str>::new()).unwrap_err();
+    ///
+    /// assert_eq!(err.context().line(), Some(2));
+    /// ```
+    pub fn render<K, V>(&self, vars: &HashMap<K, V>) -> Result<Macro, DATError>
+    where
+        K: Borrow<str> + Eq + Hash,
+        V: AsRef<str>,
+    {
+        let limits = MacroLimits::default();
+        let title = substitute(&self.title, vars)
+            .map_err(|name| DATError::InvalidInput(format!("Undefined variable `{}` in title.", name).into()))?;
+        if char_count(&title) > limits.title_length {
+            return Err(DATError::Overflow(
+                format!("Title is longer than {} characters.", limits.title_length).into(),
+            ));
+        }
+
+        let mut rendered = Vec::<RenderedLine>::new();
+        self.render_source(&self.source, None, vars, &mut Vec::new(), &mut rendered)?;
+
+        if let Some(extra) = rendered.get(limits.line_count) {
+            return Err(line_error(
+                DATError::Overflow,
+                format!("Template renders more than {} lines", limits.line_count),
+                extra,
+            ));
+        }
+        for line in rendered.iter() {
+            if char_count(&line.text) > limits.line_length {
+                return Err(line_error(
+                    DATError::Overflow,
+                    format!("Line is longer than {} characters", limits.line_length),
+                    line,
+                ));
+            }
+        }
+        Macro::new(title, rendered.into_iter().map(|line| line.text).collect(), self.icon)
+    }
+
+    /// Renders a template or partial source into lines, following includes.
+    fn render_source<K, V>(
+        &self, source: &str, partial: Option<&str>, vars: &HashMap<K, V>, stack: &mut Vec<String>,
+        out: &mut Vec<RenderedLine>,
+    ) -> Result<(), DATError>
+    where
+        K: Borrow<str> + Eq + Hash,
+        V: AsRef<str>,
+    {
+        let mut blocks = Vec::<Block>::new();
+        for (index, raw) in source.lines().enumerate() {
+            let line = RenderedLine {
+                line: index + 1,
+                partial: partial.map(str::to_owned),
+                text: String::new(),
+            };
+            let active = blocks.last().map_or(true, Block::is_active);
+            match directive(raw) {
+                Some(Directive::If(name)) => blocks.push(Block {
+                    parent_active: active,
+                    condition: vars.get(name).map_or(false, |value| !value.as_ref().is_empty()),
+                    in_else: false,
+                    line: line.line,
+                }),
+                Some(Directive::Else) => match blocks.last_mut() {
+                    Some(block) if !block.in_else => block.in_else = true,
+                    Some(_) => return Err(line_error(DATError::InvalidInput, "Duplicate {{else}}".into(), &line)),
+                    None => {
+                        return Err(line_error(
+                            DATError::InvalidInput,
+                            "{{else}} without {{#if}}".into(),
+                            &line,
+                        ))
+                    }
+                },
+                Some(Directive::EndIf) => {
+                    blocks
+                        .pop()
+                        .ok_or_else(|| line_error(DATError::InvalidInput, "{{/if}} without {{#if}}".into(), &line))?;
+                }
+                Some(Directive::Include(name)) if active => {
+                    let partial_source = match self.partials.get(name) {
+                        Some(partial_source) => partial_source,
+                        None => {
+                            return Err(line_error(
+                                DATError::InvalidInput,
+                                format!("Undefined partial `{}`", name),
+                                &line,
+                            ))
+                        }
+                    };
+                    if stack.iter().any(|included| included == name) {
+                        return Err(line_error(
+                            DATError::InvalidInput,
+                            format!("Partial `{}` includes itself", name),
+                            &line,
+                        ));
+                    }
+                    stack.push(name.to_owned());
+                    self.render_source(partial_source, Some(name), vars, stack, out)?;
+                    stack.pop();
+                }
+                Some(Directive::Include(_)) => (),
+                None if active => {
+                    let text = substitute(raw, vars).map_err(|name| {
+                        line_error(DATError::InvalidInput, format!("Undefined variable `{}`", name), &line)
+                    })?;
+                    out.push(RenderedLine { text, ..line });
+                }
+                None => (),
+            }
+        }
+        match blocks.last() {
+            Some(block) => Err(line_error(
+                DATError::InvalidInput,
+                "Unclosed {{#if}}".into(),
+                &RenderedLine {
+                    line: block.line,
+                    partial: partial.map(str::to_owned),
+                    text: String::new(),
+                },
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a line consisting of only a block or include tag.
+fn directive(raw: &str) -> Option<Directive<'_>> {
+    let trimmed = raw.trim();
+    let inner = trimmed.strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    if inner == "else" {
+        return Some(Directive::Else);
+    }
+    if inner == "/if" {
+        return Some(Directive::EndIf);
+    }
+    if let Some(name) = inner.strip_prefix("#if ") {
+        return Some(Directive::If(name.trim()));
+    }
+    inner.strip_prefix('>').map(|name| Directive::Include(name.trim()))
+}
+
+/// Builds an error located at a rendered line, naming the partial if the line came from one.
+fn line_error(kind: fn(ErrorContext) -> DATError, message: String, line: &RenderedLine) -> DATError {
+    let message = match &line.partial {
+        Some(partial) => format!("{} in partial `{}`.", message, partial),
+        None => format!("{}.", message),
+    };
+    kind(ErrorContext::from(message).with_line(line.line))
+}
+
+/// Replaces all `{{name}}` variables in a line. Returns the name of the first undefined variable as an error.
+/// An unterminated `{{` is left unchanged.
+fn substitute<K, V>(text: &str, vars: &HashMap<K, V>) -> Result<String, String>
+where
+    K: Borrow<str> + Eq + Hash,
+    V: AsRef<str>,
+{
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let close = match rest[open + 2..].find("}}") {
+            Some(close) => open + 2 + close,
+            None => break,
+        };
+        let name = rest[open + 2..close].trim();
+        match vars.get(name) {
+            Some(value) => {
+                out.push_str(&rest[..open]);
+                out.push_str(value.as_ref());
+            }
+            None => return Err(name.to_owned()),
+        }
+        rest = &rest[close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&'static str, &'static str)]) -> HashMap<&'static str, &'static str> {
+        pairs.iter().cloned().collect()
+    }
+
+    // --- Template
+
+    #[test]
+    fn test_template_render_conditionals() -> Result<(), String> {
+        let template = Template::new(
+            "Title",
+            "{{#if a}}\nA\n{{#if b}}\nAB\n{{else}}\nA!B\n{{/if}}\n{{else}}\n!A\n{{/if}}",
+            MacroIcon::DefaultIcon,
+        );
+        let cases = [
+            (vars(&[("a", "1"), ("b", "1")]), vec!["A", "AB"]),
+            (vars(&[("a", "1"), ("b", "")]), vec!["A", "A!B"]),
+            (vars(&[]), vec!["!A"]),
+        ];
+        for (case_vars, expected) in cases.iter() {
+            match template.render(case_vars) {
+                Ok(a_macro) => assert_eq!(&a_macro.lines[..expected.len()], &expected[..]),
+                Err(err) => return Err(format!("Error: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_render_skips_inactive_errors() -> Result<(), String> {
+        let template = Template::new(
+            "",
+            "{{#if a}}\n{{undefined}}\n{{> missing}}\n{{/if}}",
+            MacroIcon::DefaultIcon,
+        );
+        match template.render(&vars(&[])) {
+            Ok(a_macro) => {
+                assert!(a_macro.is_empty());
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_template_render_nested_partials() -> Result<(), String> {
+        let mut template = Template::new("", "first\n{{> outer}}\nlast", MacroIcon::DefaultIcon);
+        template.add_partial("outer", "outer {{x}}\n{{> inner}}");
+        template.add_partial("inner", "inner {{x}}");
+        match template.render(&vars(&[("x", "1")])) {
+            Ok(a_macro) => {
+                assert_eq!(&a_macro.lines[..4], &["first", "outer 1", "inner 1", "last"]);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_line_count() -> Result<(), String> {
+        let source = vec!["/p line"; 16].join("\n");
+        let template = Template::new("", source, MacroIcon::DefaultIcon);
+        match template.render(&vars(&[])) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(ref ctx) => {
+                    assert_eq!(ctx.line(), Some(16));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_line_length() -> Result<(), String> {
+        let mut template = Template::new("", "/p short\n{{> long}}", MacroIcon::DefaultIcon);
+        template.add_partial("long", "/p short\n/p {{text}}");
+        let long_text = "X".repeat(180);
+        let mut case_vars = HashMap::new();
+        case_vars.insert("text", long_text.as_str());
+        match template.render(&case_vars) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(ref ctx) => {
+                    assert_eq!(ctx.line(), Some(2));
+                    assert!(ctx.message().contains("`long`"));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_title_length() -> Result<(), String> {
+        let template = Template::new("{{name}} Opener", "", MacroIcon::DefaultIcon);
+        match template.render(&vars(&[("name", "A Very Long Name")])) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_undefined_variable() -> Result<(), String> {
+        let template = Template::new("", "one\ntwo {{ missing }}", MacroIcon::DefaultIcon);
+        match template.render(&vars(&[])) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(ref ctx) => {
+                    assert_eq!(ctx.line(), Some(2));
+                    assert!(ctx.message().contains("`missing`"));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_recursive_partial() -> Result<(), String> {
+        let mut template = Template::new("", "{{> a}}", MacroIcon::DefaultIcon);
+        template.add_partial("a", "{{> b}}");
+        template.add_partial("b", "{{> a}}");
+        match template.render(&vars(&[])) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_template_render_error_unbalanced() -> Result<(), String> {
+        for (source, line) in [("one\n{{#if a}}\ntwo", 2), ("{{/if}}", 1), ("{{else}}", 1)].iter() {
+            let template = Template::new("", *source, MacroIcon::DefaultIcon);
+            match template.render(&vars(&[])) {
+                Ok(_) => return Err("No error returned.".to_owned()),
+                Err(err) => match err {
+                    DATError::InvalidInput(ref ctx) => assert_eq!(ctx.line(), Some(*line)),
+                    _ => return Err(format!("Incorrect error: {}", err)),
+                },
+            }
+        }
+        Ok(())
+    }
+}
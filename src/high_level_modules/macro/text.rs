@@ -0,0 +1,711 @@
+use std::fmt;
+
+/// The byte that starts an embedded payload.
+pub const PAYLOAD_START: u8 = 0x02;
+
+/// The byte that ends an embedded payload.
+pub const PAYLOAD_END: u8 = 0x03;
+
+/// The payload type of an auto-translate phrase.
+pub const PAYLOAD_TYPE_AUTO_TRANSLATE: u8 = 0x2E;
+
+/// A glyph from the private-use area of the game's font, such as the HQ icon.
+///
+/// Each glyph has a readable name used in placeholders like `{HQ}`. See [`to_readable()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GameGlyph {
+    /// Down arrow. `{ArrowDown}`
+    ArrowDown,
+    /// Right arrow. `{ArrowRight}`
+    ArrowRight,
+    /// Opening bracket of an auto-translate phrase. `{TranslateOpen}`
+    AutoTranslateOpen,
+    /// Closing bracket of an auto-translate phrase. `{TranslateClose}`
+    AutoTranslateClose,
+    /// Botanist sprout icon. `{Sprout}`
+    BotanistSprout,
+    /// A boxed letter, from `A` to `Z`. `{BoxedA}` through `{BoxedZ}`
+    BoxedLetter(char),
+    /// A boxed number, from 0 to 31. Numbers 1 through 8 are used for party list positions.
+    /// `{Boxed0}` through `{Boxed31}`
+    BoxedNumber(u8),
+    /// Boxed plus sign. `{BoxedPlus}`
+    BoxedPlus,
+    /// Boxed question mark. `{BoxedQuestion}`
+    BoxedQuestionMark,
+    /// Clock icon. `{Clock}`
+    Clock,
+    /// Collectible icon. `{Collectible}`
+    Collectible,
+    /// Eureka level icon. `{EurekaLevel}`
+    EurekaLevel,
+    /// Gil icon. `{Gil}`
+    Gil,
+    /// High quality item icon. `{HQ}`
+    HighQuality,
+    /// Hyadelyn icon. `{Hyadelyn}`
+    Hyadelyn,
+    /// Item level icon. `{ItemLevel}`
+    ItemLevel,
+    /// German level icon. `{LevelDe}`
+    LevelDe,
+    /// English level icon. `{LevelEn}`
+    LevelEn,
+    /// French level icon. `{LevelFr}`
+    LevelFr,
+    /// Item link arrow. `{Link}`
+    LinkMarker,
+    /// Mouse with both buttons highlighted. `{MouseBoth}`
+    MouseBothClick,
+    /// Mouse with the left button highlighted. `{MouseLeft}`
+    MouseLeftClick,
+    /// Mouse with no buttons highlighted. `{Mouse}`
+    MouseNoClick,
+    /// Mouse with the right button highlighted. `{MouseRight}`
+    MouseRightClick,
+    /// Mouse with the wheel highlighted. `{MouseWheel}`
+    MouseWheel,
+    /// A stylized digit, from 0 to 9. `{Num0}` through `{Num9}`
+    Number(u8),
+    /// Repeatable quest icon. `{QuestRepeat}`
+    QuestRepeatable,
+    /// Level sync quest icon. `{QuestSync}`
+    QuestSync,
+    /// AM time icon. `{AM}`
+    TimeAm,
+    /// PM time icon. `{PM}`
+    TimePm,
+}
+
+/// An embedded payload, stored in text as `0x02`, a type byte, an encoded length, the payload data, and `0x03`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Payload {
+    /// An auto-translate phrase, identified by its group and key in the game's completion sheet.
+    /// Rendered as `{AT:group:key}`.
+    AutoTranslate {
+        /// The group of the phrase.
+        group: u32,
+        /// The key of the phrase within its group.
+        key: u32,
+    },
+    /// Any other payload, stored as its raw type and data. Rendered as `{Payload:type:data}`,
+    /// with the type and data in hexadecimal.
+    Other {
+        /// The raw payload data.
+        data: Vec<u8>,
+        /// The payload type.
+        kind: u8,
+    },
+}
+
+/// A segment of game text, as produced by [`decode()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TextSegment {
+    /// A single [`GameGlyph`].
+    Glyph(GameGlyph),
+    /// An embedded [`Payload`].
+    Payload(Payload),
+    /// A run of plain text.
+    Text(String),
+}
+
+impl fmt::Display for GameGlyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameGlyph::ArrowDown => write!(f, "ArrowDown"),
+            GameGlyph::ArrowRight => write!(f, "ArrowRight"),
+            GameGlyph::AutoTranslateOpen => write!(f, "TranslateOpen"),
+            GameGlyph::AutoTranslateClose => write!(f, "TranslateClose"),
+            GameGlyph::BotanistSprout => write!(f, "Sprout"),
+            GameGlyph::BoxedLetter(letter) => write!(f, "Boxed{}", letter),
+            GameGlyph::BoxedNumber(num) => write!(f, "Boxed{}", num),
+            GameGlyph::BoxedPlus => write!(f, "BoxedPlus"),
+            GameGlyph::BoxedQuestionMark => write!(f, "BoxedQuestion"),
+            GameGlyph::Clock => write!(f, "Clock"),
+            GameGlyph::Collectible => write!(f, "Collectible"),
+            GameGlyph::EurekaLevel => write!(f, "EurekaLevel"),
+            GameGlyph::Gil => write!(f, "Gil"),
+            GameGlyph::HighQuality => write!(f, "HQ"),
+            GameGlyph::Hyadelyn => write!(f, "Hyadelyn"),
+            GameGlyph::ItemLevel => write!(f, "ItemLevel"),
+            GameGlyph::LevelDe => write!(f, "LevelDe"),
+            GameGlyph::LevelEn => write!(f, "LevelEn"),
+            GameGlyph::LevelFr => write!(f, "LevelFr"),
+            GameGlyph::LinkMarker => write!(f, "Link"),
+            GameGlyph::MouseBothClick => write!(f, "MouseBoth"),
+            GameGlyph::MouseLeftClick => write!(f, "MouseLeft"),
+            GameGlyph::MouseNoClick => write!(f, "Mouse"),
+            GameGlyph::MouseRightClick => write!(f, "MouseRight"),
+            GameGlyph::MouseWheel => write!(f, "MouseWheel"),
+            GameGlyph::Number(num) => write!(f, "Num{}", num),
+            GameGlyph::QuestRepeatable => write!(f, "QuestRepeat"),
+            GameGlyph::QuestSync => write!(f, "QuestSync"),
+            GameGlyph::TimeAm => write!(f, "AM"),
+            GameGlyph::TimePm => write!(f, "PM"),
+        }
+    }
+}
+
+impl fmt::Display for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Payload::AutoTranslate { group, key } => write!(f, "AT:{}:{}", group, key),
+            Payload::Other { data, kind } => {
+                write!(f, "Payload:{:02X}:", kind)?;
+                for byte in data.iter() {
+                    write!(f, "{:02X}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl GameGlyph {
+    /// Returns the [`GameGlyph`] for a private-use character, or [`None`] if the character is not a known glyph.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::text::GameGlyph;
+    ///
+    /// assert_eq!(GameGlyph::from_char('\u{E03C}'), Some(GameGlyph::HighQuality));
+    /// assert_eq!(GameGlyph::from_char('\u{E090}'), Some(GameGlyph::BoxedNumber(1)));
+    /// assert_eq!(GameGlyph::from_char('A'), None);
+    /// ```
+    pub fn from_char(glyph: char) -> Option<GameGlyph> {
+        let code = glyph as u32;
+        Some(match code {
+            0xE031 => GameGlyph::Clock,
+            0xE033 => GameGlyph::ItemLevel,
+            0xE034 => GameGlyph::BotanistSprout,
+            0xE035 => GameGlyph::ArrowDown,
+            0xE03A => GameGlyph::EurekaLevel,
+            0xE03C => GameGlyph::HighQuality,
+            0xE03D => GameGlyph::Collectible,
+            0xE040 => GameGlyph::AutoTranslateOpen,
+            0xE041 => GameGlyph::AutoTranslateClose,
+            0xE048 => GameGlyph::Hyadelyn,
+            0xE049 => GameGlyph::Gil,
+            0xE050 => GameGlyph::MouseNoClick,
+            0xE051 => GameGlyph::MouseLeftClick,
+            0xE052 => GameGlyph::MouseRightClick,
+            0xE053 => GameGlyph::MouseBothClick,
+            0xE054 => GameGlyph::MouseWheel,
+            0xE060..=0xE069 => GameGlyph::Number((code - 0xE060) as u8),
+            0xE06A => GameGlyph::LevelEn,
+            0xE06B => GameGlyph::LevelDe,
+            0xE06C => GameGlyph::LevelFr,
+            0xE06D => GameGlyph::TimeAm,
+            0xE06E => GameGlyph::TimePm,
+            0xE06F => GameGlyph::ArrowRight,
+            0xE070 => GameGlyph::BoxedQuestionMark,
+            0xE071..=0xE08A => GameGlyph::BoxedLetter((b'A' + (code - 0xE071) as u8) as char),
+            0xE08F..=0xE0AE => GameGlyph::BoxedNumber((code - 0xE08F) as u8),
+            0xE0AF => GameGlyph::BoxedPlus,
+            0xE0BB => GameGlyph::LinkMarker,
+            0xE0BE => GameGlyph::QuestSync,
+            0xE0BF => GameGlyph::QuestRepeatable,
+            _ => return None,
+        })
+    }
+
+    /// Returns the [`GameGlyph`] for a readable name, such as `HQ`, or [`None`] if the name is not known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::text::GameGlyph;
+    ///
+    /// assert_eq!(GameGlyph::from_name("HQ"), Some(GameGlyph::HighQuality));
+    /// assert_eq!(GameGlyph::from_name("Boxed3"), Some(GameGlyph::BoxedNumber(3)));
+    /// assert_eq!(GameGlyph::from_name("hq"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<GameGlyph> {
+        // Every glyph renders a unique name, so search the full range of glyph characters.
+        (0xE000..=0xE0FF)
+            .filter_map(std::char::from_u32)
+            .filter_map(GameGlyph::from_char)
+            .find(|glyph| glyph.to_string() == name)
+    }
+
+    /// Returns the private-use character for the [`GameGlyph`]. Out of range letters and numbers
+    /// return [`None`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::text::GameGlyph;
+    ///
+    /// assert_eq!(GameGlyph::HighQuality.to_char(), Some('\u{E03C}'));
+    /// assert_eq!(GameGlyph::BoxedLetter('B').to_char(), Some('\u{E072}'));
+    /// assert_eq!(GameGlyph::Number(10).to_char(), None);
+    /// ```
+    pub fn to_char(&self) -> Option<char> {
+        let code = match self {
+            GameGlyph::ArrowDown => 0xE035,
+            GameGlyph::ArrowRight => 0xE06F,
+            GameGlyph::AutoTranslateOpen => 0xE040,
+            GameGlyph::AutoTranslateClose => 0xE041,
+            GameGlyph::BotanistSprout => 0xE034,
+            GameGlyph::BoxedLetter(letter @ 'A'..='Z') => 0xE071 + (*letter as u32 - 'A' as u32),
+            GameGlyph::BoxedNumber(num @ 0..=31) => 0xE08F + u32::from(*num),
+            GameGlyph::BoxedPlus => 0xE0AF,
+            GameGlyph::BoxedQuestionMark => 0xE070,
+            GameGlyph::Clock => 0xE031,
+            GameGlyph::Collectible => 0xE03D,
+            GameGlyph::EurekaLevel => 0xE03A,
+            GameGlyph::Gil => 0xE049,
+            GameGlyph::HighQuality => 0xE03C,
+            GameGlyph::Hyadelyn => 0xE048,
+            GameGlyph::ItemLevel => 0xE033,
+            GameGlyph::LevelDe => 0xE06B,
+            GameGlyph::LevelEn => 0xE06A,
+            GameGlyph::LevelFr => 0xE06C,
+            GameGlyph::LinkMarker => 0xE0BB,
+            GameGlyph::MouseBothClick => 0xE053,
+            GameGlyph::MouseLeftClick => 0xE051,
+            GameGlyph::MouseNoClick => 0xE050,
+            GameGlyph::MouseRightClick => 0xE052,
+            GameGlyph::MouseWheel => 0xE054,
+            GameGlyph::Number(num @ 0..=9) => 0xE060 + u32::from(*num),
+            GameGlyph::QuestRepeatable => 0xE0BF,
+            GameGlyph::QuestSync => 0xE0BE,
+            GameGlyph::TimeAm => 0xE06D,
+            GameGlyph::TimePm => 0xE06E,
+            GameGlyph::BoxedLetter(_) | GameGlyph::BoxedNumber(_) | GameGlyph::Number(_) => return None,
+        };
+        std::char::from_u32(code)
+    }
+}
+
+impl Payload {
+    /// Returns the raw bytes of the payload, including the start and end bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::xiv_macro::text::Payload;
+    ///
+    /// let payload = Payload::AutoTranslate { group: 4, key: 12 };
+    /// assert_eq!(payload.to_bytes(), vec![0x02, 0x2E, 0x03, 0x05, 0x0D, 0x03]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (kind, data) = match self {
+            Payload::AutoTranslate { group, key } => {
+                let mut data = encode_integer(*group);
+                data.append(&mut encode_integer(*key));
+                (PAYLOAD_TYPE_AUTO_TRANSLATE, data)
+            }
+            Payload::Other { data, kind } => (*kind, data.clone()),
+        };
+        let mut bytes = vec![PAYLOAD_START, kind];
+        bytes.append(&mut encode_integer(data.len() as u32));
+        bytes.extend_from_slice(&data);
+        bytes.push(PAYLOAD_END);
+        bytes
+    }
+}
+
+/// Splits raw game text into plain text, [`GameGlyphs`](GameGlyph), and [`Payloads`](Payload).
+///
+/// Macro lines containing payloads with large values are not valid UTF-8, so this function
+/// accepts raw bytes, such as the contents of a [`RawSection`](crate::section::RawSection).
+/// Any invalid UTF-8 outside of payloads is replaced with `U+FFFD`. A `0x02` byte that does not
+/// start a well-formed payload is treated as text.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::text::{decode, GameGlyph, Payload, TextSegment};
+///
+/// let segments = decode(b"/p \x02\x2E\x03\x05\x0D\x03 Potion\xEE\x80\xBC");
+/// assert_eq!(
+///     segments,
+///     vec![
+///         TextSegment::Text("/p ".to_string()),
+///         TextSegment::Payload(Payload::AutoTranslate { group: 4, key: 12 }),
+///         TextSegment::Text(" Potion".to_string()),
+///         TextSegment::Glyph(GameGlyph::HighQuality),
+///     ]
+/// );
+/// ```
+pub fn decode(bytes: &[u8]) -> Vec<TextSegment> {
+    let mut segments = Vec::<TextSegment>::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match parse_payload(&bytes[pos..]) {
+            Some((payload, len)) => {
+                push_text(&mut segments, &bytes[text_start..pos]);
+                segments.push(TextSegment::Payload(payload));
+                pos += len;
+                text_start = pos;
+            }
+            None => pos += 1,
+        }
+    }
+    push_text(&mut segments, &bytes[text_start..]);
+    segments
+}
+
+/// Converts [`TextSegments`](TextSegment) back into raw game text. This is the inverse of [`decode()`].
+///
+/// Glyphs without a character (ie, [`GameGlyph::Number`] greater than 9) are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::text::{decode, encode};
+///
+/// let bytes = b"/p \x02\x2E\x03\x05\x0D\x03 Potion\xEE\x80\xBC";
+/// assert_eq!(encode(&decode(bytes)), bytes.to_vec());
+/// ```
+pub fn encode(segments: &[TextSegment]) -> Vec<u8> {
+    let mut bytes = Vec::<u8>::new();
+    for segment in segments.iter() {
+        match segment {
+            TextSegment::Glyph(glyph) => {
+                if let Some(glyph) = glyph.to_char() {
+                    let mut buf = [0u8; 4];
+                    bytes.extend_from_slice(glyph.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+            TextSegment::Payload(payload) => bytes.append(&mut payload.to_bytes()),
+            TextSegment::Text(text) => bytes.extend_from_slice(text.as_bytes()),
+        }
+    }
+    bytes
+}
+
+/// Converts readable text back into raw game text, replacing placeholders such as `{HQ}` and `{AT:4:12}`
+/// with their glyphs and payloads. This is the inverse of [`to_readable()`].
+///
+/// Doubled braces, `{{` and `}}`, are read as literal braces. Any other brace that does not start a known
+/// placeholder is left unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::text::from_readable;
+///
+/// assert_eq!(from_readable("Potion{HQ}"), "Potion\u{E03C}".as_bytes().to_vec());
+/// assert_eq!(from_readable("{AT:4:12} {Unknown}"), b"\x02\x2E\x03\x05\x0D\x03 {Unknown}".to_vec());
+/// assert_eq!(from_readable("literal {{HQ}} braces"), b"literal {HQ} braces".to_vec());
+/// ```
+pub fn from_readable(text: &str) -> Vec<u8> {
+    let mut segments = Vec::<TextSegment>::new();
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(brace) = rest.find(|c| c == '{' || c == '}') {
+        literal.push_str(&rest[..brace]);
+        rest = &rest[brace..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            literal.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let segment = rest
+            .find('}')
+            .filter(|_| rest.starts_with('{'))
+            .and_then(|close| Some((parse_placeholder(&rest[1..close])?, close)));
+        match segment {
+            Some((segment, close)) => {
+                segments.push(TextSegment::Text(std::mem::take(&mut literal)));
+                segments.push(segment);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                literal.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    literal.push_str(rest);
+    segments.push(TextSegment::Text(literal));
+    encode(&segments)
+}
+
+/// Converts raw game text into readable text, replacing glyphs and payloads with placeholders
+/// such as `{HQ}` and `{AT:4:12}`. See [`GameGlyph`] and [`Payload`] for all placeholder names.
+///
+/// Literal braces in the text are doubled, so that [`from_readable()`] does not read them as placeholders.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::xiv_macro::text::to_readable;
+///
+/// assert_eq!(to_readable("Potion\u{E03C}".as_bytes()), "Potion{HQ}");
+/// assert_eq!(to_readable(b"/p \x02\x2E\x03\x05\x0D\x03"), "/p {AT:4:12}");
+/// assert_eq!(to_readable(b"literal {HQ} braces"), "literal {{HQ}} braces");
+/// ```
+pub fn to_readable(bytes: &[u8]) -> String {
+    decode(bytes)
+        .into_iter()
+        .map(|segment| match segment {
+            TextSegment::Glyph(glyph) => format!("{{{}}}", glyph),
+            TextSegment::Payload(payload) => format!("{{{}}}", payload),
+            TextSegment::Text(text) => text.replace('{', "{{").replace('}', "}}"),
+        })
+        .collect()
+}
+
+/// Decodes a single integer in the game's variable-length format, returning the value and its length in bytes.
+///
+/// Values below `0xCF` are stored as one byte of `value + 1`. Larger values are stored as a marker byte
+/// (`0xF0` to `0xFE`), whose low bits (after adding 1) flag which big-endian bytes of the value follow.
+fn decode_integer(bytes: &[u8]) -> Option<(u32, usize)> {
+    let marker = *bytes.first()?;
+    match marker {
+        0x01..=0xCF => Some((u32::from(marker) - 1, 1)),
+        0xF0..=0xFE => {
+            let flags = (marker + 1) & 0x0F;
+            let mut value = 0u32;
+            let mut len = 1;
+            for i in (0..4).rev() {
+                if flags & (1 << i) != 0 {
+                    value |= u32::from(*bytes.get(len)?) << (8 * i);
+                    len += 1;
+                }
+            }
+            Some((value, len))
+        }
+        _ => None,
+    }
+}
+
+/// Encodes an integer in the game's variable-length format. See [`decode_integer()`].
+fn encode_integer(value: u32) -> Vec<u8> {
+    if value < 0xCF {
+        return vec![value as u8 + 1];
+    }
+    let mut bytes = vec![0xF0];
+    for (i, byte) in value.to_be_bytes().iter().enumerate() {
+        if *byte != 0 {
+            bytes[0] |= 1 << (3 - i);
+            bytes.push(*byte);
+        }
+    }
+    bytes[0] -= 1;
+    bytes
+}
+
+/// Returns the length in bytes of a well-formed payload at the start of the input, including the start
+/// and end bytes, or `None` if the input does not start with one.
+///
+/// The length is taken from the payload's encoded length, so data bytes equal to `0x03` do not end it.
+pub(crate) fn payload_len(bytes: &[u8]) -> Option<usize> {
+    payload_bounds(bytes).map(|(_, data_end)| data_end + 1)
+}
+
+/// Returns the start and end indices of the data of a well-formed payload at the start of the input.
+fn payload_bounds(bytes: &[u8]) -> Option<(usize, usize)> {
+    if bytes.first() != Some(&PAYLOAD_START) {
+        return None;
+    }
+    let (data_len, int_len) = decode_integer(bytes.get(2..)?)?;
+    let data_start = 2 + int_len;
+    let data_end = data_start.checked_add(data_len as usize)?;
+    if bytes.get(data_end) != Some(&PAYLOAD_END) {
+        return None;
+    }
+    Some((data_start, data_end))
+}
+
+/// Parses a payload at the start of the input, returning the payload and its length in bytes.
+fn parse_payload(bytes: &[u8]) -> Option<(Payload, usize)> {
+    let (data_start, data_end) = payload_bounds(bytes)?;
+    let kind = bytes[1];
+    let data = &bytes[data_start..data_end];
+    let payload = match kind {
+        PAYLOAD_TYPE_AUTO_TRANSLATE => decode_auto_translate(data),
+        _ => None,
+    }
+    .unwrap_or_else(|| Payload::Other {
+        data: data.to_vec(),
+        kind,
+    });
+    Some((payload, data_end + 1))
+}
+
+/// Decodes the data of an auto-translate payload, which is exactly two encoded integers.
+fn decode_auto_translate(data: &[u8]) -> Option<Payload> {
+    let (group, group_len) = decode_integer(data)?;
+    let (key, key_len) = decode_integer(&data[group_len..])?;
+    if group_len + key_len == data.len() {
+        Some(Payload::AutoTranslate { group, key })
+    } else {
+        None
+    }
+}
+
+/// Parses the contents of a readable placeholder, ie `HQ` for `{HQ}`.
+fn parse_placeholder(name: &str) -> Option<TextSegment> {
+    if let Some(glyph) = GameGlyph::from_name(name) {
+        return Some(TextSegment::Glyph(glyph));
+    }
+    let mut parts = name.split(':');
+    let payload = match (parts.next()?, parts.next()?, parts.next()?, parts.next()) {
+        ("AT", group, key, None) => Payload::AutoTranslate {
+            group: group.parse().ok()?,
+            key: key.parse().ok()?,
+        },
+        ("Payload", kind, data, None) if data.len() % 2 == 0 => Payload::Other {
+            data: (0..data.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(data.get(i..i + 2)?, 16).ok())
+                .collect::<Option<Vec<u8>>>()?,
+            kind: u8::from_str_radix(kind, 16).ok()?,
+        },
+        _ => return None,
+    };
+    Some(TextSegment::Payload(payload))
+}
+
+/// Appends decoded text to a list of segments, splitting out any [`GameGlyphs`](GameGlyph).
+fn push_text(segments: &mut Vec<TextSegment>, bytes: &[u8]) {
+    let text = String::from_utf8_lossy(bytes);
+    let mut run = String::new();
+    for next in text.chars() {
+        match GameGlyph::from_char(next) {
+            Some(glyph) => {
+                if !run.is_empty() {
+                    segments.push(TextSegment::Text(std::mem::take(&mut run)));
+                }
+                segments.push(TextSegment::Glyph(glyph));
+            }
+            None => run.push(next),
+        }
+    }
+    if !run.is_empty() {
+        segments.push(TextSegment::Text(run));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- Module Functions
+
+    #[test]
+    fn test_decode_encode_round_trip() -> Result<(), String> {
+        let inputs: [&[u8]; 5] = [
+            b"",
+            b"plain text",
+            "\u{E03C}\u{E090}\u{E0BB}Item".as_bytes(),
+            b"\x02\x2E\x03\x05\x0D\x03",
+            b"\x02\x2E\x07\xF6\x01\x02\x03\xF1\x04\x03\x02\x10\x02\xFF\x03",
+        ];
+        for input in inputs.iter() {
+            assert_eq!(encode(&decode(input)), input.to_vec());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_large_auto_translate() -> Result<(), String> {
+        let segments = decode(b"\x02\x2E\x07\xF6\x01\x02\x03\xF1\x04\x03");
+        assert_eq!(
+            segments,
+            vec![TextSegment::Payload(Payload::AutoTranslate {
+                group: 0x010203,
+                key: 0x0400,
+            })]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_other_payload() -> Result<(), String> {
+        let segments = decode(b"a\x02\x10\x02\xFF\x03b");
+        assert_eq!(
+            segments,
+            vec![
+                TextSegment::Text("a".to_owned()),
+                TextSegment::Payload(Payload::Other {
+                    data: vec![0xFF],
+                    kind: 0x10,
+                }),
+                TextSegment::Text("b".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_malformed_payload() -> Result<(), String> {
+        // Missing end byte
+        let segments = decode(b"\x02\x2E\x03\x05\x0Dx");
+        assert_eq!(segments, vec![TextSegment::Text("\u{2}.\u{3}\u{5}\rx".to_owned())]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_readable_round_trip() -> Result<(), String> {
+        let bytes = b"\x02\x2E\x03\x05\x0D\x03 \xEE\x80\xBC \x02\x10\x02\xFF\x03 {HQ";
+        let readable = to_readable(bytes);
+        assert_eq!(readable, "{AT:4:12} {HQ} {Payload:10:FF} {{HQ");
+        assert_eq!(from_readable(&readable), bytes.to_vec());
+        Ok(())
+    }
+
+    #[test]
+    fn test_readable_round_trip_literal_braces() -> Result<(), String> {
+        let inputs: [&[u8]; 5] = [
+            b"literal {HQ} braces",
+            b"{{HQ}}",
+            b"}{",
+            "{\u{E03C}}".as_bytes(),
+            b"{\x02\x2E\x03\x05\x0D\x03}}",
+        ];
+        for input in inputs.iter() {
+            assert_eq!(from_readable(&to_readable(input)), input.to_vec());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_readable_invalid_placeholders() -> Result<(), String> {
+        let text = "{} {AT:1} {AT:x:1} {Payload:10:F} {HQ {{HQ}} {{{HQ}}}";
+        assert_eq!(
+            from_readable(text),
+            "{} {AT:1} {AT:x:1} {Payload:10:F} {HQ {HQ} {\u{E03C}}".as_bytes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_len() -> Result<(), String> {
+        // The length and data bytes of {AT:4:12} include 0x03.
+        assert_eq!(payload_len(b"\x02\x2E\x03\x05\x0D\x03 "), Some(6));
+        assert_eq!(payload_len(b"\x02\x2E\x03\x05\x0Dx"), None);
+        assert_eq!(payload_len(b"\x02"), None);
+        assert_eq!(payload_len(b"x"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_round_trip() -> Result<(), String> {
+        for value in [0, 1, 0xCE, 0xCF, 0xFF, 0x100, 0x10000, 0x01000000, u32::MAX].iter() {
+            let bytes = encode_integer(*value);
+            assert_eq!(decode_integer(&bytes), Some((*value, bytes.len())));
+        }
+        Ok(())
+    }
+
+    // --- GameGlyph
+
+    #[test]
+    fn test_game_glyph_round_trip() -> Result<(), String> {
+        for code in 0xE000..=0xE0FF {
+            let glyph_char = std::char::from_u32(code).unwrap();
+            if let Some(glyph) = GameGlyph::from_char(glyph_char) {
+                assert_eq!(glyph.to_char(), Some(glyph_char));
+                assert_eq!(GameGlyph::from_name(&glyph.to_string()), Some(glyph));
+            }
+        }
+        Ok(())
+    }
+}
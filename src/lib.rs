@@ -29,7 +29,8 @@
 //! in this library. A section consists of a single UTF-8 char type tag, u16le size, and a null-terminated
 //! UTF-8 string. A single resource (ie, a macro) is then comprised of a repeating pattern of sections.
 //! Other DAT files use fixed-size resource blocks, with each resource immediately following the last.
-//! These are referred to as "Block DATs" below.
+//! These are referred to as "Block DATs" below. A toolkit for working with the raw records of these files
+//! is provided in the [`block`](crate::block) submodule.
 //!
 //! Some DAT files contain unique binary data that does not follow the "standard" DAT format. Others contain
 //! UTF-8 plaintext and are not binary files at all. Support for these files is not currently planned.
@@ -50,8 +51,8 @@
 //! | CONTROL0.DAT       | Gamepad control config           | Plaintext  |         ❌         |         ❌        |
 //! | CONTROL1.DAT       | Keyboard/mouse control config    | Plaintext  |         ❌         |         ❌        |
 //! | FFXIV_CHARA_XX.DAT | Character appearance presets     | Unique     |         ❌         |         ❌        |
-//! | GEARSET.DAT        | Gearsets                         | Block      |         ✅         |         ❌        |
-//! | GS.DAT             | Gold Saucer config (Triad decks) | Block      |         ✅         |         ❌        |
+//! | GEARSET.DAT        | Gearsets                         | Block      |         ✅         |    🌀 - `block`   |
+//! | GS.DAT             | Gold Saucer config (Triad decks) | Block      |         ✅         |    🌀 - `block`   |
 //! | HOTBAR.DAT         | Hotbar layouts                   | Block      |         ✅         |         ❌        |
 //! | ITEMFDR.DAT        | "Search for item" indexing?      | Block      |         ✅         |         ❌        |
 //! | ITEMODR.DAT        | Item order in bags               | Block      |         ✅         |         ❌        |
@@ -118,8 +119,33 @@
 //! dat_file.read(&mut first_256_bytes).unwrap();
 //! ```
 
+// Unit tests return `Ok(assert!(..))` and ignore read/write counts on fixed-size fixtures.
+#![cfg_attr(test, allow(clippy::unit_arg, clippy::needless_return, clippy::unused_io_amount))]
+
+// Allows code generated by `libxivdat-derive` to refer to `::libxivdat` from within this crate.
+#[cfg(feature = "derive")]
+extern crate self as libxivdat;
+
+/// Contains [`AsyncDATFile`](crate::async_dat_file::AsyncDATFile), an async counterpart of
+/// [`DATFile`](crate::dat_file::DATFile) implementing tokio's `AsyncRead`, `AsyncSeek`, and `AsyncWrite`, as well as
+/// async versions of the whole-file convenience functions such as [`read_content()`](crate::async_dat_file::read_content).
+///
+/// Enabled by feature `tokio`.
+#[cfg(feature = "tokio")]
+pub mod async_dat_file;
+/// Contains a generic tool set for working with block-based binary DAT files, which store resources
+/// as fixed-size records. [`read_block()`](crate::block::read_block) and [`write_block()`](crate::block::write_block)
+/// read and overwrite single records of a [`DATFile`](crate::dat_file::DATFile) in-place, while
+/// [`as_block()`](crate::block::as_block) and [`as_block_vec()`](crate::block::as_block_vec) provide zero-copy
+/// access to records of pre-allocated byte arrays.
+///
+/// Records are returned as raw bytes. Known record layouts are available from
+/// [`get_block_layout_for_type()`](crate::block::get_block_layout_for_type). With feature `derive`, records can be
+/// decoded into structs using `#[derive(DatRecord)]`.
+pub mod block;
 /// Contains the [`DATError`](crate::dat_error::DATError) wrapper error. This error type is used
-/// for all functions that do not implement a `std::io` trait.
+/// for all functions that do not implement a `std::io` trait. Each error carries an
+/// [`ErrorContext`](crate::dat_error::ErrorContext) describing where it occurred.
 pub mod dat_error;
 /// Contains a generic, low-level tool set for working with any standard binary DAT files.
 /// This provides the convenience functions [`read_content()`](crate::dat_file::read_content)
@@ -132,7 +158,8 @@ pub mod dat_type;
 /// Contains general-purpose traits and functions applicable to all high-level, file-type-specific
 /// modules such as [`xiv_macro`].
 ///
-/// Enabled by feature `high-level`, which is implied by any file type feature.
+/// Enabled by feature `high-level`, which is implied by any file type feature. Feature `derive` additionally
+/// provides `#[derive(DatRecord)]` for fixed-size binary records.
 #[cfg(feature = "high-level")]
 pub mod high_level;
 /// Contains a generic tool set for working with any section-based binary DAT files.
@@ -140,12 +167,26 @@ pub mod high_level;
 /// [`read_section()`](crate::section::read_section), and [`read_section_content()`](crate::section::read_section_content)
 /// for working with files on disk and [`SectionData`](`crate::section::SectionData),
 /// [`as_section()`](crate::section::as_section`), and [`as_section_vec()`](crate::section::as_section_vec)
-/// for working with pre-allocated byte arrays.
+/// for working with pre-allocated byte arrays. [`SectionReader`](crate::section::SectionReader) and
+/// [`SectionIter`](crate::section::SectionIter) provide the same functionality as lazy iterators.
 ///
-/// Because sections are variable-length data structures, no functions for writing sections in-place are
-/// provided. The recommended approach to writing section-based files is to read the entire file, then
-/// write an entirely new content block with [`write_content()`](crate::dat_file::write_content).
+/// Because sections are variable-length data structures, editing a section in-place requires shifting all
+/// following content. [`SectionWriter`](crate::section::SectionWriter) appends, inserts, replaces, and removes
+/// sections of a [`DATFile`](crate::dat_file::DATFile) in-place, handling this automatically. For bulk changes,
+/// it may be more efficient to read the entire file, then write an entirely new content block with
+/// [`write_content()`](crate::dat_file::write_content).
 pub mod section;
+/// Contains [`Transaction`](crate::transaction::Transaction), which stages content edits to several DAT files and
+/// applies them together. All edits are validated and written to temporary files before any original is replaced,
+/// and replaced originals are restored if any step fails.
+pub mod transaction;
+/// Contains [`DATWatcher`](crate::watch::DATWatcher), which watches a config directory for changes to DAT files
+/// and reports each change as a typed [`DATEvent`](crate::watch::DATEvent) carrying the file's type, character
+/// directory, and a parsed [`DATSnapshot`](crate::watch::DATSnapshot) of its content.
+///
+/// Enabled by feature `watch`.
+#[cfg(feature = "watch")]
+pub mod watch;
 /// Contains the high-level toolkit for working with macro files, `MACRO.DAT` and `MACROSYS.DAT`.
 /// This module contains two equivalent implementations: [`Macro`](crate::xiv_macro::Macro),
 /// [`read_macro()`](crate::xiv_macro::read_macro), and [`read_macro_content()`](crate::xiv_macro::read_macro_content)
@@ -202,6 +243,12 @@ pub mod section;
 /// let out_bytes = to_writeable_bytes(&macro_vec).unwrap();
 /// write_content(&out_path, &out_bytes);
 /// ```
+///
+/// [`MacroBook`](crate::xiv_macro::book::MacroBook) wraps this workflow, offering slot-addressed editing
+/// of a single book and saving it back to the same file in one call.
+///
+/// Macro lines can be parsed into commands, arguments, placeholders, and waits with the
+/// [`script`](crate::xiv_macro::script) submodule.
 #[cfg(feature = "macro")]
 pub mod xiv_macro {
     pub use crate::high_level_modules::r#macro::*;
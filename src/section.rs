@@ -1,7 +1,7 @@
 use std::convert::{TryFrom, TryInto};
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 
-use crate::dat_error::DATError;
+use crate::dat_error::{DATError, ErrorContext};
 use crate::dat_file::{check_type, read_content, DATFile};
 use crate::dat_type::DATType;
 use std::cmp::Ordering;
@@ -77,6 +77,56 @@ pub struct SectionData<'a> {
     pub tag: &'a str,
 }
 
+/// A `Section` is variable-length data structure common to several binary DAT files.
+/// A `Resource` (ie, a Macro or Gearset) is then made out of a repeating pattern of sections.
+/// [`RawSection`] owns its constituent data as raw bytes and is returned from helper functions like [`read_raw_section()`].
+/// Unlike [`Section`], it can hold content that is not valid utf8 text, such as corrupted data.
+///
+/// A [`RawSection`] can be converted to a [`Section`] using [`RawSection::decode()`].
+///
+/// # Section-using file types
+/// `ACQ`, `KEYBIND`, `MACRO`, and `MACROSYS`. See [`SECTION_BASED_TYPES`].
+///
+/// # Data Structure
+/// ```text
+/// 0
+/// 0  1  2  3  ...
+/// |  |--|  |- ...
+/// |  |     \_ null-terminated string bytes
+/// |  \_ u16le content_size
+/// \_ u8 section_type
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RawSection {
+    /// Data content of the section. Does not include the terminating null.
+    pub content: Vec<u8>,
+    /// Length of section content in bytes. Includes terminating null.
+    pub content_size: u16,
+    /// Single byte data type tag. The meaning of this tag varies by file type.
+    /// Some tags are reused with different meanings between types.
+    pub tag: u8,
+}
+
+/// Controls how section content that is not valid utf8 text is handled when decoding a [`RawSection`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeMode {
+    /// Invalid utf8 sequences in the content are replaced with `U+FFFD REPLACEMENT CHARACTER`. The `content_size`
+    /// of the resulting [`Section`] matches the decoded content, which may differ from the original.
+    ///
+    /// The tag is never replaced, since a replacement character could not be written back as a single byte.
+    /// A tag that is not valid utf8 text still produces a
+    /// [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding) error.
+    Lossy,
+    /// Invalid utf8 text produces a [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding) error.
+    Strict,
+}
+
+impl Default for DecodeMode {
+    fn default() -> Self {
+        DecodeMode::Strict
+    }
+}
+
 impl From<&SectionData<'_>> for Section {
     fn from(x: &SectionData) -> Self {
         Section {
@@ -111,10 +161,12 @@ impl TryFrom<&[u8]> for Section {
 
         match usize::from(content_size).cmp(&remaining_buf_size) {
             Ordering::Greater => Err(DATError::Overflow(
-                "Data buffer is too small for content_size specified in header.",
+                ErrorContext::from("Data buffer is too small for content_size specified in header.")
+                    .with_sizes(u64::from(content_size), remaining_buf_size as u64),
             )),
             Ordering::Less => Err(DATError::Underflow(
-                "Data buffer is too large for content_size specified in header.",
+                ErrorContext::from("Data buffer is too large for content_size specified in header.")
+                    .with_sizes(u64::from(content_size), remaining_buf_size as u64),
             )),
             Ordering::Equal => Ok(Section {
                 content: String::from_utf8(x[3..x.len() - 1].to_vec())?,
@@ -159,10 +211,12 @@ impl<'a> TryFrom<&'a [u8]> for SectionData<'a> {
 
         match usize::from(content_size).cmp(&remaining_buf_size) {
             Ordering::Greater => Err(DATError::Overflow(
-                "Data buffer is too small for content_size specified in header.",
+                ErrorContext::from("Data buffer is too small for content_size specified in header.")
+                    .with_sizes(u64::from(content_size), remaining_buf_size as u64),
             )),
             Ordering::Less => Err(DATError::Underflow(
-                "Data buffer is too large for content_size specified in header.",
+                ErrorContext::from("Data buffer is too large for content_size specified in header.")
+                    .with_sizes(u64::from(content_size), remaining_buf_size as u64),
             )),
             Ordering::Equal => Ok(SectionData {
                 content: from_utf8(&x[3..x.len() - 1])?,
@@ -173,6 +227,117 @@ impl<'a> TryFrom<&'a [u8]> for SectionData<'a> {
     }
 }
 
+impl From<RawSection> for Vec<u8> {
+    fn from(x: RawSection) -> Self {
+        let content_size_bytes = x.content_size.to_le_bytes();
+        [x.tag]
+            .iter()
+            .chain(&content_size_bytes)
+            .chain(&x.content)
+            .chain(&[0u8; 1])
+            .copied()
+            .collect()
+    }
+}
+
+impl TryFrom<&Section> for RawSection {
+    type Error = DATError;
+    fn try_from(x: &Section) -> Result<Self, Self::Error> {
+        match x.tag.as_bytes() {
+            [tag] => Ok(RawSection {
+                content: x.content.as_bytes().to_vec(),
+                content_size: x.content_size,
+                tag: *tag,
+            }),
+            _ => Err(DATError::InvalidInput(
+                "Tags may only be a single byte in length.".into(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for RawSection {
+    type Error = DATError;
+    fn try_from(x: &[u8]) -> Result<Self, Self::Error> {
+        // Check the length before validating the content, matching `Section`.
+        if x.len() >= SECTION_HEADER_SIZE {
+            let content_size = u16::from_le_bytes(x[1..SECTION_HEADER_SIZE].try_into()?);
+            if x.len() > SECTION_HEADER_SIZE + usize::from(content_size) {
+                return Err(DATError::Underflow(
+                    "Data buffer is too large for content_size specified in header.".into(),
+                ));
+            }
+        }
+        let (tag, content_size, content) = split_section(x)?;
+        Ok(RawSection {
+            content: content.to_vec(),
+            content_size,
+            tag: tag[0],
+        })
+    }
+}
+
+impl TryFrom<RawSection> for Section {
+    type Error = DATError;
+    fn try_from(x: RawSection) -> Result<Self, Self::Error> {
+        x.decode(DecodeMode::Strict)
+    }
+}
+
+impl RawSection {
+    /// Decodes the [`RawSection`] as a [`Section`] containing utf8 text.
+    ///
+    /// # Errors
+    ///
+    /// In [`DecodeMode::Strict`], returns a [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding)
+    /// if the tag or content is not valid utf8 text.
+    ///
+    /// In [`DecodeMode::Lossy`], returns a [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding)
+    /// if the tag is not valid utf8 text, or a [`DATError::Overflow`](crate::dat_error::DATError::Overflow)
+    /// if replacing invalid sequences would make the content exceed the maximum section size.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::section::{DecodeMode, RawSection};
+    ///
+    /// let raw_section = RawSection { content: vec![0x41, 0xFF, 0x42], content_size: 4, tag: b'T' };
+    /// assert!(raw_section.decode(DecodeMode::Strict).is_err());
+    ///
+    /// let section = raw_section.decode(DecodeMode::Lossy).unwrap();
+    /// assert_eq!(section.content, "A\u{FFFD}B");
+    /// assert_eq!(section.content_size, 6);
+    /// ```
+    pub fn decode(&self, mode: DecodeMode) -> Result<Section, DATError> {
+        let tag_bytes = [self.tag];
+        match mode {
+            DecodeMode::Strict => Ok(Section {
+                content: String::from_utf8(self.content.clone())?,
+                content_size: self.content_size,
+                tag: from_utf8(&tag_bytes)?.to_owned(),
+            }),
+            DecodeMode::Lossy => {
+                // The tag must stay a single byte to be written back, so it is never replaced.
+                let tag = from_utf8(&tag_bytes)?.to_owned();
+                let content = String::from_utf8_lossy(&self.content).into_owned();
+                // Include space for terminating null
+                let content_size = match u16::try_from(content.len() + 1) {
+                    Ok(content_size) => content_size,
+                    Err(_) => {
+                        return Err(DATError::Overflow(
+                            "Section content exceeds maximum possible size (u16::MAX - 1).".into(),
+                        ))
+                    }
+                };
+                Ok(Section {
+                    content,
+                    content_size,
+                    tag,
+                })
+            }
+        }
+    }
+}
+
 impl Section {
     /// Builds a new [`Section`] with a given tag and content
     ///
@@ -187,14 +352,16 @@ impl Section {
     /// ```
     pub fn new(tag: String, content: String) -> Result<Self, DATError> {
         if tag.len() != 1 {
-            return Err(DATError::InvalidInput("Tags may only be a single character in length."));
+            return Err(DATError::InvalidInput(
+                "Tags may only be a single character in length.".into(),
+            ));
         }
         // Include space for terminating null
         let content_size = match u16::try_from(content.len() + 1) {
             Ok(content_size) => content_size,
             Err(_) => {
                 return Err(DATError::Overflow(
-                    "Section content exceeds maximum possible size (u16::MAX - 1).",
+                    "Section content exceeds maximum possible size (u16::MAX - 1).".into(),
                 ))
             }
         };
@@ -220,14 +387,16 @@ impl<'a> SectionData<'a> {
     /// ```
     pub fn new(tag: &'a str, content: &'a str) -> Result<Self, DATError> {
         if tag.len() != 1 {
-            return Err(DATError::InvalidInput("Tags may only be a single character in length."));
+            return Err(DATError::InvalidInput(
+                "Tags may only be a single character in length.".into(),
+            ));
         }
         // Include space for terminating null
         let content_size = match u16::try_from(content.len() + 1) {
             Ok(content_size) => content_size,
             Err(_) => {
                 return Err(DATError::Overflow(
-                    "Section content exceeds maximum possible size (u16::MAX - 1).",
+                    "Section content exceeds maximum possible size (u16::MAX - 1).".into(),
                 ))
             }
         };
@@ -265,17 +434,18 @@ impl<'a> SectionData<'a> {
 /// assert_eq!(section.content_size, 24);
 /// assert_eq!(section.content, "This is a test section.");
 /// ```
-pub fn as_section(bytes: &[u8]) -> Result<SectionData, DATError> {
+pub fn as_section(bytes: &[u8]) -> Result<SectionData<'_>, DATError> {
     SectionData::try_from(bytes)
 }
 
 /// Interprets a byte slice as a block of [`SectionData`], returning a [`Vec`] of them.
+/// To stop early or locate errors, use [`SectionIter`] instead.
 ///
 /// # Errors
 ///
 /// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
 /// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if a section content block
-/// does not match the expected length specified in the section header.
+/// does not match the expected length specified in the section header or the slice ends mid-section.
 ///
 /// If the tag or content is not valid utf8 text, a [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding)
 /// error will be returned.
@@ -297,29 +467,43 @@ pub fn as_section(bytes: &[u8]) -> Result<SectionData, DATError> {
 /// assert_eq!(section[1].content, "Another test section.");
 /// ```
 pub fn as_section_vec<'a>(bytes: &'a [u8]) -> Result<Vec<SectionData<'a>>, DATError> {
+    SectionIter::new(bytes)
+        .map(|res| res.map(|(_, section)| section))
+        .collect()
+}
+
+/// Interprets a byte slice as a block of [`RawSections`](RawSection), returning a [`Vec`] of them.
+/// Section content is not decoded, so this does not fail on content that is not valid utf8 text.
+///
+/// # Errors
+///
+/// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
+/// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if a section content block
+/// does not match the expected length specified in the section header or the slice ends mid-section.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::read_content;
+/// use libxivdat::section::as_raw_section_vec;
+///
+/// let content_bytes = read_content("./resources/TEST_SECTION.DAT").unwrap();
+/// let sections = as_raw_section_vec(&content_bytes).unwrap();
+///
+/// assert_eq!(sections[0].tag, b'T');
+/// assert_eq!(sections[0].content_size, 24);
+/// assert_eq!(sections[0].content, b"This is a test section.");
+/// ```
+pub fn as_raw_section_vec(bytes: &[u8]) -> Result<Vec<RawSection>, DATError> {
     let mut cursor = 0usize;
-    let mut res_vec = Vec::<SectionData<'a>>::new();
-    let buf_len = bytes.len();
-    while cursor < buf_len {
-        // Read header block
-        let header_bytes = &bytes[cursor..cursor + SECTION_HEADER_SIZE];
-        let (tag, content_size) = get_section_header_contents(header_bytes.try_into()?)?;
-        cursor += SECTION_HEADER_SIZE;
-        // Read content block; leave the terminating null out of the content slice
-        let content_bytes = &bytes[cursor..cursor + usize::from(content_size) - 1];
-        cursor += usize::from(content_size);
-        // Validate content size
-        if content_bytes.contains(&0u8) {
-            return Err(DATError::Underflow("Section content ended early."));
-        }
-        if bytes[cursor - 1] != 0u8 {
-            return Err(DATError::Overflow("Section data did not end at the expected index."));
-        }
-        // Build a section and push to vec
-        res_vec.push(SectionData::<'a> {
-            content: from_utf8(content_bytes)?,
+    let mut res_vec = Vec::<RawSection>::new();
+    while cursor < bytes.len() {
+        let (tag, content_size, content) = split_section(&bytes[cursor..])
+            .map_err(|err| err.with_offset(cursor as u64).with_section_index(res_vec.len()))?;
+        cursor += SECTION_HEADER_SIZE + usize::from(content_size);
+        res_vec.push(RawSection {
+            content: content.to_vec(),
             content_size,
-            tag,
+            tag: tag[0],
         });
     }
     Ok(res_vec)
@@ -378,7 +562,7 @@ pub fn read_section(dat_file: &mut DATFile) -> Result<Section, DATError> {
         Ok(read_section_unsafe(dat_file)?)
     } else {
         Err(DATError::IncorrectType(
-            "Target file is of a type that should not contain sections.",
+            "Target file is of a type that should not contain sections.".into(),
         ))
     }
 }
@@ -388,6 +572,10 @@ pub fn read_section(dat_file: &mut DATFile) -> Result<Section, DATError> {
 /// to prevent repeat file access. This is similar to [`read_content()`](crate::dat_file::read_content),
 /// but returns a `Vec<Section>` instead of raw bytes.
 ///
+/// A single section that is not valid utf8 text causes this function to fail. To read the remaining sections,
+/// use [`read_section_content_with_mode()`] or [`read_raw_section_content()`]. The decode mode is chosen with a
+/// separate function, rather than a parameter here, so that existing callers of this function are unaffected.
+///
 /// # Errors
 ///
 /// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
@@ -425,11 +613,153 @@ pub fn read_section_content<P: AsRef<Path>>(path: P) -> Result<Vec<Section>, DAT
         Ok(read_section_content_unsafe(path)?)
     } else {
         Err(DATError::IncorrectType(
-            "Target file is of a type that should not contain sections.",
+            "Target file is of a type that should not contain sections.".into(),
+        ))
+    }
+}
+
+/// Reads the next [`RawSection`] from a [`DATFile`](crate::dat_file::DATFile).
+/// Section content is not decoded, so this does not fail on content that is not valid utf8 text.
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain sections.
+///
+/// Returns [`DATError::EndOfFile`] if there is not a full section remaining in the file.
+///
+/// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
+/// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if the section content block
+/// does not match the expected length specified in the section header.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`]
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::DATFile;
+/// use libxivdat::section::read_raw_section;
+///
+/// let mut dat_file = DATFile::open("./resources/TEST_SECTION.DAT").unwrap();
+/// let section = read_raw_section(&mut dat_file).unwrap();
+///
+/// assert_eq!(section.tag, b'T');
+/// assert_eq!(section.content_size, 24);
+/// assert_eq!(section.content, b"This is a test section.");
+/// ```
+pub fn read_raw_section(dat_file: &mut DATFile) -> Result<RawSection, DATError> {
+    if !SECTION_BASED_TYPES.contains(&dat_file.file_type()) {
+        return Err(DATError::IncorrectType(
+            "Target file is of a type that should not contain sections.".into(),
+        ));
+    }
+    // Read section header.
+    let mut sec_bytes = vec![0u8; SECTION_HEADER_SIZE];
+    // Manually wrap EOF into DATError EOF
+    match dat_file.read_exact(&mut sec_bytes) {
+        Ok(_) => (),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            return Err(DATError::EndOfFile("Found EOF looking for next section.".into()))
+        }
+        Err(err) => return Err(DATError::from(err)),
+    };
+    let content_size = u16::from_le_bytes(sec_bytes[1..].try_into()?);
+    // Read section content, including the terminating null.
+    sec_bytes.resize(SECTION_HEADER_SIZE + usize::from(content_size), 0);
+    match dat_file.read_exact(&mut sec_bytes[SECTION_HEADER_SIZE..]) {
+        Ok(_) => (),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            return Err(DATError::EndOfFile("Found EOF reading next section.".into()))
+        }
+        Err(err) => return Err(DATError::from(err)),
+    };
+    RawSection::try_from(&sec_bytes[..])
+}
+
+/// Reads all [`RawSections`](RawSection) from a specified DAT file, returning a [`Vec`] of them.
+/// This performs only one read operation on the underlying file, loading the entire content into memory
+/// to prevent repeat file access. Section content is not decoded, so this does not fail on content that is
+/// not valid utf8 text.
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain sections.
+///
+/// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
+/// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if a section content block
+/// does not match the expected length specified in the section header.
+///
+/// Returns a [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) if the specified file does not
+/// have a valid DAT header.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::section::read_raw_section_content;
+///
+/// let sections = read_raw_section_content("./resources/TEST_SECTION.DAT").unwrap();
+///
+/// assert_eq!(sections[1].tag, b'A');
+/// assert_eq!(sections[1].content_size, 22);
+/// assert_eq!(sections[1].content, b"Another test section.");
+/// ```
+pub fn read_raw_section_content<P: AsRef<Path>>(path: P) -> Result<Vec<RawSection>, DATError> {
+    if SECTION_BASED_TYPES.contains(&check_type(&path)?) {
+        as_raw_section_vec(&read_content(&path)?).map_err(|err| err.with_path(&path))
+    } else {
+        Err(DATError::IncorrectType(
+            "Target file is of a type that should not contain sections.".into(),
         ))
     }
 }
 
+/// Reads all [`Sections`](Section) from a specified DAT file, returning a [`Vec`] of them.
+/// This is equivalent to [`read_section_content()`], but allows choosing how content
+/// that is not valid utf8 text is handled. See [`DecodeMode`].
+///
+/// # Errors
+///
+/// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+/// that does not contain sections.
+///
+/// Returns a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
+/// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if a section content block
+/// does not match the expected length specified in the section header.
+///
+/// In [`DecodeMode::Strict`], returns a [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding)
+/// if a section does not contain valid utf8 text.
+///
+/// Returns a [`DATError::BadHeader`](crate::dat_error::DATError::BadHeader) if the specified file does not
+/// have a valid DAT header.
+///
+/// If an I/O error occurs while reading the file, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO)
+/// error will be returned wrapping the underlying FS error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::section::{read_section_content_with_mode, DecodeMode};
+///
+/// let section = read_section_content_with_mode("./resources/TEST_SECTION.DAT", DecodeMode::Lossy).unwrap();
+///
+/// assert_eq!(section[0].tag, "T");
+/// assert_eq!(section[0].content_size, 24);
+/// assert_eq!(section[0].content, "This is a test section.");
+/// ```
+pub fn read_section_content_with_mode<P: AsRef<Path>>(path: P, mode: DecodeMode) -> Result<Vec<Section>, DATError> {
+    read_raw_section_content(&path)?
+        .iter()
+        .enumerate()
+        .map(|(i, raw_section)| {
+            raw_section
+                .decode(mode)
+                .map_err(|err| err.with_section_index(i).with_path(&path))
+        })
+        .collect()
+}
+
 /// Reads the next [`Section`] from a [`DATFile`](crate::dat_file::DATFile). This does not check that the
 /// file is of a type that should contain sections.
 ///
@@ -463,7 +793,7 @@ pub fn read_section_unsafe(dat_file: &mut DATFile) -> Result<Section, DATError>
     match dat_file.read_exact(&mut sec_header_bytes) {
         Ok(_) => (),
         Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
-            return Err(DATError::EndOfFile("Found EOF looking for next section."))
+            return Err(DATError::EndOfFile("Found EOF looking for next section.".into()))
         }
         Err(err) => return Err(DATError::from(err)),
     };
@@ -473,7 +803,7 @@ pub fn read_section_unsafe(dat_file: &mut DATFile) -> Result<Section, DATError>
     match dat_file.read_exact(&mut sec_content_bytes) {
         Ok(_) => (),
         Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
-            return Err(DATError::EndOfFile("Found EOF reading next section."))
+            return Err(DATError::EndOfFile("Found EOF reading next section.".into()))
         }
         Err(err) => return Err(DATError::from(err)),
     };
@@ -522,51 +852,665 @@ pub fn read_section_unsafe(dat_file: &mut DATFile) -> Result<Section, DATError>
 /// assert_eq!(section[1].content, "Another test section.");
 /// ```
 pub fn read_section_content_unsafe<P: AsRef<Path>>(path: P) -> Result<Vec<Section>, DATError> {
-    let content_bytes = read_content(path)?;
-    let section_data = as_section_vec(&content_bytes)?;
+    let content_bytes = read_content(&path)?;
+    let section_data = as_section_vec(&content_bytes).map_err(|err| err.with_path(&path))?;
     Ok(section_data.iter().map(Section::from).collect())
 }
 
-// --- Unit Tests
+/// An iterator over the [`SectionData`] contained in a byte slice. No section data is copied.
+///
+/// Each item is a tuple of the byte offset the section was found at and the section itself.
+/// After an error is returned, the iterator is exhausted and [`offset()`](Self::offset) points
+/// at the start of the invalid section.
+///
+/// # Errors
+///
+/// Items are a [`DATError::Overflow`](crate::dat_error::DATError::Overflow) or
+/// [`DATError::Underflow`](crate::dat_error::DATError::Underflow) if a section content block
+/// does not match the expected length specified in the section header or the slice ends mid-section.
+///
+/// If the tag or content is not valid utf8 text, the item is a
+/// [`DATError::BadEncoding`](crate::dat_error::DATError::BadEncoding) error.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::read_content;
+/// use libxivdat::section::SectionIter;
+///
+/// let content_bytes = read_content("./resources/TEST_SECTION.DAT").unwrap();
+/// let mut iter = SectionIter::new(&content_bytes);
+///
+/// let (offset, section) = iter.next().unwrap().unwrap();
+/// assert_eq!(offset, 0);
+/// assert_eq!(section.content, "This is a test section.");
+///
+/// let (offset, section) = iter.next().unwrap().unwrap();
+/// assert_eq!(offset, 27);
+/// assert_eq!(section.content, "Another test section.");
+///
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SectionIter<'a> {
+    /// The slice being iterated.
+    bytes: &'a [u8],
+    /// True if iteration has ended due to an error.
+    failed: bool,
+    /// Index of the next section.
+    index: usize,
+    /// Offset of the next section in `bytes`.
+    offset: usize,
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::dat_file::{read_content, DATFile};
+impl<'a> SectionIter<'a> {
+    /// Builds a new [`SectionIter`] over a byte slice.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::section::SectionIter;
+    ///
+    /// let bytes = [0x41, 0x04, 0x00, 0x41, 0x42, 0x43, 0x00];
+    /// assert_eq!(SectionIter::new(&bytes).count(), 1);
+    /// ```
+    pub fn new(bytes: &'a [u8]) -> Self {
+        SectionIter {
+            bytes,
+            failed: false,
+            index: 0,
+            offset: 0,
+        }
+    }
 
-    const TEST_FILE_PATH: &str = "./resources/TEST_SECTION.DAT";
-    const TEST_NON_SECTION_PATH: &str = "./resources/TEST_BLOCK.DAT";
-    const TEST_FILE_SEC1_CONTENTS: (&str, u16, &str) = ("T", 24, "This is a test section.");
-    const TEST_FILE_SEC2_CONTENTS: (&str, u16, &str) = ("A", 22, "Another test section.");
-    const TEST_SEC: [u8; 7] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x43, 0x00];
-    const TEST_SEC_CONTENTS: (&str, u16, &str) = ("A", 4, "ABC");
-    const TEST_SEC_NOT_UTF8: [u8; 7] = [0xc2, 0x04, 0x00, 0x01, 0x01, 0x01, 0x00];
-    const TEST_SEC_TOO_SHORT: [u8; 6] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x00];
-    const TEST_SEC_TOO_LONG: [u8; 8] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x43, 0x44, 0x00];
+    /// Returns the byte offset of the next section, or of the invalid section if an error was returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::section::SectionIter;
+    ///
+    /// // The second section claims 9 bytes of content, but only 3 remain.
+    /// let bytes = [0x41, 0x02, 0x00, 0x41, 0x00, 0x42, 0x09, 0x00, 0x42, 0x43, 0x00];
+    /// let mut iter = SectionIter::new(&bytes);
+    /// assert!(iter.next().unwrap().is_ok());
+    /// assert!(iter.next().unwrap().is_err());
+    /// assert_eq!(iter.offset(), 5);
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 
-    // --- Module Functions
+    /// Interprets the section at the current offset, returning it and its total length in bytes.
+    fn next_section(&self) -> Result<(SectionData<'a>, usize), DATError> {
+        let (tag, content_size, content_bytes) = split_section(&self.bytes[self.offset..])?;
+        Ok((
+            SectionData {
+                content: from_utf8(content_bytes)?,
+                content_size,
+                tag: from_utf8(tag)?,
+            },
+            SECTION_HEADER_SIZE + usize::from(content_size),
+        ))
+    }
+}
 
-    #[test]
-    fn test_as_section() -> Result<(), String> {
-        match as_section(&TEST_SEC[..]) {
-            Ok(section) => {
-                assert_eq!(section.tag, TEST_SEC_CONTENTS.0);
-                assert_eq!(section.content_size, TEST_SEC_CONTENTS.1);
-                assert_eq!(section.content, TEST_SEC_CONTENTS.2);
-                Ok(())
+impl<'a> Iterator for SectionIter<'a> {
+    type Item = Result<(usize, SectionData<'a>), DATError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.bytes.len() {
+            return None;
+        }
+        match self.next_section() {
+            Ok((section, section_len)) => {
+                let offset = self.offset;
+                self.offset += section_len;
+                self.index += 1;
+                Some(Ok((offset, section)))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err.with_offset(self.offset as u64).with_section_index(self.index)))
             }
-            Err(err) => Err(format!("Error: {}", err)),
         }
     }
+}
 
-    #[test]
-    fn test_as_section_vec() -> Result<(), String> {
-        let sec_bytes = match read_content(TEST_FILE_PATH) {
-            Ok(sec_bytes) => sec_bytes,
-            Err(err) => return Err(format!("Error reading file: {}", err)),
-        };
-        match as_section_vec(&sec_bytes) {
-            Ok(section) => {
+impl std::iter::FusedIterator for SectionIter<'_> {}
+
+/// An iterator that lazily reads [`Sections`](Section) from a [`DATFile`](crate::dat_file::DATFile)
+/// using [`read_section()`], starting from the current cursor position.
+///
+/// Each item is a tuple of the content offset the section was found at and the section itself.
+/// After an error is returned, the iterator is exhausted and [`offset()`](Self::offset) points
+/// at the start of the invalid section.
+///
+/// # Errors
+///
+/// Items are the same errors returned by [`read_section()`].
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::DATFile;
+/// use libxivdat::section::SectionReader;
+///
+/// let mut dat_file = DATFile::open("./resources/TEST_SECTION.DAT").unwrap();
+/// let reader = SectionReader::new(&mut dat_file).unwrap();
+///
+/// // Stop reading once the wanted section is found.
+/// let found = reader
+///     .map(|res| res.unwrap())
+///     .find(|(_, section)| section.tag == "A")
+///     .unwrap();
+/// assert_eq!(found.0, 27);
+/// assert_eq!(found.1.content, "Another test section.");
+/// ```
+#[derive(Debug)]
+pub struct SectionReader<'a> {
+    /// The file being read.
+    dat_file: &'a mut DATFile,
+    /// True if iteration has ended due to an error.
+    failed: bool,
+    /// Index of the next section, relative to the starting cursor position.
+    index: usize,
+    /// Content offset of the next section.
+    offset: u64,
+}
+
+impl<'a> SectionReader<'a> {
+    /// Builds a new [`SectionReader`] for a [`DATFile`](crate::dat_file::DATFile).
+    /// Sections will be read starting from the current cursor position of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+    /// that does not contain sections.
+    ///
+    /// If an I/O error occurs while reading the cursor position, a [`DATError::FileIO`]
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::SectionReader;
+    ///
+    /// let mut dat_file = DATFile::open("./resources/TEST_SECTION.DAT").unwrap();
+    /// assert_eq!(SectionReader::new(&mut dat_file).unwrap().count(), 2);
+    /// ```
+    pub fn new(dat_file: &'a mut DATFile) -> Result<Self, DATError> {
+        if !SECTION_BASED_TYPES.contains(&dat_file.file_type()) {
+            return Err(DATError::IncorrectType(
+                "Target file is of a type that should not contain sections.".into(),
+            ));
+        }
+        let offset = dat_file.stream_position()?;
+        Ok(SectionReader {
+            dat_file,
+            failed: false,
+            index: 0,
+            offset,
+        })
+    }
+
+    /// Returns the content offset of the next section, or of the invalid section if an error was returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::SectionReader;
+    ///
+    /// let mut dat_file = DATFile::open("./resources/TEST_SECTION.DAT").unwrap();
+    /// let mut reader = SectionReader::new(&mut dat_file).unwrap();
+    /// reader.next();
+    /// assert_eq!(reader.offset(), 27);
+    /// ```
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl Iterator for SectionReader<'_> {
+    type Item = Result<(u64, Section), DATError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Readable content is 1 byte shorter than content_size.
+        if self.failed || self.offset + 1 >= u64::from(self.dat_file.content_size()) {
+            return None;
+        }
+        let res = self.dat_file.seek(SeekFrom::Start(self.offset)).map_err(DATError::from);
+        match res.and_then(|_| read_section(self.dat_file)) {
+            Ok(section) => {
+                let offset = self.offset;
+                self.offset += (SECTION_HEADER_SIZE + usize::from(section.content_size)) as u64;
+                self.index += 1;
+                Some(Ok((offset, section)))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err.with_offset(self.offset).with_section_index(self.index)))
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for SectionReader<'_> {}
+
+/// Edits the [`Sections`](Section) of a [`DATFile`](crate::dat_file::DATFile) in-place.
+///
+/// Sections are addressed by their index in the file. Writes shift any following sections as needed and
+/// update the content size of the file with [`set_content_size()`](crate::dat_file::DATFile::set_content_size).
+/// All size checks are performed before the file is modified. The file must be opened with read and write access.
+///
+/// The cursor position of the underlying [`DATFile`](crate::dat_file::DATFile) is not preserved.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::DATFile;
+/// use libxivdat::section::{read_section_content, SectionData, SectionWriter};
+/// use std::fs::OpenOptions;
+///
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let temp_dir = tempdir().unwrap();
+/// # let path = temp_dir.path().join("TEST.DAT");
+/// # std::fs::copy("./resources/TEST_SECTION.DAT", &path).unwrap();
+///
+/// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+/// let mut writer = SectionWriter::new(&mut dat_file).unwrap();
+///
+/// writer.insert(1, SectionData::new("B", "Inserted.").unwrap()).unwrap();
+/// writer.remove(0).unwrap();
+/// writer.append(SectionData::new("C", "Appended.").unwrap()).unwrap();
+///
+/// let sections = read_section_content(&path).unwrap();
+/// assert_eq!(sections[0].content, "Inserted.");
+/// assert_eq!(sections[1].content, "Another test section.");
+/// assert_eq!(sections[2].content, "Appended.");
+/// ```
+#[derive(Debug)]
+pub struct SectionWriter<'a> {
+    /// The file being edited.
+    dat_file: &'a mut DATFile,
+}
+
+impl<'a> SectionWriter<'a> {
+    /// Builds a new [`SectionWriter`] for a [`DATFile`](crate::dat_file::DATFile).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DATError::IncorrectType`] if the file appears to be of a [`DATType`]
+    /// that does not contain sections.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::SectionWriter;
+    ///
+    /// let mut dat_file = DATFile::open("./resources/TEST_SECTION.DAT").unwrap();
+    /// assert!(SectionWriter::new(&mut dat_file).is_ok());
+    /// ```
+    pub fn new(dat_file: &'a mut DATFile) -> Result<Self, DATError> {
+        if SECTION_BASED_TYPES.contains(&dat_file.file_type()) {
+            Ok(SectionWriter { dat_file })
+        } else {
+            Err(DATError::IncorrectType(
+                "Target file is of a type that should not contain sections.".into(),
+            ))
+        }
+    }
+
+    /// Appends a section to the end of the file content.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::Overflow`] if the content would exceed the maximum size of the file.
+    ///
+    /// Returns a [`DATError::InvalidInput`] if the section is malformed.
+    ///
+    /// If an I/O error occurs while reading or writing the file, a [`DATError::FileIO`]
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::{read_section_content, SectionData, SectionWriter};
+    /// use std::fs::OpenOptions;
+    ///
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("TEST.DAT");
+    /// # std::fs::copy("./resources/TEST_SECTION.DAT", &path).unwrap();
+    ///
+    /// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+    /// let mut writer = SectionWriter::new(&mut dat_file).unwrap();
+    /// writer.append(SectionData::new("C", "Appended.").unwrap()).unwrap();
+    ///
+    /// let sections = read_section_content(&path).unwrap();
+    /// assert_eq!(sections.len(), 3);
+    /// assert_eq!(sections[2].content, "Appended.");
+    /// ```
+    pub fn append(&mut self, section: SectionData) -> Result<(), DATError> {
+        let end = self.dat_file.content_size() - 1;
+        self.splice(end, 0, &section_to_bytes(section)?)
+    }
+
+    /// Inserts a section at a given index, shifting all following sections back.
+    /// An index equal to the number of sections in the file appends the section.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::InvalidInput`] if the index is greater than the number of sections in the file
+    /// or if the section is malformed.
+    ///
+    /// Returns a [`DATError::Overflow`] if the content would exceed the maximum size of the file.
+    ///
+    /// Returns a [`DATError::EndOfFile`] if the existing content ends in the middle of a section.
+    ///
+    /// If an I/O error occurs while reading or writing the file, a [`DATError::FileIO`]
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::{read_section_content, SectionData, SectionWriter};
+    /// use std::fs::OpenOptions;
+    ///
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("TEST.DAT");
+    /// # std::fs::copy("./resources/TEST_SECTION.DAT", &path).unwrap();
+    ///
+    /// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+    /// let mut writer = SectionWriter::new(&mut dat_file).unwrap();
+    /// writer.insert(0, SectionData::new("B", "Inserted.").unwrap()).unwrap();
+    ///
+    /// let sections = read_section_content(&path).unwrap();
+    /// assert_eq!(sections[0].content, "Inserted.");
+    /// assert_eq!(sections[1].content, "This is a test section.");
+    /// ```
+    pub fn insert(&mut self, index: usize, section: SectionData) -> Result<(), DATError> {
+        let bytes = section_to_bytes(section)?;
+        let (offset, _) = self.find_section(index)?;
+        self.splice(offset, 0, &bytes)
+    }
+
+    /// Removes the section at a given index, shifting all following sections forward.
+    /// Returns the removed section.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::InvalidInput`] if there is no section at the index.
+    ///
+    /// Returns a [`DATError::EndOfFile`] if the existing content ends in the middle of a section.
+    ///
+    /// If an I/O error occurs while reading or writing the file, a [`DATError::FileIO`]
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// If the removed section is not valid utf8 text, a [`DATError::BadEncoding`]
+    /// error will be returned and the file will not be modified.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::{read_section_content, SectionWriter};
+    /// use std::fs::OpenOptions;
+    ///
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("TEST.DAT");
+    /// # std::fs::copy("./resources/TEST_SECTION.DAT", &path).unwrap();
+    ///
+    /// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+    /// let mut writer = SectionWriter::new(&mut dat_file).unwrap();
+    /// let removed = writer.remove(0).unwrap();
+    /// assert_eq!(removed.content, "This is a test section.");
+    ///
+    /// let sections = read_section_content(&path).unwrap();
+    /// assert_eq!(sections.len(), 1);
+    /// assert_eq!(sections[0].content, "Another test section.");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Result<Section, DATError> {
+        let (offset, len) = self.find_existing_section(index)?;
+        let removed = self.read_section_at(offset)?;
+        self.splice(offset, len, &[])?;
+        Ok(removed)
+    }
+
+    /// Replaces the section at a given index, shifting all following sections as needed.
+    /// Returns the replaced section.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::InvalidInput`] if there is no section at the index or if the section is malformed.
+    ///
+    /// Returns a [`DATError::Overflow`] if the content would exceed the maximum size of the file.
+    ///
+    /// Returns a [`DATError::EndOfFile`] if the existing content ends in the middle of a section.
+    ///
+    /// If an I/O error occurs while reading or writing the file, a [`DATError::FileIO`]
+    /// error will be returned wrapping the underlying FS error.
+    ///
+    /// If the replaced section is not valid utf8 text, a [`DATError::BadEncoding`]
+    /// error will be returned and the file will not be modified.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_file::DATFile;
+    /// use libxivdat::section::{read_section_content, SectionData, SectionWriter};
+    /// use std::fs::OpenOptions;
+    ///
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let path = temp_dir.path().join("TEST.DAT");
+    /// # std::fs::copy("./resources/TEST_SECTION.DAT", &path).unwrap();
+    ///
+    /// let mut dat_file = DATFile::open_options(&path, OpenOptions::new().read(true).write(true)).unwrap();
+    /// let mut writer = SectionWriter::new(&mut dat_file).unwrap();
+    /// let replaced = writer.replace(0, SectionData::new("T", "Short.").unwrap()).unwrap();
+    /// assert_eq!(replaced.content, "This is a test section.");
+    ///
+    /// let sections = read_section_content(&path).unwrap();
+    /// assert_eq!(sections[0].content, "Short.");
+    /// assert_eq!(sections[1].content, "Another test section.");
+    /// ```
+    pub fn replace(&mut self, index: usize, section: SectionData) -> Result<Section, DATError> {
+        let bytes = section_to_bytes(section)?;
+        let (offset, len) = self.find_existing_section(index)?;
+        let replaced = self.read_section_at(offset)?;
+        self.splice(offset, len, &bytes)?;
+        Ok(replaced)
+    }
+
+    /// Finds the content offset of the section at a given index by walking section headers.
+    /// Returns a tuple of (`offset`, `length`), where `length` is [`None`] if the index is one past the last section.
+    fn find_section(&mut self, index: usize) -> Result<(u32, Option<u32>), DATError> {
+        let content_end = self.dat_file.content_size() - 1;
+        let mut offset = 0u32;
+        let mut cur_index = 0usize;
+        while offset < content_end {
+            self.dat_file.seek(SeekFrom::Start(u64::from(offset)))?;
+            let mut header_bytes = [0u8; SECTION_HEADER_SIZE];
+            match self.dat_file.read_exact(&mut header_bytes) {
+                Ok(_) => (),
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    return Err(DATError::EndOfFile("Found EOF looking for next section.".into()))
+                }
+                Err(err) => return Err(DATError::from(err)),
+            };
+            let (_, content_size) = get_section_header_contents(&header_bytes)?;
+            let len = SECTION_HEADER_SIZE as u32 + u32::from(content_size);
+            if offset + len > content_end {
+                return Err(DATError::EndOfFile("Found EOF reading next section.".into()));
+            }
+            if cur_index == index {
+                return Ok((offset, Some(len)));
+            }
+            offset += len;
+            cur_index += 1;
+        }
+        if cur_index == index {
+            Ok((offset, None))
+        } else {
+            Err(DATError::InvalidInput("Section index is out of bounds.".into()))
+        }
+    }
+
+    /// Finds the content offset and length of an existing section at a given index.
+    fn find_existing_section(&mut self, index: usize) -> Result<(u32, u32), DATError> {
+        match self.find_section(index)? {
+            (offset, Some(len)) => Ok((offset, len)),
+            (_, None) => Err(DATError::InvalidInput("Section index is out of bounds.".into())),
+        }
+    }
+
+    /// Reads the section starting at a given content offset.
+    fn read_section_at(&mut self, offset: u32) -> Result<Section, DATError> {
+        self.dat_file.seek(SeekFrom::Start(u64::from(offset)))?;
+        read_section_unsafe(self.dat_file)
+    }
+
+    /// Replaces `old_len` bytes of content at `offset` with `new_bytes`, shifting all following content.
+    fn splice(&mut self, offset: u32, old_len: u32, new_bytes: &[u8]) -> Result<(), DATError> {
+        let content_size = self.dat_file.content_size();
+        let tail_start = offset + old_len;
+        let tail_len = content_size - 1 - tail_start;
+        // Check the final size before touching the file.
+        let new_size = match u32::try_from(new_bytes.len())
+            .ok()
+            .and_then(|new_len| offset.checked_add(new_len))
+            .and_then(|size| size.checked_add(tail_len))
+            .and_then(|size| size.checked_add(1))
+        {
+            Some(new_size) if new_size <= self.dat_file.max_size() => new_size,
+            _ => return Err(DATError::Overflow("Content size would exceed maximum size.".into())),
+        };
+        let mut tail = vec![0u8; usize::try_from(tail_len)?];
+        self.dat_file.seek(SeekFrom::Start(u64::from(tail_start)))?;
+        self.dat_file.read_exact(&mut tail)?;
+        // Grow before writing and shrink after, so content outside the new size is always null padded.
+        if new_size > content_size {
+            self.dat_file.set_content_size(new_size)?;
+        }
+        self.dat_file.seek(SeekFrom::Start(u64::from(offset)))?;
+        self.dat_file.write_all(new_bytes)?;
+        self.dat_file.write_all(&tail)?;
+        if new_size < content_size {
+            self.dat_file.set_content_size(new_size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the section at the start of a byte slice into (`tag`, `content_size`, `content`), validating its length.
+/// The terminating null is not included in `content`. Any bytes following the section are ignored.
+fn split_section(bytes: &[u8]) -> Result<(&[u8], u16, &[u8]), DATError> {
+    if bytes.len() < SECTION_HEADER_SIZE {
+        return Err(DATError::Overflow(
+            ErrorContext::from("Data buffer is too small for a section header.")
+                .with_sizes(SECTION_HEADER_SIZE as u64, bytes.len() as u64),
+        ));
+    }
+    let content_size = u16::from_le_bytes(bytes[1..SECTION_HEADER_SIZE].try_into()?);
+    let tag = String::from_utf8_lossy(&bytes[..1]).into_owned();
+    if content_size == 0 {
+        return Err(DATError::Underflow(
+            ErrorContext::from("Section content_size does not include a terminating null.").with_tag(tag),
+        ));
+    }
+    let section_len = SECTION_HEADER_SIZE + usize::from(content_size);
+    if bytes.len() < section_len {
+        return Err(DATError::Overflow(
+            ErrorContext::from("Data buffer is too small for content_size specified in header.")
+                .with_tag(tag)
+                .with_sizes(section_len as u64, bytes.len() as u64),
+        ));
+    }
+    // Leave the terminating null out of the content slice
+    let content_bytes = &bytes[SECTION_HEADER_SIZE..section_len - 1];
+    // Validate content size
+    if let Some(null_pos) = content_bytes.iter().position(|b| *b == 0u8) {
+        return Err(DATError::Underflow(
+            ErrorContext::from("Section content ended early.")
+                .with_tag(tag)
+                .with_sizes(u64::from(content_size), null_pos as u64 + 1),
+        ));
+    }
+    if bytes[section_len - 1] != 0u8 {
+        return Err(DATError::Overflow(
+            ErrorContext::from("Section data did not end at the expected index.").with_tag(tag),
+        ));
+    }
+    Ok((&bytes[..1], content_size, content_bytes))
+}
+
+/// Converts [`SectionData`] to bytes, checking that it is well-formed.
+fn section_to_bytes(section: SectionData) -> Result<Vec<u8>, DATError> {
+    if section.tag.len() != 1 {
+        return Err(DATError::InvalidInput(
+            "Tags may only be a single character in length.".into(),
+        ));
+    }
+    if section.content.len() + 1 != usize::from(section.content_size) {
+        return Err(DATError::InvalidInput(
+            "Section content_size does not match content length.".into(),
+        ));
+    }
+    if section.content.as_bytes().contains(&0u8) {
+        return Err(DATError::InvalidInput(
+            "Section content may not contain null bytes.".into(),
+        ));
+    }
+    Ok(Vec::<u8>::from(section))
+}
+
+// --- Unit Tests
+
+#[cfg(test)]
+mod tests {
+    extern crate tempfile;
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+    use crate::dat_file::{read_content, DATFile};
+    use std::fs::{copy, OpenOptions};
+    use std::path::PathBuf;
+
+    const TEST_FILE_PATH: &str = "./resources/TEST_SECTION.DAT";
+    const TEST_NON_SECTION_PATH: &str = "./resources/TEST_BLOCK.DAT";
+    const TEST_FILE_SEC1_CONTENTS: (&str, u16, &str) = ("T", 24, "This is a test section.");
+    const TEST_FILE_SEC2_CONTENTS: (&str, u16, &str) = ("A", 22, "Another test section.");
+    const TEST_SEC: [u8; 7] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x43, 0x00];
+    const TEST_SEC_CONTENTS: (&str, u16, &str) = ("A", 4, "ABC");
+    const TEST_SEC_NOT_UTF8: [u8; 7] = [0xc2, 0x04, 0x00, 0x01, 0x01, 0x01, 0x00];
+    const TEST_SEC_NOT_UTF8_CONTENT: [u8; 7] = [0x41, 0x04, 0x00, 0x41, 0xFF, 0x43, 0x00];
+    const TEST_SEC_TOO_SHORT: [u8; 6] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x00];
+    const TEST_SEC_TOO_LONG: [u8; 8] = [0x41, 0x04, 0x00, 0x41, 0x42, 0x43, 0x44, 0x00];
+
+    // --- Module Functions
+
+    #[test]
+    fn test_as_section() -> Result<(), String> {
+        match as_section(&TEST_SEC[..]) {
+            Ok(section) => {
+                assert_eq!(section.tag, TEST_SEC_CONTENTS.0);
+                assert_eq!(section.content_size, TEST_SEC_CONTENTS.1);
+                assert_eq!(section.content, TEST_SEC_CONTENTS.2);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_as_section_vec() -> Result<(), String> {
+        let sec_bytes = match read_content(TEST_FILE_PATH) {
+            Ok(sec_bytes) => sec_bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        match as_section_vec(&sec_bytes) {
+            Ok(section) => {
                 assert_eq!(section[0].tag, TEST_FILE_SEC1_CONTENTS.0);
                 assert_eq!(section[0].content_size, TEST_FILE_SEC1_CONTENTS.1);
                 assert_eq!(section[0].content, TEST_FILE_SEC1_CONTENTS.2);
@@ -706,6 +1650,586 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_as_section_vec_error_truncated() -> Result<(), String> {
+        match as_section_vec(&TEST_SEC[..TEST_SEC.len() - 2]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(ref ctx) => {
+                    assert_eq!(ctx.expected_size(), Some(TEST_SEC.len() as u64));
+                    assert_eq!(ctx.actual_size(), Some(TEST_SEC.len() as u64 - 2));
+                    assert_eq!(ctx.tag(), Some(TEST_SEC_CONTENTS.0));
+                    Ok(())
+                }
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_as_raw_section_vec() -> Result<(), String> {
+        let mut sec_bytes = match read_content(TEST_FILE_PATH) {
+            Ok(sec_bytes) => sec_bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        // Add a random utf8 control char.
+        sec_bytes[SECTION_HEADER_SIZE] = 0xc2;
+        match as_raw_section_vec(&sec_bytes) {
+            Ok(sections) => {
+                assert_eq!(sections.len(), 2);
+                assert_eq!(sections[0].content_size, TEST_FILE_SEC1_CONTENTS.1);
+                assert_eq!(sections[0].content[0], 0xc2);
+                assert_eq!(sections[1].tag, TEST_FILE_SEC2_CONTENTS.0.as_bytes()[0]);
+                assert_eq!(sections[1].content, TEST_FILE_SEC2_CONTENTS.2.as_bytes());
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_read_raw_section() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_FILE_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match read_raw_section(&mut dat_file) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match read_raw_section(&mut dat_file) {
+            Ok(section) => {
+                assert_eq!(section.tag, TEST_FILE_SEC2_CONTENTS.0.as_bytes()[0]);
+                assert_eq!(section.content_size, TEST_FILE_SEC2_CONTENTS.1);
+                assert_eq!(section.content, TEST_FILE_SEC2_CONTENTS.2.as_bytes());
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_read_raw_section_error_type() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_NON_SECTION_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match read_raw_section(&mut dat_file) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_read_raw_section_content() -> Result<(), String> {
+        match read_raw_section_content(TEST_FILE_PATH) {
+            Ok(sections) => {
+                assert_eq!(sections[0].tag, TEST_FILE_SEC1_CONTENTS.0.as_bytes()[0]);
+                assert_eq!(sections[0].content_size, TEST_FILE_SEC1_CONTENTS.1);
+                assert_eq!(sections[0].content, TEST_FILE_SEC1_CONTENTS.2.as_bytes());
+                assert_eq!(sections[1].tag, TEST_FILE_SEC2_CONTENTS.0.as_bytes()[0]);
+                assert_eq!(sections[1].content_size, TEST_FILE_SEC2_CONTENTS.1);
+                assert_eq!(sections[1].content, TEST_FILE_SEC2_CONTENTS.2.as_bytes());
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_read_section_content_with_mode() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        // Replace the first content byte of the first section with a random utf8 control char.
+        match dat_file
+            .seek(SeekFrom::Start(SECTION_HEADER_SIZE as u64))
+            .and_then(|_| dat_file.write_all(&[0xc2]))
+        {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error writing temp file: {}", err)),
+        };
+        match read_section_content_with_mode(&tmp_path, DecodeMode::Strict) {
+            Ok(_) => return Err("No error returned in strict mode.".to_owned()),
+            Err(DATError::BadEncoding(_)) => (),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+        };
+        match read_section_content_with_mode(&tmp_path, DecodeMode::Lossy) {
+            Ok(sections) => {
+                assert_eq!(sections[0].content, "\u{FFFD}his is a test section.");
+                assert_eq!(sections[0].content_size, TEST_FILE_SEC1_CONTENTS.1 + 2);
+                assert_eq!(sections[1].content, TEST_FILE_SEC2_CONTENTS.2);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    // --- SectionIter
+
+    #[test]
+    fn test_sectioniter() -> Result<(), String> {
+        let sec_bytes = match read_content(TEST_FILE_PATH) {
+            Ok(sec_bytes) => sec_bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        let res = match SectionIter::new(&sec_bytes).collect::<Result<Vec<_>, DATError>>() {
+            Ok(res) => res,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].0, 0);
+        assert_eq!(res[0].1.content, TEST_FILE_SEC1_CONTENTS.2);
+        assert_eq!(res[1].0, SECTION_HEADER_SIZE + usize::from(TEST_FILE_SEC1_CONTENTS.1));
+        assert_eq!(res[1].1.content, TEST_FILE_SEC2_CONTENTS.2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sectioniter_error_offset() -> Result<(), String> {
+        let mut sec_bytes = match read_content(TEST_FILE_PATH) {
+            Ok(sec_bytes) => sec_bytes,
+            Err(err) => return Err(format!("Error reading file: {}", err)),
+        };
+        // Corrupt the second section.
+        let sec2_offset = SECTION_HEADER_SIZE + usize::from(TEST_FILE_SEC1_CONTENTS.1);
+        sec_bytes[sec2_offset + SECTION_HEADER_SIZE] = 0xc2;
+        let mut iter = SectionIter::new(&sec_bytes);
+        match iter.next() {
+            Some(Ok(_)) => (),
+            _ => return Err("First section was not read.".to_owned()),
+        };
+        match iter.next() {
+            Some(Err(DATError::BadEncoding(_))) => (),
+            _ => return Err("Incorrect result for corrupt section.".to_owned()),
+        };
+        assert_eq!(iter.offset(), sec2_offset);
+        assert!(iter.next().is_none());
+        // Errors from the slice helpers carry the same location.
+        match as_section_vec(&sec_bytes) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => {
+                assert_eq!(err.context().offset(), Some(sec2_offset as u64));
+                assert_eq!(err.context().section_index(), Some(1));
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_sectioniter_error_zero_size() -> Result<(), String> {
+        match SectionIter::new(&[0x41, 0x00, 0x00]).next() {
+            Some(Err(DATError::Underflow(_))) => Ok(()),
+            _ => Err("Incorrect result for zero-length section.".to_owned()),
+        }
+    }
+
+    // --- SectionReader
+
+    #[test]
+    fn test_sectionreader() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_FILE_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        let reader = match SectionReader::new(&mut dat_file) {
+            Ok(reader) => reader,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        let res = match reader.collect::<Result<Vec<_>, DATError>>() {
+            Ok(res) => res,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].0, 0);
+        assert_eq!(res[0].1.tag, TEST_FILE_SEC1_CONTENTS.0);
+        assert_eq!(res[0].1.content, TEST_FILE_SEC1_CONTENTS.2);
+        assert_eq!(
+            res[1].0,
+            (SECTION_HEADER_SIZE + usize::from(TEST_FILE_SEC1_CONTENTS.1)) as u64
+        );
+        assert_eq!(res[1].1.tag, TEST_FILE_SEC2_CONTENTS.0);
+        assert_eq!(res[1].1.content, TEST_FILE_SEC2_CONTENTS.2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sectionreader_error_type() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_NON_SECTION_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match SectionReader::new(&mut dat_file) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    // --- SectionWriter
+
+    fn copy_test_file(tmp_dir: &TempDir) -> Result<PathBuf, String> {
+        let tmp_path = tmp_dir.path().join("TEST.DAT");
+        match copy(TEST_FILE_PATH, &tmp_path) {
+            Ok(_) => Ok(tmp_path),
+            Err(err) => Err(format!("Could not create temp file for testing: {}", err)),
+        }
+    }
+
+    fn open_rw(path: &PathBuf) -> Result<DATFile, String> {
+        match DATFile::open_options(path, OpenOptions::new().read(true).write(true)) {
+            Ok(dat_file) => Ok(dat_file),
+            Err(err) => Err(format!("Error opening temp file: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_new_error_type() -> Result<(), String> {
+        let mut dat_file = match DATFile::open(TEST_NON_SECTION_PATH) {
+            Ok(dat_file) => dat_file,
+            Err(err) => return Err(format!("Error opening file: {}", err)),
+        };
+        match SectionWriter::new(&mut dat_file) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::IncorrectType(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_append() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.append(SectionData::new("A", "ABC").unwrap()) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        assert_eq!(dat_file.content_size(), 60);
+        match read_content(&tmp_path) {
+            Ok(bytes) => Ok(assert_eq!(&bytes[52..], &TEST_SEC[..])),
+            Err(err) => Err(format!("Error reading file: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_insert() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.insert(1, SectionData::new("A", "ABC").unwrap()) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match read_section_content(&tmp_path) {
+            Ok(sections) => {
+                assert_eq!(sections.len(), 3);
+                assert_eq!(sections[0].content, TEST_FILE_SEC1_CONTENTS.2);
+                assert_eq!(sections[1].content, TEST_SEC_CONTENTS.2);
+                assert_eq!(sections[2].content, TEST_FILE_SEC2_CONTENTS.2);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error reading file: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_insert_error_index() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.insert(3, SectionData::new("A", "ABC").unwrap()) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_remove() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.remove(1) {
+            Ok(section) => assert_eq!(section.content, TEST_FILE_SEC2_CONTENTS.2),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        assert_eq!(dat_file.content_size(), 28);
+        match read_section_content(&tmp_path) {
+            Ok(sections) => {
+                assert_eq!(sections.len(), 1);
+                assert_eq!(sections[0].content, TEST_FILE_SEC1_CONTENTS.2);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error reading file: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_remove_error_index() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.remove(2) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_replace() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        // Shrink, then grow past the original size.
+        match writer.replace(0, SectionData::new("A", "ABC").unwrap()) {
+            Ok(section) => assert_eq!(section.content, TEST_FILE_SEC1_CONTENTS.2),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match writer.replace(0, SectionData::new("T", "A much longer replacement section.").unwrap()) {
+            Ok(section) => assert_eq!(section.content, TEST_SEC_CONTENTS.2),
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match read_section_content(&tmp_path) {
+            Ok(sections) => {
+                assert_eq!(sections.len(), 2);
+                assert_eq!(sections[0].content, "A much longer replacement section.");
+                assert_eq!(sections[1].content, TEST_FILE_SEC2_CONTENTS.2);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error reading file: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_replace_error_overflow() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        let long_content = "X".repeat(128);
+        match writer.replace(0, SectionData::new("T", &long_content).unwrap()) {
+            Ok(_) => return Err("No error returned.".to_owned()),
+            Err(DATError::Overflow(_)) => (),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+        };
+        // File should be untouched.
+        match (read_content(&tmp_path), read_content(TEST_FILE_PATH)) {
+            (Ok(res), Ok(orig)) => Ok(assert_eq!(res, orig)),
+            _ => Err("Error reading file.".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_sectionwriter_error_malformed() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let tmp_path = copy_test_file(&tmp_dir)?;
+        let mut dat_file = open_rw(&tmp_path)?;
+        let mut writer = match SectionWriter::new(&mut dat_file) {
+            Ok(writer) => writer,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        let section = SectionData {
+            content: "ABC",
+            content_size: 12,
+            tag: "A",
+        };
+        match writer.append(section) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::InvalidInput(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    // --- RawSection
+
+    #[test]
+    fn test_rawsection_from_bytes() -> Result<(), String> {
+        match RawSection::try_from(&TEST_SEC_NOT_UTF8[..]) {
+            Ok(section) => {
+                assert_eq!(section.tag, 0xc2);
+                assert_eq!(section.content_size, 4);
+                assert_eq!(section.content, [0x01, 0x01, 0x01]);
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_rawsection_from_bytes_error_overflow() -> Result<(), String> {
+        match RawSection::try_from(&TEST_SEC_TOO_SHORT[..]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Overflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_rawsection_from_bytes_error_underflow() -> Result<(), String> {
+        match RawSection::try_from(&TEST_SEC_TOO_LONG[..]) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(err) => match err {
+                DATError::Underflow(_) => Ok(()),
+                _ => Err(format!("Incorrect error: {}", err)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_rawsection_to_bytes() -> Result<(), String> {
+        match RawSection::try_from(&TEST_SEC_NOT_UTF8[..]) {
+            Ok(section) => Ok(assert_eq!(Vec::<u8>::from(section), TEST_SEC_NOT_UTF8)),
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_rawsection_decode() -> Result<(), String> {
+        let raw_section = match RawSection::try_from(&TEST_SEC[..]) {
+            Ok(section) => section,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        for mode in [DecodeMode::Strict, DecodeMode::Lossy].iter() {
+            match raw_section.decode(*mode) {
+                Ok(section) => {
+                    assert_eq!(section.tag, TEST_SEC_CONTENTS.0);
+                    assert_eq!(section.content_size, TEST_SEC_CONTENTS.1);
+                    assert_eq!(section.content, TEST_SEC_CONTENTS.2);
+                }
+                Err(err) => return Err(format!("Error: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rawsection_decode_lossy() -> Result<(), String> {
+        let raw_section = match RawSection::try_from(&TEST_SEC_NOT_UTF8_CONTENT[..]) {
+            Ok(section) => section,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match raw_section.decode(DecodeMode::Strict) {
+            Ok(_) => return Err("No error returned in strict mode.".to_owned()),
+            Err(DATError::BadEncoding(_)) => (),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+        };
+        match raw_section.decode(DecodeMode::Lossy) {
+            Ok(section) => {
+                assert_eq!(section.tag, "A");
+                assert_eq!(section.content, "A\u{FFFD}C");
+                assert_eq!(section.content_size, 6);
+                // The decoded section can be written back with the original tag.
+                match RawSection::try_from(&section) {
+                    Ok(raw_section) => assert_eq!(raw_section.tag, 0x41),
+                    Err(err) => return Err(format!("Error converting back to raw section: {}", err)),
+                };
+                Ok(())
+            }
+            Err(err) => Err(format!("Error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_rawsection_decode_lossy_error_tag() -> Result<(), String> {
+        let raw_section = match RawSection::try_from(&TEST_SEC_NOT_UTF8[..]) {
+            Ok(section) => section,
+            Err(err) => return Err(format!("Error: {}", err)),
+        };
+        match raw_section.decode(DecodeMode::Lossy) {
+            Ok(_) => Err("No error returned.".to_owned()),
+            Err(DATError::BadEncoding(_)) => Ok(()),
+            Err(err) => Err(format!("Incorrect error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_rawsection_from_section_error_tag() -> Result<(), String> {
+        for tag in ["", "AB", "\u{3000}"].iter() {
+            let section = Section {
+                content: "Test".to_owned(),
+                content_size: 5,
+                tag: tag.to_string(),
+            };
+            match RawSection::try_from(&section) {
+                Ok(_) => return Err(format!("No error returned for tag {:?}.", tag)),
+                Err(DATError::InvalidInput(_)) => (),
+                Err(err) => return Err(format!("Incorrect error: {}", err)),
+            };
+        }
+        Ok(())
+    }
+
     // --- Section
 
     #[test]
@@ -0,0 +1,405 @@
+use std::convert::TryFrom;
+use std::fs::{copy, remove_file, rename, File};
+use std::path::{Path, PathBuf};
+
+use crate::dat_error::{DATError, ErrorContext};
+use crate::dat_file::{write_content, DATFile};
+
+/// A set of content edits to several DAT files that are applied together or not at all.
+///
+/// Edits are staged in memory with [`stage()`](Self::stage). On [`commit()`](Self::commit), every staged edit is first
+/// validated against its file's header. The new content of each file is then written to a temporary copy next
+/// to the original, and the originals are backed up. Finally, each temporary file is renamed over its original.
+/// If any step fails, files that were already replaced are restored from their backups, and all temporary files
+/// are removed, leaving every original as it was.
+///
+/// Temporary and backup files are created in the same directory as the file they belong to, named after it with a
+/// `.tmp` or `.bak` suffix, so they do not have a `.DAT` extension.
+///
+/// # Examples
+/// ```rust
+/// use libxivdat::dat_file::read_content;
+/// use libxivdat::transaction::Transaction;
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let temp_dir = tempdir().unwrap();
+/// # let macro_path = temp_dir.path().join("MACRO.DAT");
+/// # let hotbar_path = temp_dir.path().join("HOTBAR.DAT");
+/// # std::fs::copy("./resources/TEST_XOR.DAT", &macro_path).unwrap();
+/// # std::fs::copy("./resources/TEST.DAT", &hotbar_path).unwrap();
+///
+/// let mut transaction = Transaction::new();
+/// transaction.stage(&macro_path, b"Macro?").stage(&hotbar_path, b"Beep!");
+/// transaction.commit().unwrap();
+///
+/// assert_eq!(read_content(&macro_path).unwrap(), b"Macro?");
+/// assert_eq!(read_content(&hotbar_path).unwrap(), b"Beep!");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Transaction {
+    /// Staged edits in the order they were staged, as pairs of file path and new content.
+    edits: Vec<(PathBuf, Vec<u8>)>,
+}
+
+/// A [`Transaction`] whose new contents have been validated and written to temporary files,
+/// and whose originals have been backed up, but which has not yet replaced any original.
+///
+/// Dropping a [`PreparedTransaction`] without committing it removes its temporary and backup files.
+#[derive(Debug)]
+struct PreparedTransaction {
+    /// Prepared files in staging order.
+    files: Vec<PreparedFile>,
+}
+
+/// The paths used while committing a single file.
+#[derive(Debug)]
+struct PreparedFile {
+    backup_path: PathBuf,
+    path: PathBuf,
+    temp_path: PathBuf,
+}
+
+impl Transaction {
+    /// Validates and applies all staged edits, replacing the content of every staged file.
+    /// Either all files are updated, or none are.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while validating or writing the staged edits. In this case, no original
+    /// file is modified. See [`stage()`](Self::stage) for the validation performed.
+    ///
+    /// If replacing a file fails, the error is returned after all replaced files are restored.
+    /// If restoring also fails, a [`DATError::FileIO`](crate::dat_error::DATError::FileIO) is returned
+    /// for the file that could not be restored, and its original content is kept in its backup file.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::dat_error::DATError;
+    /// use libxivdat::dat_file::read_content;
+    /// use libxivdat::transaction::Transaction;
+    /// # extern crate tempfile;
+    /// # use tempfile::tempdir;
+    /// # let temp_dir = tempdir().unwrap();
+    /// # let macro_path = temp_dir.path().join("MACRO.DAT");
+    /// # let hotbar_path = temp_dir.path().join("HOTBAR.DAT");
+    /// # std::fs::copy("./resources/TEST_XOR.DAT", &macro_path).unwrap();
+    /// # std::fs::copy("./resources/TEST.DAT", &hotbar_path).unwrap();
+    ///
+    /// // The second edit is too long for its file, so neither file is changed.
+    /// let mut transaction = Transaction::new();
+    /// transaction.stage(&macro_path, b"Macro?").stage(&hotbar_path, &[0u8; 4096]);
+    /// match transaction.commit() {
+    ///     Err(DATError::Overflow(_)) => (),
+    ///     _ => panic!("Expected an overflow."),
+    /// }
+    /// assert_eq!(read_content(&macro_path).unwrap(), b"Macro!");
+    /// ```
+    pub fn commit(self) -> Result<(), DATError> {
+        self.prepare()?.commit()
+    }
+
+    /// Returns true if no edits are staged.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the number of staged edits.
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Creates a new, empty [`Transaction`].
+    pub fn new() -> Self {
+        Transaction::default()
+    }
+
+    /// Returns an iterator over the paths of the staged files, in staging order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.edits.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Stages new content for an existing DAT file, replacing any content previously staged for the same path.
+    /// The content is the unmasked content block, as with [`write_content()`](crate::dat_file::write_content).
+    ///
+    /// Nothing is read or written until [`commit()`](Self::commit). At that point, each file must be an existing
+    /// binary DAT file with a valid header, and its new content must fit within the maximum size in its header.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libxivdat::transaction::Transaction;
+    ///
+    /// let mut transaction = Transaction::new();
+    /// transaction.stage("MACRO.DAT", b"Old").stage("MACRO.DAT", b"New");
+    /// assert_eq!(transaction.len(), 1);
+    /// ```
+    pub fn stage<P: AsRef<Path>>(&mut self, path: P, content: &[u8]) -> &mut Self {
+        let path = path.as_ref();
+        match self.edits.iter_mut().find(|(staged_path, _)| staged_path == path) {
+            Some((_, staged_content)) => *staged_content = content.to_vec(),
+            None => self.edits.push((path.to_owned(), content.to_vec())),
+        }
+        self
+    }
+
+    /// Validates all staged edits, then writes each new content to a temporary copy of its file
+    /// and backs up each original.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first validation or I/O error encountered. Any temporary and backup files already
+    /// created are removed.
+    fn prepare(self) -> Result<PreparedTransaction, DATError> {
+        for (path, content) in self.edits.iter() {
+            validate_edit(path, content).map_err(|err| err.with_path(path))?;
+        }
+        let mut prepared = PreparedTransaction { files: Vec::new() };
+        for (path, content) in self.edits.iter() {
+            let file = PreparedFile {
+                backup_path: sibling_path(path, "bak"),
+                path: path.clone(),
+                temp_path: sibling_path(path, "tmp"),
+            };
+            // Push first, so that partially written files are cleaned up on error.
+            prepared.files.push(file);
+            let file = &prepared.files[prepared.files.len() - 1];
+            copy(path, &file.temp_path).map_err(|err| DATError::from(err).with_path(path))?;
+            write_content(&file.temp_path, content)?;
+            File::open(&file.temp_path)
+                .and_then(|temp_file| temp_file.sync_all())
+                .map_err(|err| DATError::from(err).with_path(&file.temp_path))?;
+            copy(path, &file.backup_path).map_err(|err| DATError::from(err).with_path(path))?;
+        }
+        Ok(prepared)
+    }
+}
+
+impl Drop for PreparedTransaction {
+    fn drop(&mut self) {
+        for file in self.files.iter() {
+            // Either file may not exist, depending on how far the transaction got.
+            let _ = remove_file(&file.temp_path);
+            let _ = remove_file(&file.backup_path);
+        }
+    }
+}
+
+impl PreparedTransaction {
+    /// Renames each temporary file over its original. If a rename fails, replaced originals are restored
+    /// from their backups.
+    ///
+    /// # Errors
+    ///
+    /// See [`Transaction::commit()`].
+    fn commit(mut self) -> Result<(), DATError> {
+        for (index, file) in self.files.iter().enumerate() {
+            if let Err(err) = rename(&file.temp_path, &file.path) {
+                let err = DATError::from(err).with_path(&file.path);
+                return match self.restore(index) {
+                    Ok(()) => Err(err),
+                    Err(restore_err) => Err(restore_err),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the originals of the first `count` files from their backups.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::FileIO`] for the first file that could not be restored. Backups of files that
+    /// could not be restored are kept, rather than removed when the transaction is dropped.
+    fn restore(&mut self, count: usize) -> Result<(), DATError> {
+        let mut first_err = None;
+        let mut kept_backups = Vec::new();
+        for (index, file) in self.files.iter().enumerate().take(count) {
+            if let Err(err) = rename(&file.backup_path, &file.path) {
+                kept_backups.push(index);
+                if first_err.is_none() {
+                    let message = format!(
+                        "Could not restore file after a failed transaction. The original is kept at {}.",
+                        file.backup_path.display()
+                    );
+                    let context = ErrorContext::from(message).with_path(&file.path).with_source(err);
+                    first_err = Some(DATError::FileIO(context));
+                }
+            }
+        }
+        for index in kept_backups.into_iter().rev() {
+            self.files.remove(index);
+        }
+        match first_err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the path of a temporary or backup file for a DAT file, in the same directory.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(format!(".{}.{}", std::process::id(), suffix));
+    path.with_file_name(file_name)
+}
+
+/// Checks that a file is a valid DAT file that can hold `content`.
+///
+/// # Errors
+///
+/// Returns any error from [`DATFile::open()`], or a [`DATError::Overflow`] if the content
+/// would exceed the maximum size in the file's header.
+fn validate_edit(path: &Path, content: &[u8]) -> Result<(), DATError> {
+    let dat_file = DATFile::open(path)?;
+    match u32::try_from(content.len() + 1) {
+        Ok(content_size) if content_size <= dat_file.max_size() => Ok(()),
+        _ => Err(DATError::Overflow(
+            ErrorContext::from("Staged content would exceed the maximum size of the file.")
+                .with_sizes(u64::from(dat_file.max_size()), content.len() as u64 + 1),
+        )),
+    }
+}
+
+// --- Unit Tests
+
+#[cfg(test)]
+mod tests {
+    extern crate tempfile;
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+    use crate::dat_file::read_content;
+    use std::fs::copy;
+
+    const TEST_PATH: &str = "./resources/TEST.DAT";
+    const TEST_XOR_PATH: &str = "./resources/TEST_XOR.DAT";
+
+    /// Copies the test files to a temp dir, returning the dir and the paths of the copies.
+    fn setup() -> Result<(TempDir, PathBuf, PathBuf), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let test_path = tmp_dir.path().join("TEST.DAT");
+        let xor_path = tmp_dir.path().join("TEST_XOR.DAT");
+        for (src, dst) in [(TEST_PATH, &test_path), (TEST_XOR_PATH, &xor_path)].iter() {
+            match copy(src, dst) {
+                Ok(_) => (),
+                Err(err) => return Err(format!("Could not create temp file for testing: {}", err)),
+            };
+        }
+        Ok((tmp_dir, test_path, xor_path))
+    }
+
+    /// Returns the number of entries in a directory.
+    fn count_dir_entries(path: &Path) -> Result<usize, String> {
+        match std::fs::read_dir(path) {
+            Ok(entries) => Ok(entries.count()),
+            Err(err) => Err(format!("Error reading temp dir: {}", err)),
+        }
+    }
+
+    // --- Transaction
+
+    #[test]
+    fn test_transaction_stage_replaces() {
+        let mut transaction = Transaction::new();
+        assert!(transaction.is_empty());
+        transaction
+            .stage("A.DAT", b"Old")
+            .stage("B.DAT", b"B")
+            .stage("A.DAT", b"New");
+        assert_eq!(transaction.len(), 2);
+        assert_eq!(
+            transaction.paths().collect::<Vec<&Path>>(),
+            vec![Path::new("A.DAT"), Path::new("B.DAT")]
+        );
+        assert_eq!(transaction.edits[0].1, b"New");
+    }
+
+    #[test]
+    fn test_transaction_commit() -> Result<(), String> {
+        let (tmp_dir, test_path, xor_path) = setup()?;
+        let mut transaction = Transaction::new();
+        transaction.stage(&test_path, b"Beep!").stage(&xor_path, b"Macro?");
+        match transaction.commit() {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error committing transaction: {}", err)),
+        };
+        match read_content(&test_path) {
+            Ok(content) => assert_eq!(content, b"Beep!"),
+            Err(err) => return Err(format!("Error reading file after commit: {}", err)),
+        };
+        match read_content(&xor_path) {
+            Ok(content) => assert_eq!(content, b"Macro?"),
+            Err(err) => return Err(format!("Error reading file after commit: {}", err)),
+        };
+        // No temporary or backup files are left behind.
+        Ok(assert_eq!(count_dir_entries(tmp_dir.path())?, 2))
+    }
+
+    #[test]
+    fn test_transaction_commit_error_missing_file() -> Result<(), String> {
+        let (tmp_dir, test_path, _) = setup()?;
+        let mut transaction = Transaction::new();
+        transaction
+            .stage(&test_path, b"Beep!")
+            .stage(tmp_dir.path().join("MISSING.DAT"), b"Beep!");
+        match transaction.commit() {
+            Err(DATError::FileIO(_)) => (),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+            Ok(_) => return Err("No error returned.".to_owned()),
+        };
+        match read_content(&test_path) {
+            Ok(content) => assert_eq!(content, b"Boop!"),
+            Err(err) => return Err(format!("Error reading file after commit: {}", err)),
+        };
+        Ok(assert_eq!(count_dir_entries(tmp_dir.path())?, 2))
+    }
+
+    #[test]
+    fn test_transaction_commit_error_overflow() -> Result<(), String> {
+        let (tmp_dir, test_path, xor_path) = setup()?;
+        let mut transaction = Transaction::new();
+        transaction.stage(&test_path, b"Beep!").stage(&xor_path, &[1u8; 4096]);
+        match transaction.commit() {
+            Err(DATError::Overflow(context)) => assert_eq!(context.path(), Some(xor_path.as_path())),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+            Ok(_) => return Err("No error returned.".to_owned()),
+        };
+        match read_content(&test_path) {
+            Ok(content) => assert_eq!(content, b"Boop!"),
+            Err(err) => return Err(format!("Error reading file after commit: {}", err)),
+        };
+        Ok(assert_eq!(count_dir_entries(tmp_dir.path())?, 2))
+    }
+
+    #[test]
+    fn test_transaction_commit_rollback() -> Result<(), String> {
+        let (tmp_dir, test_path, xor_path) = setup()?;
+        let mut transaction = Transaction::new();
+        transaction.stage(&test_path, b"Beep!").stage(&xor_path, b"Macro?");
+        let prepared = match transaction.prepare() {
+            Ok(prepared) => prepared,
+            Err(err) => return Err(format!("Error preparing transaction: {}", err)),
+        };
+        // Remove the second temp file so its rename fails after the first file is replaced.
+        match remove_file(&prepared.files[1].temp_path) {
+            Ok(_) => (),
+            Err(err) => return Err(format!("Error removing temp file: {}", err)),
+        };
+        match prepared.commit() {
+            Err(DATError::FileIO(_)) => (),
+            Err(err) => return Err(format!("Incorrect error: {}", err)),
+            Ok(_) => return Err("No error returned.".to_owned()),
+        };
+        match read_content(&test_path) {
+            Ok(content) => assert_eq!(content, b"Boop!"),
+            Err(err) => return Err(format!("Error reading file after rollback: {}", err)),
+        };
+        match read_content(&xor_path) {
+            Ok(content) => assert_eq!(content, b"Macro!"),
+            Err(err) => return Err(format!("Error reading file after rollback: {}", err)),
+        };
+        Ok(assert_eq!(count_dir_entries(tmp_dir.path())?, 2))
+    }
+}
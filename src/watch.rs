@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::dat_error::{DATError, ErrorContext};
+use crate::dat_file::{check_type, get_character_dir_name, read_content};
+use crate::dat_type::DATType;
+use crate::section::{read_section_content, Section, SECTION_BASED_TYPES};
+#[cfg(feature = "macro")]
+use crate::xiv_macro::{read_macro_content, Macro};
+use notify::event::{Event, EventKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// The default time to wait after the last change to a file before reading it. The game client
+/// writes DAT files in several partial writes, so reading too early can observe a file mid-update.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A change to a DAT file observed by a [`DATWatcher`].
+///
+/// Enabled by feature `watch`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DATEvent {
+    /// The name of the character directory containing the file, or `None` for global files.
+    /// See [`get_character_dir_name()`](crate::dat_file::get_character_dir_name).
+    pub character: Option<String>,
+    /// The type of the file, from [`check_type()`](crate::dat_file::check_type).
+    pub file_type: DATType,
+    /// The path of the changed file.
+    pub path: PathBuf,
+    /// The content of the file before this change, if it has been seen before by this watcher.
+    /// This can be compared against `snapshot` to find what changed.
+    pub previous: Option<DATSnapshot>,
+    /// The content of the file after this change.
+    pub snapshot: DATSnapshot,
+}
+
+/// The parsed content of a DAT file at a point in time.
+///
+/// Files are parsed with the highest-level support available for their type. Which variant is
+/// produced for a type depends on the enabled features, so new variants may be added.
+///
+/// Enabled by feature `watch`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DATSnapshot {
+    /// The macros of a macro file. Enabled by feature `macro`.
+    #[cfg(feature = "macro")]
+    Macros(Vec<Macro>),
+    /// The unmasked content bytes of a file without higher-level support.
+    Raw(Vec<u8>),
+    /// The sections of a section-based file.
+    Sections(Vec<Section>),
+}
+
+/// Watches a directory, such as the game's config directory, for changes to DAT files.
+///
+/// Character directories are watched recursively. Changes are debounced per file, so the several
+/// partial writes the game client makes when saving a file produce a single [`DATEvent`]. Files with content
+/// identical to their previous snapshot are not reported. Files that are not binary DAT files, such as the
+/// plaintext `COMMON.DAT`, are ignored.
+///
+/// Events are delivered through the watcher's channel, see [`recv()`](Self::recv). Watching stops when the
+/// watcher is dropped.
+///
+/// Enabled by feature `watch`.
+///
+/// # Examples
+///
+/// ```rust
+/// use libxivdat::dat_file::write_content;
+/// use libxivdat::watch::{DATWatcher, DATSnapshot};
+/// use std::time::Duration;
+/// # extern crate tempfile;
+/// # use tempfile::tempdir;
+/// # let config_dir = tempdir().unwrap();
+/// # let chr_dir = config_dir.path().join("FFXIV_CHR0040000000000000");
+/// # std::fs::create_dir(&chr_dir).unwrap();
+/// # let dat_path = chr_dir.join("ACQ.DAT");
+/// # std::fs::copy("./resources/default_dats/ACQ.DAT", &dat_path).unwrap();
+///
+/// let watcher = DATWatcher::new(config_dir.path(), Duration::from_millis(100)).unwrap();
+///
+/// // Several quick writes are reported as one event.
+/// write_content(&dat_path, b"\x00\x00").unwrap();
+/// write_content(&dat_path, b"").unwrap();
+///
+/// let event = watcher.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+/// assert_eq!(event.character.as_deref(), Some("FFXIV_CHR0040000000000000"));
+/// assert_eq!(event.snapshot, DATSnapshot::Sections(vec![]));
+/// ```
+#[derive(Debug)]
+pub struct DATWatcher {
+    /// Debounced events from the worker thread.
+    events: Receiver<Result<DATEvent, DATError>>,
+    /// The underlying file system watcher. Dropping it stops the worker thread.
+    _watcher: RecommendedWatcher,
+}
+
+impl DATSnapshot {
+    /// Reads and parses the content of a DAT file of a known [`DATType`].
+    ///
+    /// Macro files are read as [`Macros`](DATSnapshot::Macros) when feature `macro` is enabled, other section-based
+    /// files as [`Sections`](DATSnapshot::Sections), and all other files as [`Raw`](DATSnapshot::Raw) content.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned while reading or parsing the file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::dat_type::DATType;
+    /// use libxivdat::watch::DATSnapshot;
+    ///
+    /// let snapshot = DATSnapshot::read("./resources/TEST.DAT", DATType::Unknown).unwrap();
+    /// assert_eq!(snapshot, DATSnapshot::Raw(b"Boop!".to_vec()));
+    /// ```
+    pub fn read<P: AsRef<Path>>(path: P, file_type: DATType) -> Result<Self, DATError> {
+        match file_type {
+            #[cfg(feature = "macro")]
+            DATType::Macro => Ok(DATSnapshot::Macros(read_macro_content(path)?)),
+            DATType::Unknown => Ok(DATSnapshot::Raw(read_content(path)?)),
+            _ if SECTION_BASED_TYPES.contains(&file_type) => Ok(DATSnapshot::Sections(read_section_content(path)?)),
+            _ => Ok(DATSnapshot::Raw(read_content(path)?)),
+        }
+    }
+}
+
+impl DATWatcher {
+    /// Starts watching a directory and its subdirectories for changes to DAT files. Each file is read once
+    /// no changes to it have been observed for the `debounce` duration. [`DEFAULT_DEBOUNCE`] is suitable for
+    /// files written by the game client.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DATError::FileIO`] if the directory cannot be watched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libxivdat::watch::{DATWatcher, DEFAULT_DEBOUNCE};
+    ///
+    /// let watcher = DATWatcher::new("./resources", DEFAULT_DEBOUNCE).unwrap();
+    /// ```
+    pub fn new<P: AsRef<Path>>(dir: P, debounce: Duration) -> Result<Self, DATError> {
+        let (raw_tx, raw_rx) = channel();
+        let (events_tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(raw_tx).map_err(|err| notify_error(err).with_path(&dir))?;
+        watcher
+            .watch(dir.as_ref(), RecursiveMode::Recursive)
+            .map_err(|err| notify_error(err).with_path(&dir))?;
+        thread::spawn(move || debounce_events(raw_rx, events_tx, debounce));
+        Ok(DATWatcher {
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Blocks until the next event is available, returning `None` if watching has stopped.
+    ///
+    /// # Errors
+    ///
+    /// An event is an error if the file system watcher reported an error, or if a changed file could not
+    /// be read or parsed. Errors for files carry the file's path. Watching continues after an error.
+    pub fn recv(&self) -> Option<Result<DATEvent, DATError>> {
+        self.events.recv().ok()
+    }
+
+    /// Blocks until the next event is available or `timeout` has elapsed, returning `None` on timeout
+    /// or if watching has stopped.
+    ///
+    /// # Errors
+    ///
+    /// See [`recv()`](Self::recv).
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<DATEvent, DATError>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Returns the next event if one is available, without blocking.
+    ///
+    /// # Errors
+    ///
+    /// See [`recv()`](Self::recv).
+    pub fn try_recv(&self) -> Option<Result<DATEvent, DATError>> {
+        match self.events.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+}
+
+/// Collects raw file system events, reading each changed DAT file once it has been quiet for `debounce`.
+/// Runs until the file system watcher or the [`DATWatcher`] is dropped.
+fn debounce_events(
+    raw_rx: Receiver<notify::Result<Event>>, events_tx: Sender<Result<DATEvent, DATError>>, debounce: Duration,
+) {
+    let mut pending = HashMap::<PathBuf, Instant>::new();
+    let mut snapshots = HashMap::<PathBuf, DATSnapshot>::new();
+    loop {
+        let next = match pending.values().min() {
+            Some(deadline) => raw_rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => raw_rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match next {
+            Ok(Ok(event)) => {
+                if let EventKind::Create(_) | EventKind::Modify(_) = event.kind {
+                    for path in event.paths.into_iter().filter(|path| is_dat_path(path)) {
+                        pending.insert(path, Instant::now() + debounce);
+                    }
+                }
+            }
+            Ok(Err(err)) => {
+                if events_tx.send(Err(notify_error(err))).is_err() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        let now = Instant::now();
+        let ready: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in ready {
+            pending.remove(&path);
+            let event = match read_event(&path, &mut snapshots) {
+                Ok(Some(event)) => Ok(event),
+                Ok(None) => continue,
+                Err(err) => Err(err.with_path(&path)),
+            };
+            if events_tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Returns true if a path has a `.DAT` extension, ignoring case.
+fn is_dat_path(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext.to_string_lossy().eq_ignore_ascii_case("dat"),
+        None => false,
+    }
+}
+
+/// Converts a [`notify::Error`] to a [`DATError::FileIO`], keeping the underlying I/O error if there is one.
+fn notify_error(err: notify::Error) -> DATError {
+    match err.kind {
+        notify::ErrorKind::Io(io_err) => DATError::from(io_err),
+        _ => DATError::FileIO(ErrorContext::from(err.to_string())),
+    }
+}
+
+/// Reads a changed file, returning `None` if it should not be reported because it no longer exists,
+/// is not a binary DAT file, or is unchanged since its last snapshot.
+fn read_event(path: &Path, snapshots: &mut HashMap<PathBuf, DATSnapshot>) -> Result<Option<DATEvent>, DATError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let file_type = match check_type(path) {
+        Ok(file_type) => file_type,
+        Err(DATError::BadHeader(_)) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let snapshot = DATSnapshot::read(path, file_type)?;
+    let previous = snapshots.insert(path.to_owned(), snapshot.clone());
+    if previous.as_ref() == Some(&snapshot) {
+        return Ok(None);
+    }
+    Ok(Some(DATEvent {
+        character: get_character_dir_name(path),
+        file_type,
+        path: path.to_owned(),
+        previous,
+        snapshot,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempfile;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::dat_file::write_content;
+    use std::fs::{copy, create_dir, write};
+
+    const TEST_PATH: &str = "./resources/TEST.DAT";
+    const TEST_SECTION_PATH: &str = "./resources/TEST_SECTION.DAT";
+    const TEST_PLAINTEXT_PATH: &str = "./resources/default_dats/COMMON.DAT";
+    const TEST_CHR_DIR: &str = "FFXIV_CHR0040000000000000";
+    const TEST_DEBOUNCE: Duration = Duration::from_millis(50);
+    const TEST_TIMEOUT: Duration = Duration::from_secs(5);
+    const TEST_QUIET: Duration = Duration::from_millis(300);
+
+    // --- DATSnapshot
+
+    #[test]
+    fn test_snapshot_read_raw() -> Result<(), String> {
+        match DATSnapshot::read(TEST_PATH, DATType::Unknown) {
+            Ok(snapshot) => Ok(assert_eq!(snapshot, DATSnapshot::Raw(b"Boop!".to_vec()))),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_read_sections() -> Result<(), String> {
+        let expected = match read_section_content(TEST_SECTION_PATH) {
+            Ok(sections) => sections,
+            Err(err) => return Err(format!("Read error: {}", err)),
+        };
+        match DATSnapshot::read(TEST_SECTION_PATH, DATType::KEYBIND) {
+            Ok(snapshot) => Ok(assert_eq!(snapshot, DATSnapshot::Sections(expected))),
+            Err(err) => Err(format!("Read error: {}", err)),
+        }
+    }
+
+    // --- DATWatcher
+
+    #[test]
+    fn test_watcher_debounces_writes() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let chr_dir = tmp_dir.path().join(TEST_CHR_DIR);
+        let dat_path = chr_dir.join("TEST.DAT");
+        if let Err(err) = create_dir(&chr_dir).and_then(|_| copy(TEST_PATH, &dat_path)) {
+            return Err(format!("Could not create temp file for testing: {}", err));
+        }
+        let watcher = match DATWatcher::new(tmp_dir.path(), TEST_DEBOUNCE) {
+            Ok(watcher) => watcher,
+            Err(err) => return Err(format!("Watch error: {}", err)),
+        };
+        for content in [&b"B"[..], b"Bo", b"Boo"].iter() {
+            if let Err(err) = write_content(&dat_path, content) {
+                return Err(format!("Error writing content: {}", err));
+            }
+        }
+        match watcher.recv_timeout(TEST_TIMEOUT) {
+            Some(Ok(event)) => {
+                assert_eq!(event.character.as_deref(), Some(TEST_CHR_DIR));
+                assert_eq!(event.file_type, DATType::Unknown);
+                assert_eq!(event.path, dat_path);
+                assert_eq!(event.previous, None);
+                assert_eq!(event.snapshot, DATSnapshot::Raw(b"Boo".to_vec()));
+            }
+            Some(Err(err)) => return Err(format!("Event error: {}", err)),
+            None => return Err("No event received.".to_owned()),
+        };
+        match watcher.recv_timeout(TEST_QUIET) {
+            Some(event) => Err(format!("Unexpected extra event: {:?}", event)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn test_watcher_skips_unchanged_content() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let dat_path = tmp_dir.path().join("TEST.DAT");
+        if let Err(err) = copy(TEST_PATH, &dat_path) {
+            return Err(format!("Could not create temp file for testing: {}", err));
+        }
+        let watcher = match DATWatcher::new(tmp_dir.path(), TEST_DEBOUNCE) {
+            Ok(watcher) => watcher,
+            Err(err) => return Err(format!("Watch error: {}", err)),
+        };
+        // Each write waits for the previous one to be reported, or to be skipped.
+        let mut events = Vec::new();
+        for content in [&b"Beep!"[..], b"Beep!", b"Boop!"].iter() {
+            if let Err(err) = write_content(&dat_path, content) {
+                return Err(format!("Error writing content: {}", err));
+            }
+            match watcher.recv_timeout(TEST_QUIET) {
+                Some(Ok(event)) => events.push(event),
+                Some(Err(err)) => return Err(format!("Event error: {}", err)),
+                None => (),
+            };
+        }
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].character, None);
+        assert_eq!(events[1].previous, Some(DATSnapshot::Raw(b"Beep!".to_vec())));
+        assert_eq!(events[1].snapshot, DATSnapshot::Raw(b"Boop!".to_vec()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_watcher_ignores_non_dat_files() -> Result<(), String> {
+        let tmp_dir = match tempdir() {
+            Ok(tmp_dir) => tmp_dir,
+            Err(err) => return Err(format!("Error creating temp dir: {}", err)),
+        };
+        let watcher = match DATWatcher::new(tmp_dir.path(), TEST_DEBOUNCE) {
+            Ok(watcher) => watcher,
+            Err(err) => return Err(format!("Watch error: {}", err)),
+        };
+        // Neither a plaintext DAT nor a non-DAT file produce events.
+        let res = copy(TEST_PLAINTEXT_PATH, tmp_dir.path().join("COMMON.DAT"))
+            .and_then(|_| write(tmp_dir.path().join("notes.txt"), b"Not a DAT."))
+            .and_then(|_| copy(TEST_PATH, tmp_dir.path().join("TEST.DAT")));
+        if let Err(err) = res {
+            return Err(format!("Could not create temp files for testing: {}", err));
+        }
+        match watcher.recv_timeout(TEST_TIMEOUT) {
+            Some(Ok(event)) => assert_eq!(event.path, tmp_dir.path().join("TEST.DAT")),
+            Some(Err(err)) => return Err(format!("Event error: {}", err)),
+            None => return Err("No event received.".to_owned()),
+        };
+        match watcher.recv_timeout(TEST_QUIET) {
+            Some(event) => Err(format!("Unexpected extra event: {:?}", event)),
+            None => Ok(()),
+        }
+    }
+}